@@ -1,6 +1,29 @@
-use std::env;
+/// Oldest upstream meshoptimizer release with the API and codec versions of `gen/bindings.rs`.
+#[cfg(feature = "system")]
+const MIN_SYSTEM_VERSION: &str = "0.25";
 
 fn main() {
+    link_meshoptimizer();
+
+    generate_bindings("gen/bindings.rs");
+}
+
+#[cfg(feature = "system")]
+fn link_meshoptimizer() {
+    if let Err(err) = pkg_config::Config::new()
+        .atleast_version(MIN_SYSTEM_VERSION)
+        .probe("meshoptimizer")
+    {
+        panic!(
+            "the `system` feature requires meshoptimizer {} or newer, found through pkg-config \
+             as `meshoptimizer.pc`; disable the feature to build the bundled sources instead\n{}",
+            MIN_SYSTEM_VERSION, err
+        );
+    }
+}
+
+#[cfg(not(feature = "system"))]
+fn link_meshoptimizer() {
     let mut build = cc::Build::new();
 
     build.include("src");
@@ -9,17 +32,19 @@ fn main() {
     let source_files = [
         "vendor/src/allocator.cpp",
         "vendor/src/clusterizer.cpp",
+        "vendor/src/indexanalyzer.cpp",
         "vendor/src/indexcodec.cpp",
         "vendor/src/indexgenerator.cpp",
-        "vendor/src/overdrawanalyzer.cpp",
         "vendor/src/overdrawoptimizer.cpp",
+        "vendor/src/partition.cpp",
+        "vendor/src/quantization.cpp",
+        "vendor/src/rasterizer.cpp",
         "vendor/src/simplifier.cpp",
         "vendor/src/spatialorder.cpp",
         "vendor/src/stripifier.cpp",
-        "vendor/src/vcacheanalyzer.cpp",
         "vendor/src/vcacheoptimizer.cpp",
         "vendor/src/vertexcodec.cpp",
-        "vendor/src/vfetchanalyzer.cpp",
+        "vendor/src/vertexfilter.cpp",
         "vendor/src/vfetchoptimizer.cpp",
     ];
 
@@ -27,7 +52,7 @@ fn main() {
         build.file(source_file);
     }
 
-    let target = env::var("TARGET").unwrap();
+    let target = std::env::var("TARGET").unwrap();
     if target.contains("darwin") {
         build
             .flag("-std=c++11")
@@ -46,15 +71,13 @@ fn main() {
         // The Wasm backend needs a compatible ar
         // which will most likely be available under
         // this name on Windows, via manual LLVM install
-        let host = env::var("HOST").unwrap();
+        let host = std::env::var("HOST").unwrap();
         if host.contains("windows") {
             build.archiver("llvm-ar");
         }
     }
 
     build.compile("meshopt_cpp");
-
-    generate_bindings("gen/bindings.rs");
 }
 
 #[cfg(feature = "generate_bindings")]
@@ -69,6 +92,8 @@ fn generate_bindings(output_file: &str) {
         .trust_clang_mangling(false)
         .layout_tests(false)
         .size_t_is_usize(true)
+        .use_core()
+        .ctypes_prefix("::core::ffi")
         .generate()
         .expect("Unable to generate bindings!");
 
@@ -96,7 +96,7 @@ impl fmt::Debug for Mesh {
 
 impl Mesh {
     fn is_valid(&self) -> bool {
-        if self.indices.len() % 3 != 0 {
+        if !self.indices.len().is_multiple_of(3) {
             return false;
         } else {
             for i in 0..self.indices.len() {
@@ -297,7 +297,7 @@ impl Mesh {
         result
     }
 
-    fn split(&mut self) -> (VertexDataAdapter, &mut [u32]) {
+    fn split(&mut self) -> (VertexDataAdapter<'_>, &mut [u32]) {
         let position_offset = offset_of!(Vertex, p);
         let vertex_stride = std::mem::size_of::<Vertex>();
         let vertex_data = typed_to_bytes(&self.vertices);
@@ -308,7 +308,7 @@ impl Mesh {
         )
     }
 
-    fn vertex_adapter(&self) -> VertexDataAdapter {
+    fn vertex_adapter(&self) -> VertexDataAdapter<'_> {
         let position_offset = offset_of!(Vertex, p);
         let vertex_stride = std::mem::size_of::<Vertex>();
         let vertex_data = typed_to_bytes(&self.vertices);
@@ -404,9 +404,10 @@ fn opt_fetch(mesh: &mut Mesh) {
 }
 
 fn opt_fetch_remap(mesh: &mut Mesh) {
-    let remap = meshopt::optimize_vertex_fetch_remap(&mesh.indices, mesh.vertices.len());
+    let (remap, unique_count) =
+        meshopt::optimize_vertex_fetch_remap(&mesh.indices, mesh.vertices.len());
     mesh.indices = meshopt::remap_index_buffer(Some(&mesh.indices), mesh.indices.len(), &remap);
-    mesh.vertices = meshopt::remap_vertex_buffer(&mesh.vertices, mesh.vertices.len(), &remap);
+    mesh.vertices = meshopt::remap_vertex_buffer(&mesh.vertices, unique_count, &remap);
 }
 
 fn opt_complete(mesh: &mut Mesh) {
@@ -427,14 +428,18 @@ fn opt_complete(mesh: &mut Mesh) {
 }
 
 fn stripify(mesh: &Mesh, use_restart: bool) {
-    let restart_index = if use_restart { 0xffffffff } else { 0x00000000 };
+    let restart = if use_restart {
+        meshopt::StripRestart::Index(0xffffffff)
+    } else {
+        meshopt::StripRestart::None
+    };
 
     let process_start = Instant::now();
-    let strip = meshopt::stripify(&mesh.indices, mesh.vertices.len(), restart_index).unwrap();
+    let strip = meshopt::stripify(&mesh.indices, mesh.vertices.len(), restart).unwrap();
     let process_elapsed = process_start.elapsed();
 
     let mut copy = mesh.clone();
-    copy.indices = meshopt::unstripify(&strip, restart_index).unwrap();
+    copy.indices = meshopt::unstripify(&strip, restart).unwrap();
 
     assert!(copy.is_valid());
     assert_eq!(mesh, &copy);
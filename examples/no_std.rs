@@ -0,0 +1,25 @@
+//! Using meshopt from a `#![no_std]` crate.
+//!
+//! Depend on meshopt with `default-features = false` to drop the `std` requirement;
+//! the wrapper only needs `alloc`. This example is built as a library so it can be
+//! compiled without a `main` or a panic handler.
+
+#![no_std]
+
+extern crate alloc;
+
+use alloc::vec::Vec;
+
+/// Optimizes a mesh for rendering and encodes it into compressed vertex and index streams.
+pub fn optimize_and_encode(
+    positions: &[[f32; 3]],
+    indices: &[u32],
+) -> meshopt::Result<(Vec<u8>, Vec<u8>)> {
+    let mut indices = meshopt::optimize_vertex_cache(indices, positions.len());
+    meshopt::optimize_overdraw_in_place_decoder(&mut indices, positions, 1.05);
+    let vertices = meshopt::optimize_vertex_fetch(&mut indices, positions);
+
+    let encoded_vertices = meshopt::encode_vertex_buffer(&vertices)?;
+    let encoded_indices = meshopt::encode_index_buffer(&indices, vertices.len())?;
+    Ok((encoded_vertices, encoded_indices))
+}
@@ -0,0 +1,77 @@
+//! Compares the number of Rust heap allocations made when optimizing many small meshes
+//! with the allocating functions and with a reused `meshopt::Optimizer`.
+
+extern crate alloc;
+
+// the grid fixtures are shared with the unit tests
+#[allow(dead_code)]
+#[path = "../src/test_utils.rs"]
+mod test_utils;
+
+use meshopt::{Optimizer, VertexDataAdapter};
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+struct CountingAllocator;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.realloc(ptr, layout, new_size)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAllocator = CountingAllocator;
+
+fn grid(size: u32) -> (Vec<[f32; 3]>, Vec<u32>) {
+    (
+        test_utils::grid_positions(size),
+        test_utils::grid_indices(size),
+    )
+}
+
+fn main() {
+    const MESH_COUNT: usize = 1000;
+    let meshes: Vec<_> = (0..MESH_COUNT).map(|i| grid(4 + (i % 8) as u32)).collect();
+
+    let start = ALLOCATIONS.load(Ordering::Relaxed);
+    for (positions, indices) in &meshes {
+        let adapter = VertexDataAdapter::new(meshopt::typed_to_bytes(positions), 12, 0).unwrap();
+        let indices = meshopt::optimize_vertex_cache(indices, positions.len());
+        let mut indices = meshopt::optimize_overdraw(&indices, &adapter, 1.05);
+        let vertices = meshopt::optimize_vertex_fetch(&mut indices, positions);
+        assert_eq!(vertices.len(), positions.len());
+    }
+    let allocating = ALLOCATIONS.load(Ordering::Relaxed) - start;
+
+    let start = ALLOCATIONS.load(Ordering::Relaxed);
+    let mut optimizer = Optimizer::new();
+    let mut cache_optimized = Vec::new();
+    let mut indices = Vec::new();
+    let mut vertices = Vec::new();
+    for (positions, source) in &meshes {
+        let adapter = VertexDataAdapter::new(meshopt::typed_to_bytes(positions), 12, 0).unwrap();
+        optimizer.optimize_vertex_cache_into(source, positions.len(), &mut cache_optimized);
+        optimizer.optimize_overdraw_into(&cache_optimized, &adapter, 1.05, &mut indices);
+        optimizer.optimize_vertex_fetch_into(&mut indices, positions, &mut vertices);
+        assert_eq!(vertices.len(), positions.len());
+    }
+    let reused = ALLOCATIONS.load(Ordering::Relaxed) - start;
+
+    println!(
+        "{} meshes: {} allocations with the allocating functions, {} with a reused Optimizer",
+        MESH_COUNT, allocating, reused
+    );
+}
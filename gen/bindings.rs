@@ -5,7 +5,7 @@
 #[repr(C)]
 #[derive(Debug, Copy, Clone)]
 pub struct meshopt_Stream {
-    pub data: *const ::std::os::raw::c_void,
+    pub data: *const ::core::ffi::c_void,
     pub size: usize,
     pub stride: usize,
 }
@@ -18,10 +18,10 @@ extern "C" {
     #[doc = " destination must contain enough space for the resulting remap table (vertex_count elements)"]
     #[doc = " indices can be NULL if the input is unindexed"]
     pub fn meshopt_generateVertexRemap(
-        destination: *mut ::std::os::raw::c_uint,
-        indices: *const ::std::os::raw::c_uint,
+        destination: *mut ::core::ffi::c_uint,
+        indices: *const ::core::ffi::c_uint,
         index_count: usize,
-        vertices: *const ::std::os::raw::c_void,
+        vertices: *const ::core::ffi::c_void,
         vertex_count: usize,
         vertex_size: usize,
     ) -> usize;
@@ -37,25 +37,52 @@ extern "C" {
     #[doc = " indices can be NULL if the input is unindexed"]
     #[doc = " stream_count must be <= 16"]
     pub fn meshopt_generateVertexRemapMulti(
-        destination: *mut ::std::os::raw::c_uint,
-        indices: *const ::std::os::raw::c_uint,
+        destination: *mut ::core::ffi::c_uint,
+        indices: *const ::core::ffi::c_uint,
         index_count: usize,
         vertex_count: usize,
         streams: *const meshopt_Stream,
         stream_count: usize,
     ) -> usize;
 }
+extern "C" {
+    #[doc = " Generates a vertex remap table from the vertex buffer and an optional index buffer and returns number of unique vertices"]
+    #[doc = " As a result, all vertices that are equivalent map to the same (new) location, with no gaps in the resulting sequence."]
+    #[doc = " Equivalence is checked in two steps: vertex positions are compared for equality, and then the user-specified equality function is called (if provided)."]
+    #[doc = " Resulting remap table maps old vertices to new vertices and can be used in meshopt_remapVertexBuffer/meshopt_remapIndexBuffer."]
+    #[doc = ""]
+    #[doc = " destination must contain enough space for the resulting remap table (vertex_count elements)"]
+    #[doc = " indices can be NULL if the input is unindexed"]
+    #[doc = " vertex_positions should have float3 position in the first 12 bytes of each vertex"]
+    #[doc = " callback can be NULL if no additional equality check is needed; otherwise, it should return 1 if vertices with specified indices are equivalent and 0 if they are not"]
+    pub fn meshopt_generateVertexRemapCustom(
+        destination: *mut ::core::ffi::c_uint,
+        indices: *const ::core::ffi::c_uint,
+        index_count: usize,
+        vertex_positions: *const f32,
+        vertex_count: usize,
+        vertex_positions_stride: usize,
+        callback: ::core::option::Option<
+            unsafe extern "C" fn(
+                arg1: *mut ::core::ffi::c_void,
+                arg2: ::core::ffi::c_uint,
+                arg3: ::core::ffi::c_uint,
+            ) -> ::core::ffi::c_int,
+        >,
+        context: *mut ::core::ffi::c_void,
+    ) -> usize;
+}
 extern "C" {
     #[doc = " Generates vertex buffer from the source vertex buffer and remap table generated by meshopt_generateVertexRemap"]
     #[doc = ""]
     #[doc = " destination must contain enough space for the resulting vertex buffer (unique_vertex_count elements, returned by meshopt_generateVertexRemap)"]
     #[doc = " vertex_count should be the initial vertex count and not the value returned by meshopt_generateVertexRemap"]
     pub fn meshopt_remapVertexBuffer(
-        destination: *mut ::std::os::raw::c_void,
-        vertices: *const ::std::os::raw::c_void,
+        destination: *mut ::core::ffi::c_void,
+        vertices: *const ::core::ffi::c_void,
         vertex_count: usize,
         vertex_size: usize,
-        remap: *const ::std::os::raw::c_uint,
+        remap: *const ::core::ffi::c_uint,
     );
 }
 extern "C" {
@@ -64,10 +91,10 @@ extern "C" {
     #[doc = " destination must contain enough space for the resulting index buffer (index_count elements)"]
     #[doc = " indices can be NULL if the input is unindexed"]
     pub fn meshopt_remapIndexBuffer(
-        destination: *mut ::std::os::raw::c_uint,
-        indices: *const ::std::os::raw::c_uint,
+        destination: *mut ::core::ffi::c_uint,
+        indices: *const ::core::ffi::c_uint,
         index_count: usize,
-        remap: *const ::std::os::raw::c_uint,
+        remap: *const ::core::ffi::c_uint,
     );
 }
 extern "C" {
@@ -78,10 +105,10 @@ extern "C" {
     #[doc = ""]
     #[doc = " destination must contain enough space for the resulting index buffer (index_count elements)"]
     pub fn meshopt_generateShadowIndexBuffer(
-        destination: *mut ::std::os::raw::c_uint,
-        indices: *const ::std::os::raw::c_uint,
+        destination: *mut ::core::ffi::c_uint,
+        indices: *const ::core::ffi::c_uint,
         index_count: usize,
-        vertices: *const ::std::os::raw::c_void,
+        vertices: *const ::core::ffi::c_void,
         vertex_count: usize,
         vertex_size: usize,
         vertex_stride: usize,
@@ -96,14 +123,28 @@ extern "C" {
     #[doc = " destination must contain enough space for the resulting index buffer (index_count elements)"]
     #[doc = " stream_count must be <= 16"]
     pub fn meshopt_generateShadowIndexBufferMulti(
-        destination: *mut ::std::os::raw::c_uint,
-        indices: *const ::std::os::raw::c_uint,
+        destination: *mut ::core::ffi::c_uint,
+        indices: *const ::core::ffi::c_uint,
         index_count: usize,
         vertex_count: usize,
         streams: *const meshopt_Stream,
         stream_count: usize,
     );
 }
+extern "C" {
+    #[doc = " Experimental: Generates a remap table that maps all vertices with the same position to the same (existing) index."]
+    #[doc = " Similarly to meshopt_generateShadowIndexBuffer, this can be helpful to pre-process meshes for position-only rendering."]
+    #[doc = " This can also be used to implement algorithms that require positional-only connectivity, such as hierarchical simplification."]
+    #[doc = ""]
+    #[doc = " destination must contain enough space for the resulting remap table (vertex_count elements)"]
+    #[doc = " vertex_positions should have float3 position in the first 12 bytes of each vertex"]
+    pub fn meshopt_generatePositionRemap(
+        destination: *mut ::core::ffi::c_uint,
+        vertex_positions: *const f32,
+        vertex_count: usize,
+        vertex_positions_stride: usize,
+    );
+}
 extern "C" {
     #[doc = " Generate index buffer that can be used as a geometry shader input with triangle adjacency topology"]
     #[doc = " Each triangle is converted into a 6-vertex patch with the following layout:"]
@@ -115,8 +156,8 @@ extern "C" {
     #[doc = " destination must contain enough space for the resulting index buffer (index_count*2 elements)"]
     #[doc = " vertex_positions should have float3 position in the first 12 bytes of each vertex"]
     pub fn meshopt_generateAdjacencyIndexBuffer(
-        destination: *mut ::std::os::raw::c_uint,
-        indices: *const ::std::os::raw::c_uint,
+        destination: *mut ::core::ffi::c_uint,
+        indices: *const ::core::ffi::c_uint,
         index_count: usize,
         vertex_positions: *const f32,
         vertex_count: usize,
@@ -137,14 +178,32 @@ extern "C" {
     #[doc = " destination must contain enough space for the resulting index buffer (index_count*4 elements)"]
     #[doc = " vertex_positions should have float3 position in the first 12 bytes of each vertex"]
     pub fn meshopt_generateTessellationIndexBuffer(
-        destination: *mut ::std::os::raw::c_uint,
-        indices: *const ::std::os::raw::c_uint,
+        destination: *mut ::core::ffi::c_uint,
+        indices: *const ::core::ffi::c_uint,
         index_count: usize,
         vertex_positions: *const f32,
         vertex_count: usize,
         vertex_positions_stride: usize,
     );
 }
+extern "C" {
+    #[doc = " Generate index buffer that can be used for visibility buffer rendering and returns the size of the reorder table"]
+    #[doc = " Each triangle's provoking vertex index is equal to primitive id; this allows passing it to the fragment shader using flat/nointerpolation attribute."]
+    #[doc = " This is important for performance on hardware where primitive id can't be accessed efficiently in fragment shader."]
+    #[doc = " The reorder table stores the original vertex id for each vertex in the new index buffer, and should be used in the vertex shader to load vertex data."]
+    #[doc = " The provoking vertex is assumed to be the first vertex in the triangle; if this is not the case (OpenGL), rotate each triangle (abc -> bca) before rendering."]
+    #[doc = " For maximum efficiency the input index buffer should be optimized for vertex cache first."]
+    #[doc = ""]
+    #[doc = " destination must contain enough space for the resulting index buffer (index_count elements)"]
+    #[doc = " reorder must contain enough space for the worst case reorder table (vertex_count + index_count/3 elements)"]
+    pub fn meshopt_generateProvokingIndexBuffer(
+        destination: *mut ::core::ffi::c_uint,
+        reorder: *mut ::core::ffi::c_uint,
+        indices: *const ::core::ffi::c_uint,
+        index_count: usize,
+        vertex_count: usize,
+    ) -> usize;
+}
 extern "C" {
     #[doc = " Vertex transform cache optimizer"]
     #[doc = " Reorders indices to reduce the number of GPU vertex shader invocations"]
@@ -152,8 +211,8 @@ extern "C" {
     #[doc = ""]
     #[doc = " destination must contain enough space for the resulting index buffer (index_count elements)"]
     pub fn meshopt_optimizeVertexCache(
-        destination: *mut ::std::os::raw::c_uint,
-        indices: *const ::std::os::raw::c_uint,
+        destination: *mut ::core::ffi::c_uint,
+        indices: *const ::core::ffi::c_uint,
         index_count: usize,
         vertex_count: usize,
     );
@@ -165,8 +224,8 @@ extern "C" {
     #[doc = ""]
     #[doc = " destination must contain enough space for the resulting index buffer (index_count elements)"]
     pub fn meshopt_optimizeVertexCacheStrip(
-        destination: *mut ::std::os::raw::c_uint,
-        indices: *const ::std::os::raw::c_uint,
+        destination: *mut ::core::ffi::c_uint,
+        indices: *const ::core::ffi::c_uint,
         index_count: usize,
         vertex_count: usize,
     );
@@ -180,11 +239,11 @@ extern "C" {
     #[doc = " destination must contain enough space for the resulting index buffer (index_count elements)"]
     #[doc = " cache_size should be less than the actual GPU cache size to avoid cache thrashing"]
     pub fn meshopt_optimizeVertexCacheFifo(
-        destination: *mut ::std::os::raw::c_uint,
-        indices: *const ::std::os::raw::c_uint,
+        destination: *mut ::core::ffi::c_uint,
+        indices: *const ::core::ffi::c_uint,
         index_count: usize,
         vertex_count: usize,
-        cache_size: ::std::os::raw::c_uint,
+        cache_size: ::core::ffi::c_uint,
     );
 }
 extern "C" {
@@ -197,8 +256,8 @@ extern "C" {
     #[doc = " vertex_positions should have float3 position in the first 12 bytes of each vertex"]
     #[doc = " threshold indicates how much the overdraw optimizer can degrade vertex cache efficiency (1.05 = up to 5%) to reduce overdraw more efficiently"]
     pub fn meshopt_optimizeOverdraw(
-        destination: *mut ::std::os::raw::c_uint,
-        indices: *const ::std::os::raw::c_uint,
+        destination: *mut ::core::ffi::c_uint,
+        indices: *const ::core::ffi::c_uint,
         index_count: usize,
         vertex_positions: *const f32,
         vertex_count: usize,
@@ -215,10 +274,10 @@ extern "C" {
     #[doc = " destination must contain enough space for the resulting vertex buffer (vertex_count elements)"]
     #[doc = " indices is used both as an input and as an output index buffer"]
     pub fn meshopt_optimizeVertexFetch(
-        destination: *mut ::std::os::raw::c_void,
-        indices: *mut ::std::os::raw::c_uint,
+        destination: *mut ::core::ffi::c_void,
+        indices: *mut ::core::ffi::c_uint,
         index_count: usize,
-        vertices: *const ::std::os::raw::c_void,
+        vertices: *const ::core::ffi::c_void,
         vertex_count: usize,
         vertex_size: usize,
     ) -> usize;
@@ -231,8 +290,8 @@ extern "C" {
     #[doc = ""]
     #[doc = " destination must contain enough space for the resulting remap table (vertex_count elements)"]
     pub fn meshopt_optimizeVertexFetchRemap(
-        destination: *mut ::std::os::raw::c_uint,
-        indices: *const ::std::os::raw::c_uint,
+        destination: *mut ::core::ffi::c_uint,
+        indices: *const ::core::ffi::c_uint,
         index_count: usize,
         vertex_count: usize,
     ) -> usize;
@@ -246,9 +305,9 @@ extern "C" {
     #[doc = ""]
     #[doc = " buffer must contain enough space for the encoded index buffer (use meshopt_encodeIndexBufferBound to compute worst case size)"]
     pub fn meshopt_encodeIndexBuffer(
-        buffer: *mut ::std::os::raw::c_uchar,
+        buffer: *mut ::core::ffi::c_uchar,
         buffer_size: usize,
-        indices: *const ::std::os::raw::c_uint,
+        indices: *const ::core::ffi::c_uint,
         index_count: usize,
     ) -> usize;
 }
@@ -258,7 +317,7 @@ extern "C" {
 extern "C" {
     #[doc = " Set index encoder format version"]
     #[doc = " version must specify the data format version to encode; valid values are 0 (decodable by all library versions) and 1 (decodable by 0.14+)"]
-    pub fn meshopt_encodeIndexVersion(version: ::std::os::raw::c_int);
+    pub fn meshopt_encodeIndexVersion(version: ::core::ffi::c_int);
 }
 extern "C" {
     #[doc = " Index buffer decoder"]
@@ -268,12 +327,21 @@ extern "C" {
     #[doc = ""]
     #[doc = " destination must contain enough space for the resulting index buffer (index_count elements)"]
     pub fn meshopt_decodeIndexBuffer(
-        destination: *mut ::std::os::raw::c_void,
+        destination: *mut ::core::ffi::c_void,
         index_count: usize,
         index_size: usize,
-        buffer: *const ::std::os::raw::c_uchar,
+        buffer: *const ::core::ffi::c_uchar,
         buffer_size: usize,
-    ) -> ::std::os::raw::c_int;
+    ) -> ::core::ffi::c_int;
+}
+extern "C" {
+    #[doc = " Get encoded index format version"]
+    #[doc = " Returns format version of the encoded index buffer/sequence, or -1 if the buffer header is invalid"]
+    #[doc = " Note that a non-negative value doesn't guarantee that the buffer will be decoded correctly if the input is malformed."]
+    pub fn meshopt_decodeIndexVersion(
+        buffer: *const ::core::ffi::c_uchar,
+        buffer_size: usize,
+    ) -> ::core::ffi::c_int;
 }
 extern "C" {
     #[doc = " Index sequence encoder"]
@@ -283,9 +351,9 @@ extern "C" {
     #[doc = ""]
     #[doc = " buffer must contain enough space for the encoded index sequence (use meshopt_encodeIndexSequenceBound to compute worst case size)"]
     pub fn meshopt_encodeIndexSequence(
-        buffer: *mut ::std::os::raw::c_uchar,
+        buffer: *mut ::core::ffi::c_uchar,
         buffer_size: usize,
-        indices: *const ::std::os::raw::c_uint,
+        indices: *const ::core::ffi::c_uint,
         index_count: usize,
     ) -> usize;
 }
@@ -300,12 +368,12 @@ extern "C" {
     #[doc = ""]
     #[doc = " destination must contain enough space for the resulting index sequence (index_count elements)"]
     pub fn meshopt_decodeIndexSequence(
-        destination: *mut ::std::os::raw::c_void,
+        destination: *mut ::core::ffi::c_void,
         index_count: usize,
         index_size: usize,
-        buffer: *const ::std::os::raw::c_uchar,
+        buffer: *const ::core::ffi::c_uchar,
         buffer_size: usize,
-    ) -> ::std::os::raw::c_int;
+    ) -> ::core::ffi::c_int;
 }
 extern "C" {
     #[doc = " Vertex buffer encoder"]
@@ -313,12 +381,13 @@ extern "C" {
     #[doc = " Returns encoded data size on success, 0 on error; the only error condition is if buffer doesn't have enough space"]
     #[doc = " This function works for a single vertex stream; for multiple vertex streams, call meshopt_encodeVertexBuffer for each stream."]
     #[doc = " Note that all vertex_size bytes of each vertex are encoded verbatim, including padding which should be zero-initialized."]
+    #[doc = " For maximum efficiency the vertex buffer being encoded has to be quantized and optimized for locality of reference (cache/fetch) first."]
     #[doc = ""]
     #[doc = " buffer must contain enough space for the encoded vertex buffer (use meshopt_encodeVertexBufferBound to compute worst case size)"]
     pub fn meshopt_encodeVertexBuffer(
-        buffer: *mut ::std::os::raw::c_uchar,
+        buffer: *mut ::core::ffi::c_uchar,
         buffer_size: usize,
-        vertices: *const ::std::os::raw::c_void,
+        vertices: *const ::core::ffi::c_void,
         vertex_count: usize,
         vertex_size: usize,
     ) -> usize;
@@ -326,10 +395,28 @@ extern "C" {
 extern "C" {
     pub fn meshopt_encodeVertexBufferBound(vertex_count: usize, vertex_size: usize) -> usize;
 }
+extern "C" {
+    #[doc = " Vertex buffer encoder"]
+    #[doc = " Encodes vertex data just like meshopt_encodeVertexBuffer, but allows to override compression level."]
+    #[doc = " For compression level to take effect, the vertex encoding version must be set to 1."]
+    #[doc = " The default compression level implied by meshopt_encodeVertexBuffer is 2."]
+    #[doc = ""]
+    #[doc = " level should be in the range [0, 3] with 0 being the fastest and 3 being the slowest and producing the best compression ratio."]
+    #[doc = " version should be -1 to use the default version (specified via meshopt_encodeVertexVersion), or 0/1 to override the version; per above, level won't take effect if version is 0."]
+    pub fn meshopt_encodeVertexBufferLevel(
+        buffer: *mut ::core::ffi::c_uchar,
+        buffer_size: usize,
+        vertices: *const ::core::ffi::c_void,
+        vertex_count: usize,
+        vertex_size: usize,
+        level: ::core::ffi::c_int,
+        version: ::core::ffi::c_int,
+    ) -> usize;
+}
 extern "C" {
     #[doc = " Set vertex encoder format version"]
-    #[doc = " version must specify the data format version to encode; valid values are 0 (decodable by all library versions)"]
-    pub fn meshopt_encodeVertexVersion(version: ::std::os::raw::c_int);
+    #[doc = " version must specify the data format version to encode; valid values are 0 (decodable by all library versions) and 1 (decodable by 0.23+)"]
+    pub fn meshopt_encodeVertexVersion(version: ::core::ffi::c_int);
 }
 extern "C" {
     #[doc = " Vertex buffer decoder"]
@@ -339,12 +426,21 @@ extern "C" {
     #[doc = ""]
     #[doc = " destination must contain enough space for the resulting vertex buffer (vertex_count * vertex_size bytes)"]
     pub fn meshopt_decodeVertexBuffer(
-        destination: *mut ::std::os::raw::c_void,
+        destination: *mut ::core::ffi::c_void,
         vertex_count: usize,
         vertex_size: usize,
-        buffer: *const ::std::os::raw::c_uchar,
+        buffer: *const ::core::ffi::c_uchar,
         buffer_size: usize,
-    ) -> ::std::os::raw::c_int;
+    ) -> ::core::ffi::c_int;
+}
+extern "C" {
+    #[doc = " Get encoded vertex format version"]
+    #[doc = " Returns format version of the encoded vertex buffer, or -1 if the buffer header is invalid"]
+    #[doc = " Note that a non-negative value doesn't guarantee that the buffer will be decoded correctly if the input is malformed."]
+    pub fn meshopt_decodeVertexVersion(
+        buffer: *const ::core::ffi::c_uchar,
+        buffer_size: usize,
+    ) -> ::core::ffi::c_int;
 }
 extern "C" {
     #[doc = " Vertex buffer filters"]
@@ -358,22 +454,32 @@ extern "C" {
     #[doc = ""]
     #[doc = " meshopt_decodeFilterExp decodes exponential encoding of floating-point data with 8-bit exponent and 24-bit integer mantissa as 2^E*M."]
     #[doc = " Each 32-bit component is decoded in isolation; stride must be divisible by 4."]
+    #[doc = ""]
+    #[doc = " Experimental: meshopt_decodeFilterColor decodes YCoCg (+A) color encoding where RGB is converted to YCoCg space with variable bit quantization."]
+    #[doc = " Each component is stored as an 8-bit or 16-bit normalized integer; stride must be equal to 4 or 8."]
     pub fn meshopt_decodeFilterOct(
-        buffer: *mut ::std::os::raw::c_void,
+        buffer: *mut ::core::ffi::c_void,
         count: usize,
         stride: usize,
     );
 }
 extern "C" {
     pub fn meshopt_decodeFilterQuat(
-        buffer: *mut ::std::os::raw::c_void,
+        buffer: *mut ::core::ffi::c_void,
         count: usize,
         stride: usize,
     );
 }
 extern "C" {
     pub fn meshopt_decodeFilterExp(
-        buffer: *mut ::std::os::raw::c_void,
+        buffer: *mut ::core::ffi::c_void,
+        count: usize,
+        stride: usize,
+    );
+}
+extern "C" {
+    pub fn meshopt_decodeFilterColor(
+        buffer: *mut ::core::ffi::c_void,
         count: usize,
         stride: usize,
     );
@@ -381,6 +487,7 @@ extern "C" {
 pub const meshopt_EncodeExpMode_meshopt_EncodeExpSeparate: meshopt_EncodeExpMode = 0;
 pub const meshopt_EncodeExpMode_meshopt_EncodeExpSharedVector: meshopt_EncodeExpMode = 1;
 pub const meshopt_EncodeExpMode_meshopt_EncodeExpSharedComponent: meshopt_EncodeExpMode = 2;
+pub const meshopt_EncodeExpMode_meshopt_EncodeExpClamped: meshopt_EncodeExpMode = 3;
 #[doc = " Vertex buffer filter encoders"]
 #[doc = " These functions can be used to encode data in a format that meshopt_decodeFilter can decode"]
 #[doc = ""]
@@ -395,43 +502,57 @@ pub const meshopt_EncodeExpMode_meshopt_EncodeExpSharedComponent: meshopt_Encode
 #[doc = " meshopt_encodeFilterExp encodes arbitrary (finite) floating-point data with 8-bit exponent and K-bit integer mantissa (1 <= K <= 24)."]
 #[doc = " Exponent can be shared between all components of a given vector as defined by stride or all values of a given component; stride must be divisible by 4."]
 #[doc = " Input data must contain stride/4 floats for every vector (count*stride/4 total)."]
-pub type meshopt_EncodeExpMode = ::std::os::raw::c_uint;
+#[doc = ""]
+#[doc = " Experimental: meshopt_encodeFilterColor encodes RGBA color data by converting RGB to YCoCg color space with variable bit quantization."]
+#[doc = " Each component is stored as an 8-bit or 16-bit integer; stride must be equal to 4 or 8."]
+#[doc = " Input data must contain 4 floats for every color (count*4 total)."]
+pub type meshopt_EncodeExpMode = ::core::ffi::c_uint;
 extern "C" {
     pub fn meshopt_encodeFilterOct(
-        destination: *mut ::std::os::raw::c_void,
+        destination: *mut ::core::ffi::c_void,
         count: usize,
         stride: usize,
-        bits: ::std::os::raw::c_int,
+        bits: ::core::ffi::c_int,
         data: *const f32,
     );
 }
 extern "C" {
     pub fn meshopt_encodeFilterQuat(
-        destination: *mut ::std::os::raw::c_void,
+        destination: *mut ::core::ffi::c_void,
         count: usize,
         stride: usize,
-        bits: ::std::os::raw::c_int,
+        bits: ::core::ffi::c_int,
         data: *const f32,
     );
 }
 extern "C" {
     pub fn meshopt_encodeFilterExp(
-        destination: *mut ::std::os::raw::c_void,
+        destination: *mut ::core::ffi::c_void,
         count: usize,
         stride: usize,
-        bits: ::std::os::raw::c_int,
+        bits: ::core::ffi::c_int,
         data: *const f32,
         mode: meshopt_EncodeExpMode,
     );
 }
+extern "C" {
+    pub fn meshopt_encodeFilterColor(
+        destination: *mut ::core::ffi::c_void,
+        count: usize,
+        stride: usize,
+        bits: ::core::ffi::c_int,
+        data: *const f32,
+    );
+}
 extern "C" {
     #[doc = " Mesh simplifier"]
     #[doc = " Reduces the number of triangles in the mesh, attempting to preserve mesh appearance as much as possible"]
     #[doc = " The algorithm tries to preserve mesh topology and can stop short of the target goal based on topology constraints or target error."]
-    #[doc = " If not all attributes from the input mesh are required, it's recommended to reindex the mesh using meshopt_generateShadowIndexBuffer prior to simplification."]
+    #[doc = " If not all attributes from the input mesh are needed, it's recommended to reindex the mesh without them prior to simplification."]
     #[doc = " Returns the number of indices after simplification, with destination containing new index data"]
+    #[doc = ""]
     #[doc = " The resulting index buffer references vertices from the original vertex buffer."]
-    #[doc = " If the original vertex data isn't required, creating a compact vertex buffer using meshopt_optimizeVertexFetch is recommended."]
+    #[doc = " If the original vertex data isn't needed, creating a compact vertex buffer using meshopt_optimizeVertexFetch is recommended."]
     #[doc = ""]
     #[doc = " destination must contain enough space for the target index buffer, worst case is index_count elements (*not* target_index_count)!"]
     #[doc = " vertex_positions should have float3 position in the first 12 bytes of each vertex"]
@@ -439,31 +560,42 @@ extern "C" {
     #[doc = " options must be a bitmask composed of meshopt_SimplifyX options; 0 is a safe default"]
     #[doc = " result_error can be NULL; when it's not NULL, it will contain the resulting (relative) error after simplification"]
     pub fn meshopt_simplify(
-        destination: *mut ::std::os::raw::c_uint,
-        indices: *const ::std::os::raw::c_uint,
+        destination: *mut ::core::ffi::c_uint,
+        indices: *const ::core::ffi::c_uint,
         index_count: usize,
         vertex_positions: *const f32,
         vertex_count: usize,
         vertex_positions_stride: usize,
         target_index_count: usize,
         target_error: f32,
-        options: ::std::os::raw::c_uint,
+        options: ::core::ffi::c_uint,
         result_error: *mut f32,
     ) -> usize;
 }
 extern "C" {
-    #[doc = " Experimental: Mesh simplifier with attribute metric"]
-    #[doc = " The algorithm ehnahces meshopt_simplify by incorporating attribute values into the error metric used to prioritize simplification order; see meshopt_simplify documentation for details."]
-    #[doc = " Note that the number of attributes affects memory requirements and running time; this algorithm requires ~1.5x more memory and time compared to meshopt_simplify when using 4 scalar attributes."]
+    #[doc = " Mesh simplifier with attribute metric"]
+    #[doc = " Reduces the number of triangles in the mesh, attempting to preserve mesh appearance as much as possible."]
+    #[doc = " Similar to meshopt_simplify, but incorporates attribute values into the error metric used to prioritize simplification order."]
+    #[doc = " The algorithm tries to preserve mesh topology and can stop short of the target goal based on topology constraints or target error."]
+    #[doc = " If not all attributes from the input mesh are needed, it's recommended to reindex the mesh without them prior to simplification."]
+    #[doc = " Returns the number of indices after simplification, with destination containing new index data"]
+    #[doc = ""]
+    #[doc = " The resulting index buffer references vertices from the original vertex buffer."]
+    #[doc = " If the original vertex data isn't needed, creating a compact vertex buffer using meshopt_optimizeVertexFetch is recommended."]
+    #[doc = " Note that the number of attributes with non-zero weights affects memory requirements and running time."]
     #[doc = ""]
+    #[doc = " destination must contain enough space for the target index buffer, worst case is index_count elements (*not* target_index_count)!"]
+    #[doc = " vertex_positions should have float3 position in the first 12 bytes of each vertex"]
     #[doc = " vertex_attributes should have attribute_count floats for each vertex"]
-    #[doc = " attribute_weights should have attribute_count floats in total; the weights determine relative priority of attributes between each other and wrt position. The recommended weight range is [1e-3..1e-1], assuming attribute data is in [0..1] range."]
-    #[doc = " attribute_count must be <= 16"]
+    #[doc = " attribute_weights should have attribute_count floats in total; the weights determine relative priority of attributes between each other and wrt position"]
+    #[doc = " attribute_count must be <= 32"]
     #[doc = " vertex_lock can be NULL; when it's not NULL, it should have a value for each vertex; 1 denotes vertices that can't be moved"]
-    #[doc = " TODO target_error/result_error currently use combined distance+attribute error; this may change in the future"]
+    #[doc = " target_error represents the error relative to mesh extents that can be tolerated, e.g. 0.01 = 1% deformation; value range [0..1]"]
+    #[doc = " options must be a bitmask composed of meshopt_SimplifyX options; 0 is a safe default"]
+    #[doc = " result_error can be NULL; when it's not NULL, it will contain the resulting (relative) error after simplification"]
     pub fn meshopt_simplifyWithAttributes(
-        destination: *mut ::std::os::raw::c_uint,
-        indices: *const ::std::os::raw::c_uint,
+        destination: *mut ::core::ffi::c_uint,
+        indices: *const ::core::ffi::c_uint,
         index_count: usize,
         vertex_positions: *const f32,
         vertex_count: usize,
@@ -472,10 +604,47 @@ extern "C" {
         vertex_attributes_stride: usize,
         attribute_weights: *const f32,
         attribute_count: usize,
-        vertex_lock: *const ::std::os::raw::c_uchar,
+        vertex_lock: *const ::core::ffi::c_uchar,
+        target_index_count: usize,
+        target_error: f32,
+        options: ::core::ffi::c_uint,
+        result_error: *mut f32,
+    ) -> usize;
+}
+extern "C" {
+    #[doc = " Experimental: Mesh simplifier with position/attribute update"]
+    #[doc = " Reduces the number of triangles in the mesh, attempting to preserve mesh appearance as much as possible."]
+    #[doc = " Similar to meshopt_simplifyWithAttributes, but destructively updates positions and attribute values for optimal appearance."]
+    #[doc = " The algorithm tries to preserve mesh topology and can stop short of the target goal based on topology constraints or target error."]
+    #[doc = " If not all attributes from the input mesh are needed, it's recommended to reindex the mesh without them prior to simplification."]
+    #[doc = " Returns the number of indices after simplification, indices are destructively updated with new index data"]
+    #[doc = ""]
+    #[doc = " The updated index buffer references vertices from the original vertex buffer, however the vertex positions and attributes are updated in-place."]
+    #[doc = " Creating a compact vertex buffer using meshopt_optimizeVertexFetch is recommended; if the original vertex data is needed, it should be copied before simplification."]
+    #[doc = " Note that the number of attributes with non-zero weights affects memory requirements and running time. Attributes with zero weights are not updated."]
+    #[doc = ""]
+    #[doc = " vertex_positions should have float3 position in the first 12 bytes of each vertex"]
+    #[doc = " vertex_attributes should have attribute_count floats for each vertex"]
+    #[doc = " attribute_weights should have attribute_count floats in total; the weights determine relative priority of attributes between each other and wrt position"]
+    #[doc = " attribute_count must be <= 32"]
+    #[doc = " vertex_lock can be NULL; when it's not NULL, it should have a value for each vertex; 1 denotes vertices that can't be moved"]
+    #[doc = " target_error represents the error relative to mesh extents that can be tolerated, e.g. 0.01 = 1% deformation; value range [0..1]"]
+    #[doc = " options must be a bitmask composed of meshopt_SimplifyX options; 0 is a safe default"]
+    #[doc = " result_error can be NULL; when it's not NULL, it will contain the resulting (relative) error after simplification"]
+    pub fn meshopt_simplifyWithUpdate(
+        indices: *mut ::core::ffi::c_uint,
+        index_count: usize,
+        vertex_positions: *mut f32,
+        vertex_count: usize,
+        vertex_positions_stride: usize,
+        vertex_attributes: *mut f32,
+        vertex_attributes_stride: usize,
+        attribute_weights: *const f32,
+        attribute_count: usize,
+        vertex_lock: *const ::core::ffi::c_uchar,
         target_index_count: usize,
         target_error: f32,
-        options: ::std::os::raw::c_uint,
+        options: ::core::ffi::c_uint,
         result_error: *mut f32,
     ) -> usize;
 }
@@ -485,36 +654,59 @@ extern "C" {
     #[doc = " The algorithm doesn't preserve mesh topology but can stop short of the target goal based on target error."]
     #[doc = " Returns the number of indices after simplification, with destination containing new index data"]
     #[doc = " The resulting index buffer references vertices from the original vertex buffer."]
-    #[doc = " If the original vertex data isn't required, creating a compact vertex buffer using meshopt_optimizeVertexFetch is recommended."]
+    #[doc = " If the original vertex data isn't needed, creating a compact vertex buffer using meshopt_optimizeVertexFetch is recommended."]
     #[doc = ""]
     #[doc = " destination must contain enough space for the target index buffer, worst case is index_count elements (*not* target_index_count)!"]
     #[doc = " vertex_positions should have float3 position in the first 12 bytes of each vertex"]
+    #[doc = " vertex_lock can be NULL; when it's not NULL, it should have a value for each vertex; vertices that can't be moved should set 1 consistently for all indices with the same position"]
     #[doc = " target_error represents the error relative to mesh extents that can be tolerated, e.g. 0.01 = 1% deformation; value range [0..1]"]
     #[doc = " result_error can be NULL; when it's not NULL, it will contain the resulting (relative) error after simplification"]
     pub fn meshopt_simplifySloppy(
-        destination: *mut ::std::os::raw::c_uint,
-        indices: *const ::std::os::raw::c_uint,
+        destination: *mut ::core::ffi::c_uint,
+        indices: *const ::core::ffi::c_uint,
         index_count: usize,
         vertex_positions: *const f32,
         vertex_count: usize,
         vertex_positions_stride: usize,
+        vertex_lock: *const ::core::ffi::c_uchar,
         target_index_count: usize,
         target_error: f32,
         result_error: *mut f32,
     ) -> usize;
 }
 extern "C" {
-    #[doc = " Experimental: Point cloud simplifier"]
+    #[doc = " Mesh simplifier (pruner)"]
+    #[doc = " Reduces the number of triangles in the mesh by removing small isolated parts of the mesh"]
+    #[doc = " Returns the number of indices after simplification, with destination containing new index data"]
+    #[doc = " The resulting index buffer references vertices from the original vertex buffer."]
+    #[doc = " If the original vertex data isn't needed, creating a compact vertex buffer using meshopt_optimizeVertexFetch is recommended."]
+    #[doc = ""]
+    #[doc = " destination must contain enough space for the target index buffer, worst case is index_count elements"]
+    #[doc = " vertex_positions should have float3 position in the first 12 bytes of each vertex"]
+    #[doc = " target_error represents the error relative to mesh extents that can be tolerated, e.g. 0.01 = 1% deformation; value range [0..1]"]
+    pub fn meshopt_simplifyPrune(
+        destination: *mut ::core::ffi::c_uint,
+        indices: *const ::core::ffi::c_uint,
+        index_count: usize,
+        vertex_positions: *const f32,
+        vertex_count: usize,
+        vertex_positions_stride: usize,
+        target_error: f32,
+    ) -> usize;
+}
+extern "C" {
+    #[doc = " Point cloud simplifier"]
     #[doc = " Reduces the number of points in the cloud to reach the given target"]
     #[doc = " Returns the number of points after simplification, with destination containing new index data"]
     #[doc = " The resulting index buffer references vertices from the original vertex buffer."]
-    #[doc = " If the original vertex data isn't required, creating a compact vertex buffer using meshopt_optimizeVertexFetch is recommended."]
+    #[doc = " If the original vertex data isn't needed, creating a compact vertex buffer using meshopt_optimizeVertexFetch is recommended."]
     #[doc = ""]
     #[doc = " destination must contain enough space for the target index buffer (target_vertex_count elements)"]
     #[doc = " vertex_positions should have float3 position in the first 12 bytes of each vertex"]
-    #[doc = " vertex_colors should can be NULL; when it's not NULL, it should have float3 color in the first 12 bytes of each vertex"]
+    #[doc = " vertex_colors can be NULL; when it's not NULL, it should have float3 color in the first 12 bytes of each vertex"]
+    #[doc = " color_weight determines relative priority of color wrt position; 1.0 is a safe default"]
     pub fn meshopt_simplifyPoints(
-        destination: *mut ::std::os::raw::c_uint,
+        destination: *mut ::core::ffi::c_uint,
         vertex_positions: *const f32,
         vertex_count: usize,
         vertex_positions_stride: usize,
@@ -545,11 +737,11 @@ extern "C" {
     #[doc = " destination must contain enough space for the target index buffer, worst case can be computed with meshopt_stripifyBound"]
     #[doc = " restart_index should be 0xffff or 0xffffffff depending on index size, or 0 to use degenerate triangles"]
     pub fn meshopt_stripify(
-        destination: *mut ::std::os::raw::c_uint,
-        indices: *const ::std::os::raw::c_uint,
+        destination: *mut ::core::ffi::c_uint,
+        indices: *const ::core::ffi::c_uint,
         index_count: usize,
         vertex_count: usize,
-        restart_index: ::std::os::raw::c_uint,
+        restart_index: ::core::ffi::c_uint,
     ) -> usize;
 }
 extern "C" {
@@ -562,10 +754,10 @@ extern "C" {
     #[doc = ""]
     #[doc = " destination must contain enough space for the target index buffer, worst case can be computed with meshopt_unstripifyBound"]
     pub fn meshopt_unstripify(
-        destination: *mut ::std::os::raw::c_uint,
-        indices: *const ::std::os::raw::c_uint,
+        destination: *mut ::core::ffi::c_uint,
+        indices: *const ::core::ffi::c_uint,
         index_count: usize,
-        restart_index: ::std::os::raw::c_uint,
+        restart_index: ::core::ffi::c_uint,
     ) -> usize;
 }
 extern "C" {
@@ -574,8 +766,8 @@ extern "C" {
 #[repr(C)]
 #[derive(Debug, Copy, Clone)]
 pub struct meshopt_VertexCacheStatistics {
-    pub vertices_transformed: ::std::os::raw::c_uint,
-    pub warps_executed: ::std::os::raw::c_uint,
+    pub vertices_transformed: ::core::ffi::c_uint,
+    pub warps_executed: ::core::ffi::c_uint,
     pub acmr: f32,
     pub atvr: f32,
 }
@@ -584,19 +776,36 @@ extern "C" {
     #[doc = " Returns cache hit statistics using a simplified FIFO model"]
     #[doc = " Results may not match actual GPU performance"]
     pub fn meshopt_analyzeVertexCache(
-        indices: *const ::std::os::raw::c_uint,
+        indices: *const ::core::ffi::c_uint,
         index_count: usize,
         vertex_count: usize,
-        cache_size: ::std::os::raw::c_uint,
-        warp_size: ::std::os::raw::c_uint,
-        primgroup_size: ::std::os::raw::c_uint,
+        cache_size: ::core::ffi::c_uint,
+        warp_size: ::core::ffi::c_uint,
+        primgroup_size: ::core::ffi::c_uint,
     ) -> meshopt_VertexCacheStatistics;
 }
 #[repr(C)]
 #[derive(Debug, Copy, Clone)]
+pub struct meshopt_VertexFetchStatistics {
+    pub bytes_fetched: ::core::ffi::c_uint,
+    pub overfetch: f32,
+}
+extern "C" {
+    #[doc = " Vertex fetch cache analyzer"]
+    #[doc = " Returns cache hit statistics using a simplified direct mapped model"]
+    #[doc = " Results may not match actual GPU performance"]
+    pub fn meshopt_analyzeVertexFetch(
+        indices: *const ::core::ffi::c_uint,
+        index_count: usize,
+        vertex_count: usize,
+        vertex_size: usize,
+    ) -> meshopt_VertexFetchStatistics;
+}
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
 pub struct meshopt_OverdrawStatistics {
-    pub pixels_covered: ::std::os::raw::c_uint,
-    pub pixels_shaded: ::std::os::raw::c_uint,
+    pub pixels_covered: ::core::ffi::c_uint,
+    pub pixels_shaded: ::core::ffi::c_uint,
     pub overdraw: f32,
 }
 extern "C" {
@@ -606,7 +815,7 @@ extern "C" {
     #[doc = ""]
     #[doc = " vertex_positions should have float3 position in the first 12 bytes of each vertex"]
     pub fn meshopt_analyzeOverdraw(
-        indices: *const ::std::os::raw::c_uint,
+        indices: *const ::core::ffi::c_uint,
         index_count: usize,
         vertex_positions: *const f32,
         vertex_count: usize,
@@ -615,33 +824,41 @@ extern "C" {
 }
 #[repr(C)]
 #[derive(Debug, Copy, Clone)]
-pub struct meshopt_VertexFetchStatistics {
-    pub bytes_fetched: ::std::os::raw::c_uint,
-    pub overfetch: f32,
+pub struct meshopt_CoverageStatistics {
+    pub coverage: [f32; 3usize],
+    pub extent: f32,
 }
 extern "C" {
-    #[doc = " Vertex fetch cache analyzer"]
-    #[doc = " Returns cache hit statistics using a simplified direct mapped model"]
-    #[doc = " Results may not match actual GPU performance"]
-    pub fn meshopt_analyzeVertexFetch(
-        indices: *const ::std::os::raw::c_uint,
+    #[doc = " Coverage analyzer"]
+    #[doc = " Returns coverage statistics (ratio of viewport pixels covered from each axis) using a software rasterizer"]
+    #[doc = ""]
+    #[doc = " vertex_positions should have float3 position in the first 12 bytes of each vertex"]
+    pub fn meshopt_analyzeCoverage(
+        indices: *const ::core::ffi::c_uint,
         index_count: usize,
+        vertex_positions: *const f32,
         vertex_count: usize,
-        vertex_size: usize,
-    ) -> meshopt_VertexFetchStatistics;
+        vertex_positions_stride: usize,
+    ) -> meshopt_CoverageStatistics;
 }
+#[doc = " Meshlet is a small mesh cluster (subset) that consists of:"]
+#[doc = " - triangles, an 8-bit micro triangle (index) buffer, that for each triangle specifies three local vertices to use;"]
+#[doc = " - vertices, a 32-bit vertex indirection buffer, that for each local vertex specifies which mesh vertex to fetch vertex attributes from."]
+#[doc = ""]
+#[doc = " For efficiency, meshlet triangles and vertices are packed into two large arrays; this structure contains offsets and counts to access the data."]
 #[repr(C)]
 #[derive(Debug, Copy, Clone)]
 pub struct meshopt_Meshlet {
-    pub vertex_offset: ::std::os::raw::c_uint,
-    pub triangle_offset: ::std::os::raw::c_uint,
-    pub vertex_count: ::std::os::raw::c_uint,
-    pub triangle_count: ::std::os::raw::c_uint,
+    pub vertex_offset: ::core::ffi::c_uint,
+    pub triangle_offset: ::core::ffi::c_uint,
+    pub vertex_count: ::core::ffi::c_uint,
+    pub triangle_count: ::core::ffi::c_uint,
 }
 extern "C" {
     #[doc = " Meshlet builder"]
     #[doc = " Splits the mesh into a set of meshlets where each meshlet has a micro index buffer indexing into meshlet vertices that refer to the original vertex buffer"]
     #[doc = " The resulting data can be used to render meshes using NVidia programmable mesh shading pipeline, or in other cluster-based renderers."]
+    #[doc = " When targeting mesh shading hardware, for maximum efficiency meshlets should be further optimized using meshopt_optimizeMeshlet."]
     #[doc = " When using buildMeshlets, vertex positions need to be provided to minimize the size of the resulting clusters."]
     #[doc = " When using buildMeshletsScan, for maximum efficiency the index buffer being converted has to be optimized for vertex cache first."]
     #[doc = ""]
@@ -649,13 +866,13 @@ extern "C" {
     #[doc = " meshlet_vertices must contain enough space for all meshlets, worst case size is equal to max_meshlets * max_vertices"]
     #[doc = " meshlet_triangles must contain enough space for all meshlets, worst case size is equal to max_meshlets * max_triangles * 3"]
     #[doc = " vertex_positions should have float3 position in the first 12 bytes of each vertex"]
-    #[doc = " max_vertices and max_triangles must not exceed implementation limits (max_vertices <= 255 - not 256!, max_triangles <= 512; max_triangles must be divisible by 4)"]
+    #[doc = " max_vertices and max_triangles must not exceed implementation limits (max_vertices <= 256, max_triangles <= 512; max_triangles must be divisible by 4)"]
     #[doc = " cone_weight should be set to 0 when cone culling is not used, and a value between 0 and 1 otherwise to balance between cluster size and cone culling efficiency"]
     pub fn meshopt_buildMeshlets(
         meshlets: *mut meshopt_Meshlet,
-        meshlet_vertices: *mut ::std::os::raw::c_uint,
-        meshlet_triangles: *mut ::std::os::raw::c_uchar,
-        indices: *const ::std::os::raw::c_uint,
+        meshlet_vertices: *mut ::core::ffi::c_uint,
+        meshlet_triangles: *mut ::core::ffi::c_uchar,
+        indices: *const ::core::ffi::c_uint,
         index_count: usize,
         vertex_positions: *const f32,
         vertex_count: usize,
@@ -668,9 +885,9 @@ extern "C" {
 extern "C" {
     pub fn meshopt_buildMeshletsScan(
         meshlets: *mut meshopt_Meshlet,
-        meshlet_vertices: *mut ::std::os::raw::c_uint,
-        meshlet_triangles: *mut ::std::os::raw::c_uchar,
-        indices: *const ::std::os::raw::c_uint,
+        meshlet_vertices: *mut ::core::ffi::c_uint,
+        meshlet_triangles: *mut ::core::ffi::c_uchar,
+        indices: *const ::core::ffi::c_uint,
         index_count: usize,
         vertex_count: usize,
         max_vertices: usize,
@@ -685,15 +902,69 @@ extern "C" {
     ) -> usize;
 }
 extern "C" {
-    #[doc = " Experimental: Meshlet optimizer"]
+    #[doc = " Experimental: Meshlet builder with flexible cluster sizes"]
+    #[doc = " Splits the mesh into a set of meshlets, similarly to meshopt_buildMeshlets, but allows to specify minimum and maximum number of triangles per meshlet."]
+    #[doc = " Clusters between min and max triangle counts are split when the cluster size would have exceeded the expected cluster size by more than split_factor."]
+    #[doc = " Additionally, allows to switch to axis aligned clusters by setting cone_weight to a negative value."]
+    #[doc = ""]
+    #[doc = " meshlets must contain enough space for all meshlets, worst case size can be computed with meshopt_buildMeshletsBound using min_triangles (not max!)"]
+    #[doc = " meshlet_vertices must contain enough space for all meshlets, worst case size is equal to max_meshlets * max_vertices"]
+    #[doc = " meshlet_triangles must contain enough space for all meshlets, worst case size is equal to max_meshlets * max_triangles * 3"]
+    #[doc = " vertex_positions should have float3 position in the first 12 bytes of each vertex"]
+    #[doc = " max_vertices, min_triangles and max_triangles must not exceed implementation limits (max_vertices <= 256, max_triangles <= 512; min_triangles <= max_triangles; both min_triangles and max_triangles must be divisible by 4)"]
+    #[doc = " cone_weight should be set to 0 when cone culling is not used, and a value between 0 and 1 otherwise to balance between cluster size and cone culling efficiency; additionally, cone_weight can be set to a negative value to prioritize axis aligned clusters (for raytracing) instead"]
+    #[doc = " split_factor should be set to a non-negative value; when greater than 0, clusters that have large bounds may be split unless they are under the min_triangles threshold"]
+    pub fn meshopt_buildMeshletsFlex(
+        meshlets: *mut meshopt_Meshlet,
+        meshlet_vertices: *mut ::core::ffi::c_uint,
+        meshlet_triangles: *mut ::core::ffi::c_uchar,
+        indices: *const ::core::ffi::c_uint,
+        index_count: usize,
+        vertex_positions: *const f32,
+        vertex_count: usize,
+        vertex_positions_stride: usize,
+        max_vertices: usize,
+        min_triangles: usize,
+        max_triangles: usize,
+        cone_weight: f32,
+        split_factor: f32,
+    ) -> usize;
+}
+extern "C" {
+    #[doc = " Experimental: Meshlet builder that produces clusters optimized for raytracing"]
+    #[doc = " Splits the mesh into a set of meshlets, similarly to meshopt_buildMeshlets, but optimizes cluster subdivision for raytracing and allows to specify minimum and maximum number of triangles per meshlet."]
+    #[doc = ""]
+    #[doc = " meshlets must contain enough space for all meshlets, worst case size can be computed with meshopt_buildMeshletsBound using min_triangles (not max!)"]
+    #[doc = " meshlet_vertices must contain enough space for all meshlets, worst case size is equal to max_meshlets * max_vertices"]
+    #[doc = " meshlet_triangles must contain enough space for all meshlets, worst case size is equal to max_meshlets * max_triangles * 3"]
+    #[doc = " vertex_positions should have float3 position in the first 12 bytes of each vertex"]
+    #[doc = " max_vertices, min_triangles and max_triangles must not exceed implementation limits (max_vertices <= 256, max_triangles <= 512; min_triangles <= max_triangles; both min_triangles and max_triangles must be divisible by 4)"]
+    #[doc = " fill_weight allows to prioritize clusters that are closer to maximum size at some cost to SAH quality; 0.5 is a safe default"]
+    pub fn meshopt_buildMeshletsSpatial(
+        meshlets: *mut meshopt_Meshlet,
+        meshlet_vertices: *mut ::core::ffi::c_uint,
+        meshlet_triangles: *mut ::core::ffi::c_uchar,
+        indices: *const ::core::ffi::c_uint,
+        index_count: usize,
+        vertex_positions: *const f32,
+        vertex_count: usize,
+        vertex_positions_stride: usize,
+        max_vertices: usize,
+        min_triangles: usize,
+        max_triangles: usize,
+        fill_weight: f32,
+    ) -> usize;
+}
+extern "C" {
+    #[doc = " Meshlet optimizer"]
     #[doc = " Reorders meshlet vertices and triangles to maximize locality to improve rasterizer throughput"]
     #[doc = ""]
     #[doc = " meshlet_triangles and meshlet_vertices must refer to meshlet triangle and vertex index data; when buildMeshlets* is used, these"]
     #[doc = " need to be computed from meshlet's vertex_offset and triangle_offset"]
-    #[doc = " triangle_count and vertex_count must not exceed implementation limits (vertex_count <= 255 - not 256!, triangle_count <= 512)"]
+    #[doc = " triangle_count and vertex_count must not exceed implementation limits (vertex_count <= 256, triangle_count <= 512)"]
     pub fn meshopt_optimizeMeshlet(
-        meshlet_vertices: *mut ::std::os::raw::c_uint,
-        meshlet_triangles: *mut ::std::os::raw::c_uchar,
+        meshlet_vertices: *mut ::core::ffi::c_uint,
+        meshlet_triangles: *mut ::core::ffi::c_uchar,
         triangle_count: usize,
         vertex_count: usize,
     );
@@ -706,8 +977,8 @@ pub struct meshopt_Bounds {
     pub cone_apex: [f32; 3usize],
     pub cone_axis: [f32; 3usize],
     pub cone_cutoff: f32,
-    pub cone_axis_s8: [::std::os::raw::c_schar; 3usize],
-    pub cone_cutoff_s8: ::std::os::raw::c_schar,
+    pub cone_axis_s8: [::core::ffi::c_schar; 3usize],
+    pub cone_cutoff_s8: ::core::ffi::c_schar,
 }
 extern "C" {
     #[doc = " Cluster bounds generator"]
@@ -729,9 +1000,10 @@ extern "C" {
     #[doc = " Real-Time Rendering 4th Edition, section 19.3)."]
     #[doc = ""]
     #[doc = " vertex_positions should have float3 position in the first 12 bytes of each vertex"]
-    #[doc = " index_count/3 should be less than or equal to 512 (the function assumes clusters of limited size)"]
+    #[doc = " vertex_count should specify the number of vertices in the entire mesh, not cluster or meshlet"]
+    #[doc = " index_count/3 and triangle_count must not exceed implementation limits (<= 512)"]
     pub fn meshopt_computeClusterBounds(
-        indices: *const ::std::os::raw::c_uint,
+        indices: *const ::core::ffi::c_uint,
         index_count: usize,
         vertex_positions: *const f32,
         vertex_count: usize,
@@ -740,14 +1012,50 @@ extern "C" {
 }
 extern "C" {
     pub fn meshopt_computeMeshletBounds(
-        meshlet_vertices: *const ::std::os::raw::c_uint,
-        meshlet_triangles: *const ::std::os::raw::c_uchar,
+        meshlet_vertices: *const ::core::ffi::c_uint,
+        meshlet_triangles: *const ::core::ffi::c_uchar,
         triangle_count: usize,
         vertex_positions: *const f32,
         vertex_count: usize,
         vertex_positions_stride: usize,
     ) -> meshopt_Bounds;
 }
+extern "C" {
+    #[doc = " Sphere bounds generator"]
+    #[doc = " Creates bounding sphere around a set of points or a set of spheres; returns the center and radius of the sphere, with other fields of the result set to 0."]
+    #[doc = ""]
+    #[doc = " positions should have float3 position in the first 12 bytes of each element"]
+    #[doc = " radii can be NULL; when it's not NULL, it should have a non-negative float radius in the first 4 bytes of each element"]
+    pub fn meshopt_computeSphereBounds(
+        positions: *const f32,
+        count: usize,
+        positions_stride: usize,
+        radii: *const f32,
+        radii_stride: usize,
+    ) -> meshopt_Bounds;
+}
+extern "C" {
+    #[doc = " Cluster partitioner"]
+    #[doc = " Partitions clusters into groups of similar size, prioritizing grouping clusters that share vertices or are close to each other."]
+    #[doc = ""]
+    #[doc = " destination must contain enough space for the resulting partition data (cluster_count elements)"]
+    #[doc = " destination[i] will contain the partition id for cluster i, with the total number of partitions returned by the function"]
+    #[doc = " cluster_indices should have the vertex indices referenced by each cluster, stored sequentially"]
+    #[doc = " cluster_index_counts should have the number of indices in each cluster; sum of all cluster_index_counts must be equal to total_index_count"]
+    #[doc = " vertex_positions should have float3 position in the first 12 bytes of each vertex (or can be NULL if not used)"]
+    #[doc = " target_partition_size is a target size for each partition, in clusters; the resulting partitions may be smaller or larger"]
+    pub fn meshopt_partitionClusters(
+        destination: *mut ::core::ffi::c_uint,
+        cluster_indices: *const ::core::ffi::c_uint,
+        total_index_count: usize,
+        cluster_index_counts: *const ::core::ffi::c_uint,
+        cluster_count: usize,
+        vertex_positions: *const f32,
+        vertex_count: usize,
+        vertex_positions_stride: usize,
+        target_partition_size: usize,
+    ) -> usize;
+}
 extern "C" {
     #[doc = " Spatial sorter"]
     #[doc = " Generates a remap table that can be used to reorder points for spatial locality."]
@@ -756,36 +1064,69 @@ extern "C" {
     #[doc = " destination must contain enough space for the resulting remap table (vertex_count elements)"]
     #[doc = " vertex_positions should have float3 position in the first 12 bytes of each vertex"]
     pub fn meshopt_spatialSortRemap(
-        destination: *mut ::std::os::raw::c_uint,
+        destination: *mut ::core::ffi::c_uint,
         vertex_positions: *const f32,
         vertex_count: usize,
         vertex_positions_stride: usize,
     );
 }
 extern "C" {
-    #[doc = " Experimental: Spatial sorter"]
+    #[doc = " Spatial sorter"]
     #[doc = " Reorders triangles for spatial locality, and generates a new index buffer. The resulting index buffer can be used with other functions like optimizeVertexCache."]
     #[doc = ""]
     #[doc = " destination must contain enough space for the resulting index buffer (index_count elements)"]
     #[doc = " vertex_positions should have float3 position in the first 12 bytes of each vertex"]
     pub fn meshopt_spatialSortTriangles(
-        destination: *mut ::std::os::raw::c_uint,
-        indices: *const ::std::os::raw::c_uint,
+        destination: *mut ::core::ffi::c_uint,
+        indices: *const ::core::ffi::c_uint,
         index_count: usize,
         vertex_positions: *const f32,
         vertex_count: usize,
         vertex_positions_stride: usize,
     );
 }
+extern "C" {
+    #[doc = " Spatial clusterizer"]
+    #[doc = " Reorders points into clusters optimized for spatial locality, and generates a new index buffer."]
+    #[doc = " Ensures the output can be split into cluster_size chunks where each chunk has good positional locality. Only the last chunk will be smaller than cluster_size."]
+    #[doc = ""]
+    #[doc = " destination must contain enough space for the resulting index buffer (vertex_count elements)"]
+    #[doc = " vertex_positions should have float3 position in the first 12 bytes of each vertex"]
+    pub fn meshopt_spatialClusterPoints(
+        destination: *mut ::core::ffi::c_uint,
+        vertex_positions: *const f32,
+        vertex_count: usize,
+        vertex_positions_stride: usize,
+        cluster_size: usize,
+    );
+}
+extern "C" {
+    #[doc = " Quantize a float into half-precision (as defined by IEEE-754 fp16) floating point value"]
+    #[doc = " Generates +-inf for overflow, preserves NaN, flushes denormals to zero, rounds to nearest"]
+    #[doc = " Representable magnitude range: [6e-5; 65504]"]
+    #[doc = " Maximum relative reconstruction error: 5e-4"]
+    pub fn meshopt_quantizeHalf(v: f32) -> ::core::ffi::c_ushort;
+}
+extern "C" {
+    #[doc = " Quantize a float into a floating point value with a limited number of significant mantissa bits, preserving the IEEE-754 fp32 binary representation"]
+    #[doc = " Generates +-inf for overflow, preserves NaN, flushes denormals to zero, rounds to nearest"]
+    #[doc = " Assumes N is in a valid mantissa precision range, which is 1..23"]
+    pub fn meshopt_quantizeFloat(v: f32, N: ::core::ffi::c_int) -> f32;
+}
+extern "C" {
+    #[doc = " Reverse quantization of a half-precision (as defined by IEEE-754 fp16) floating point value"]
+    #[doc = " Preserves Inf/NaN, flushes denormals to zero"]
+    pub fn meshopt_dequantizeHalf(h: ::core::ffi::c_ushort) -> f32;
+}
 extern "C" {
     #[doc = " Set allocation callbacks"]
     #[doc = " These callbacks will be used instead of the default operator new/operator delete for all temporary allocations in the library."]
     #[doc = " Note that all algorithms only allocate memory for temporary use."]
     #[doc = " allocate/deallocate are always called in a stack-like order - last pointer to be allocated is deallocated first."]
     pub fn meshopt_setAllocator(
-        allocate: ::std::option::Option<
-            unsafe extern "C" fn(arg1: usize) -> *mut ::std::os::raw::c_void,
+        allocate: ::core::option::Option<
+            unsafe extern "C" fn(arg1: usize) -> *mut ::core::ffi::c_void,
         >,
-        deallocate: ::std::option::Option<unsafe extern "C" fn(arg1: *mut ::std::os::raw::c_void)>,
+        deallocate: ::core::option::Option<unsafe extern "C" fn(arg1: *mut ::core::ffi::c_void)>,
     );
 }
@@ -1,12 +1,59 @@
-use crate::{ffi, DecodePosition, VertexDataAdapter};
-use std::mem;
+use crate::{
+    ffi,
+    optimize::{check_position_stride, position_count},
+    utilities::triangle_cross,
+    DecodePosition, VertexDataAdapter,
+};
+use alloc::{format, string::String, vec, vec::Vec};
+use core::{fmt, mem};
 
 pub type VertexCacheStatistics = ffi::meshopt_VertexCacheStatistics;
 pub type VertexFetchStatistics = ffi::meshopt_VertexFetchStatistics;
 pub type OverdrawStatistics = ffi::meshopt_OverdrawStatistics;
+pub type CoverageStatistics = ffi::meshopt_CoverageStatistics;
+
+/// Vertex cache parameters for `analyze_vertex_cache_preset`, approximating common GPU
+/// families. Real hardware varies between generations, so treat these as rough models.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub enum GpuPreset {
+    /// A 16 entry FIFO cache without warp or primitive group limits.
+    #[default]
+    Generic,
+    /// A 32 entry cache with vertices processed in warps of 32 and primitive groups of 32.
+    NvidiaLike,
+    /// A 14 entry cache with vertices processed in wavefronts of 64 and primitive groups
+    /// of 128.
+    AmdLike,
+    /// A 128 entry FIFO cache without warp or primitive group limits.
+    IntelLike,
+    /// An 8 entry FIFO cache, as a conservative model of tile-based mobile GPUs.
+    MobileTiler,
+}
+
+impl GpuPreset {
+    /// Returns the `(cache_size, warp_size, prim_group_size)` used for this preset.
+    pub fn parameters(self) -> (u32, u32, u32) {
+        match self {
+            GpuPreset::Generic => (16, 0, 0),
+            GpuPreset::NvidiaLike => (32, 32, 32),
+            GpuPreset::AmdLike => (14, 64, 128),
+            GpuPreset::IntelLike => (128, 0, 0),
+            GpuPreset::MobileTiler => (8, 0, 0),
+        }
+    }
+}
 
 /// Returns cache hit statistics using a simplified FIFO model.
 /// Results may not match actual GPU performance.
+///
+/// `cache_size` is the number of entries in the FIFO cache. A non-zero `warp_size` models
+/// GPUs that transform vertices in batches, and a non-zero `prim_group_size` limits the
+/// number of triangles per batch; see `GpuPreset` for typical values.
+///
+/// `acmr` is the average number of transformed vertices per triangle (0.5 is optimal for
+/// large grids, 3.0 is the worst case), which depends on the mesh topology. `atvr` is the
+/// number of transformed vertices per unique vertex (1.0 is optimal), which makes it the
+/// more hardware and mesh independent number to optimize for.
 pub fn analyze_vertex_cache(
     indices: &[u32],
     vertex_count: usize,
@@ -26,18 +73,140 @@ pub fn analyze_vertex_cache(
     }
 }
 
+/// Like `analyze_vertex_cache`, with the cache parameters of `preset`.
+pub fn analyze_vertex_cache_preset(
+    indices: &[u32],
+    vertex_count: usize,
+    preset: GpuPreset,
+) -> VertexCacheStatistics {
+    let (cache_size, warp_size, prim_group_size) = preset.parameters();
+    analyze_vertex_cache(
+        indices,
+        vertex_count,
+        cache_size,
+        warp_size,
+        prim_group_size,
+    )
+}
+
+/// Replacement policy of a `CacheModel`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub enum CachePolicy {
+    /// Evicts the vertex that entered the cache first; cache hits don't change the order.
+    /// This is the model of `analyze_vertex_cache`.
+    #[default]
+    Fifo,
+    /// Evicts the least recently used vertex; cache hits move the vertex to the front.
+    Lru,
+}
+
+/// Post-transform vertex cache model for `simulate_cache`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct CacheModel {
+    /// Number of vertices the cache holds.
+    pub size: usize,
+    /// Which vertex is evicted when a new vertex enters a full cache.
+    pub policy: CachePolicy,
+}
+
+/// Cache statistics returned by `simulate_cache`, with the same meaning as the fields of
+/// `VertexCacheStatistics`.
+#[derive(Debug, Copy, Clone, PartialEq, Default)]
+pub struct CacheStats {
+    pub vertices_transformed: usize,
+    pub acmr: f32,
+    pub atvr: f32,
+}
+
+/// Returns cache hit statistics for a user defined cache model, simulated in Rust.
+///
+/// With `CachePolicy::Fifo` this matches `analyze_vertex_cache` without warps and primitive
+/// groups. The simulation scans the cache for every index, so it is meant for the cache
+/// sizes of real hardware rather than for very large caches.
+///
+/// # Panics
+///
+/// Panics if the index count is not a multiple of 3, or if the cache size is zero.
+pub fn simulate_cache(indices: &[u32], cache: &CacheModel) -> CacheStats {
+    assert!(
+        indices.len().is_multiple_of(3),
+        "index count must be a multiple of 3"
+    );
+    assert!(cache.size > 0, "cache size must be non-zero");
+
+    // most recently inserted (or used, for LRU) vertex first
+    let mut entries: Vec<u32> = Vec::with_capacity(cache.size + 1);
+    let mut vertices_transformed = 0;
+    for &index in indices {
+        if let Some(position) = entries.iter().position(|&entry| entry == index) {
+            if cache.policy == CachePolicy::Lru {
+                entries[..=position].rotate_right(1);
+            }
+        } else {
+            vertices_transformed += 1;
+            entries.insert(0, index);
+            entries.truncate(cache.size);
+        }
+    }
+
+    let mut unique = indices.to_vec();
+    unique.sort_unstable();
+    unique.dedup();
+
+    let triangle_count = indices.len() / 3;
+    CacheStats {
+        vertices_transformed,
+        acmr: if triangle_count == 0 {
+            0.0
+        } else {
+            vertices_transformed as f32 / triangle_count as f32
+        },
+        atvr: if unique.is_empty() {
+            0.0
+        } else {
+            vertices_transformed as f32 / unique.len() as f32
+        },
+    }
+}
+
 /// Returns cache hit statistics using a simplified direct mapped model.
 /// Results may not match actual GPU performance.
+///
+/// `vertex_size` is the size of a vertex in bytes. `bytes_fetched` is the estimated memory
+/// traffic and `overfetch` its ratio to the size of all vertices (1.0 is optimal).
+///
+/// # Panics
+///
+/// Panics if the index count is not a multiple of 3, or if `vertex_size` is not in `1..=256`;
+/// see [`validate_mesh`].
 pub fn analyze_vertex_fetch(
     indices: &[u32],
     vertex_count: usize,
     vertex_size: usize,
 ) -> VertexFetchStatistics {
+    assert!(
+        indices.len().is_multiple_of(3),
+        "index count must be a multiple of 3"
+    );
+    assert!(
+        (1..=256).contains(&vertex_size),
+        "vertex_size ({}) must be in 1..=256",
+        vertex_size
+    );
     unsafe {
         ffi::meshopt_analyzeVertexFetch(indices.as_ptr(), indices.len(), vertex_count, vertex_size)
     }
 }
 
+/// Like `analyze_vertex_fetch`, with the size of `T` as the vertex size.
+///
+/// # Panics
+///
+/// Panics if the index count is not a multiple of 3, or if the size of `T` is not in `1..=256`.
+pub fn analyze_vertex_fetch_for<T>(indices: &[u32], vertex_count: usize) -> VertexFetchStatistics {
+    analyze_vertex_fetch(indices, vertex_count, mem::size_of::<T>())
+}
+
 /// Returns overdraw statistics using a software rasterizer.
 /// Results may not match actual GPU performance.
 pub fn analyze_overdraw_decoder<T: DecodePosition>(
@@ -72,3 +241,690 @@ pub fn analyze_overdraw(indices: &[u32], vertices: &VertexDataAdapter<'_>) -> Ov
         )
     }
 }
+
+/// Like `analyze_overdraw`, but reads the vertex positions from a plain float array.
+///
+/// `positions` should have a float3 position in the first 12 bytes of each vertex, with
+/// `position_stride` bytes between vertices, as in `optimize_overdraw_positions`.
+///
+/// # Panics
+///
+/// Panics if `position_stride` is not a multiple of 4 in `12..=256`.
+pub fn analyze_overdraw_positions(
+    indices: &[u32],
+    positions: &[f32],
+    position_stride: usize,
+) -> OverdrawStatistics {
+    check_position_stride(position_stride);
+    unsafe {
+        ffi::meshopt_analyzeOverdraw(
+            indices.as_ptr(),
+            indices.len(),
+            positions.as_ptr(),
+            position_count(positions, position_stride),
+            position_stride,
+        )
+    }
+}
+
+/// Returns coverage statistics using a software rasterizer: `coverage` is the ratio of
+/// viewport pixels covered when looking along each axis, and `extent` is the viewport size
+/// in mesh coordinates.
+///
+/// This is a cheap way to check that simplification keeps the silhouette of a mesh.
+///
+/// # Panics
+///
+/// Panics if the index count is not a multiple of 3, if any index is out of range of the
+/// vertex count, or if the vertex stride is not a multiple of 4 in `12..=256`; see
+/// [`validate_mesh`].
+pub fn analyze_coverage(indices: &[u32], vertices: &VertexDataAdapter<'_>) -> CoverageStatistics {
+    assert!(
+        indices.len().is_multiple_of(3),
+        "index count must be a multiple of 3"
+    );
+    assert!(
+        indices
+            .iter()
+            .all(|&index| (index as usize) < vertices.vertex_count),
+        "indices must be less than the vertex count"
+    );
+    check_position_stride(vertices.vertex_stride);
+    unsafe {
+        ffi::meshopt_analyzeCoverage(
+            indices.as_ptr(),
+            indices.len(),
+            vertices.pos_ptr(),
+            vertices.vertex_count,
+            vertices.vertex_stride,
+        )
+    }
+}
+
+/// Number of out of range indices that `validate_mesh` records the location of.
+const MAX_REPORTED_OFFENDERS: usize = 8;
+
+/// Problems found by `validate_mesh`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ValidationReport {
+    /// Number of trailing indices that don't form a complete triangle.
+    pub trailing_indices: usize,
+    /// Number of indices that are not less than the vertex count.
+    pub out_of_range_indices: usize,
+    /// Location in the index buffer and value of the first few out of range indices.
+    pub out_of_range_offenders: Vec<(usize, u32)>,
+    /// Number of triangles that reference the same vertex more than once.
+    pub degenerate_triangles: usize,
+    /// Number of triangles with distinct vertices but exactly zero area; only counted when
+    /// positions are provided.
+    pub zero_area_triangles: usize,
+    /// Number of triangles that repeat the vertices of an earlier triangle, regardless of
+    /// winding.
+    pub duplicate_triangles: usize,
+    /// Number of vertices that are not referenced by any index.
+    pub unreferenced_vertices: usize,
+}
+
+impl ValidationReport {
+    /// Returns true if no problems were found.
+    pub fn is_clean(&self) -> bool {
+        *self == ValidationReport::default()
+    }
+}
+
+/// Checks an index buffer (and optionally the vertex positions) for problems that would
+/// trigger assertions or waste work in the other functions of this crate, without panicking.
+///
+/// Triangles with out of range indices are excluded from the degenerate, zero area and
+/// duplicate counts. Zero area triangles are only detected when `positions` is provided;
+/// positions that can't be read from the adapter are treated as unknown.
+pub fn validate_mesh(
+    indices: &[u32],
+    vertex_count: usize,
+    positions: Option<&VertexDataAdapter<'_>>,
+) -> ValidationReport {
+    let mut report = ValidationReport {
+        trailing_indices: indices.len() % 3,
+        ..ValidationReport::default()
+    };
+
+    let mut referenced = vec![false; vertex_count];
+    for (location, &index) in indices.iter().enumerate() {
+        if let Some(flag) = referenced.get_mut(index as usize) {
+            *flag = true;
+        } else {
+            report.out_of_range_indices += 1;
+            if report.out_of_range_offenders.len() < MAX_REPORTED_OFFENDERS {
+                report.out_of_range_offenders.push((location, index));
+            }
+        }
+    }
+    report.unreferenced_vertices = referenced.iter().filter(|&&flag| !flag).count();
+
+    let mut triangles = Vec::with_capacity(indices.len() / 3);
+    for triangle in indices.chunks_exact(3) {
+        let [a, b, c] = [triangle[0], triangle[1], triangle[2]];
+        if [a, b, c]
+            .iter()
+            .any(|&index| index as usize >= vertex_count)
+        {
+            continue;
+        }
+        if a == b || b == c || c == a {
+            report.degenerate_triangles += 1;
+            continue;
+        }
+        if let Some(positions) = positions {
+            if let (Ok(p0), Ok(p1), Ok(p2)) = (
+                positions.xyz_f32_at(a as usize),
+                positions.xyz_f32_at(b as usize),
+                positions.xyz_f32_at(c as usize),
+            ) {
+                if triangle_cross(p0, p1, p2) == [0.0; 3] {
+                    report.zero_area_triangles += 1;
+                }
+            }
+        }
+        let mut key = [a, b, c];
+        key.sort_unstable();
+        triangles.push(key);
+    }
+    let triangle_count = triangles.len();
+    triangles.sort_unstable();
+    triangles.dedup();
+    report.duplicate_triangles = triangle_count - triangles.len();
+
+    report
+}
+
+/// All analysis statistics of a mesh, as returned by `analyze_mesh`, e.g. for logging the
+/// effectiveness of an optimization pipeline per asset.
+///
+/// `Display` formats the report as a single line; `csv_header` and `to_csv_row` produce
+/// spreadsheet rows, and `compare` shows the changes relative to an earlier report.
+#[derive(Debug, Copy, Clone)]
+pub struct MeshReport {
+    pub triangle_count: usize,
+    pub vertex_count: usize,
+    /// Statistics of `analyze_vertex_cache_preset` with `GpuPreset::Generic`.
+    pub vertex_cache: VertexCacheStatistics,
+    pub vertex_fetch: VertexFetchStatistics,
+    pub overdraw: OverdrawStatistics,
+    pub coverage: CoverageStatistics,
+}
+
+/// Analyzes `indices` with the vertex cache, vertex fetch, overdraw and coverage analyzers.
+///
+/// Vertex positions are read through `vertices`, while `vertex_size` is the size in bytes
+/// that the vertex fetch analysis assumes per vertex.
+///
+/// # Panics
+///
+/// Panics under the same conditions as `analyze_vertex_fetch` and `analyze_coverage`.
+pub fn analyze_mesh(
+    indices: &[u32],
+    vertices: &VertexDataAdapter<'_>,
+    vertex_size: usize,
+) -> MeshReport {
+    let vertex_count = vertices.vertex_count;
+    MeshReport {
+        triangle_count: indices.len() / 3,
+        vertex_count,
+        vertex_cache: analyze_vertex_cache_preset(indices, vertex_count, GpuPreset::Generic),
+        vertex_fetch: analyze_vertex_fetch(indices, vertex_count, vertex_size),
+        overdraw: analyze_overdraw(indices, vertices),
+        coverage: analyze_coverage(indices, vertices),
+    }
+}
+
+impl MeshReport {
+    /// Returns the column names matching `to_csv_row`.
+    pub fn csv_header() -> &'static str {
+        "triangles,vertices,acmr,atvr,vertices_transformed,warps_executed,bytes_fetched,\
+         overfetch,pixels_covered,pixels_shaded,overdraw,coverage_x,coverage_y,coverage_z"
+    }
+
+    /// Formats the report as a row of comma separated values, without a trailing newline.
+    pub fn to_csv_row(&self) -> String {
+        format!(
+            "{},{},{},{},{},{},{},{},{},{},{},{},{},{}",
+            self.triangle_count,
+            self.vertex_count,
+            self.vertex_cache.acmr,
+            self.vertex_cache.atvr,
+            self.vertex_cache.vertices_transformed,
+            self.vertex_cache.warps_executed,
+            self.vertex_fetch.bytes_fetched,
+            self.vertex_fetch.overfetch,
+            self.overdraw.pixels_covered,
+            self.overdraw.pixels_shaded,
+            self.overdraw.overdraw,
+            self.coverage.coverage[0],
+            self.coverage.coverage[1],
+            self.coverage.coverage[2],
+        )
+    }
+
+    /// Returns a `Display`able comparison of this report with a report of the same mesh
+    /// before some processing step.
+    pub fn compare<'a>(&'a self, before: &'a MeshReport) -> MeshReportComparison<'a> {
+        MeshReportComparison {
+            before,
+            after: self,
+        }
+    }
+}
+
+impl fmt::Display for MeshReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} triangles, {} vertices: ACMR {:.6} ATVR {:.6} Overfetch {:.6} Overdraw {:.6} \
+             Coverage {:.3}/{:.3}/{:.3}",
+            self.triangle_count,
+            self.vertex_count,
+            self.vertex_cache.acmr,
+            self.vertex_cache.atvr,
+            self.vertex_fetch.overfetch,
+            self.overdraw.overdraw,
+            self.coverage.coverage[0],
+            self.coverage.coverage[1],
+            self.coverage.coverage[2],
+        )
+    }
+}
+
+/// Changes between two `MeshReport`s, created with `MeshReport::compare`.
+///
+/// `Display` shows each statistic as `before -> after (change%)`.
+#[derive(Debug, Copy, Clone)]
+pub struct MeshReportComparison<'a> {
+    pub before: &'a MeshReport,
+    pub after: &'a MeshReport,
+}
+
+impl MeshReportComparison<'_> {
+    /// Returns the relative change of ACMR, e.g. -0.25 for a 25% reduction.
+    pub fn acmr_change(&self) -> f32 {
+        relative_change(self.before.vertex_cache.acmr, self.after.vertex_cache.acmr)
+    }
+
+    /// Returns the relative change of ATVR.
+    pub fn atvr_change(&self) -> f32 {
+        relative_change(self.before.vertex_cache.atvr, self.after.vertex_cache.atvr)
+    }
+
+    /// Returns the relative change of the vertex fetch overfetch.
+    pub fn overfetch_change(&self) -> f32 {
+        relative_change(
+            self.before.vertex_fetch.overfetch,
+            self.after.vertex_fetch.overfetch,
+        )
+    }
+
+    /// Returns the relative change of the overdraw.
+    pub fn overdraw_change(&self) -> f32 {
+        relative_change(self.before.overdraw.overdraw, self.after.overdraw.overdraw)
+    }
+}
+
+// relative change from `before` to `after`, or zero if `before` is zero
+fn relative_change(before: f32, after: f32) -> f32 {
+    if before == 0.0 {
+        0.0
+    } else {
+        (after - before) / before
+    }
+}
+
+impl fmt::Display for MeshReportComparison<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (before, after) = (self.before, self.after);
+        write!(
+            f,
+            "{} -> {} triangles, {} -> {} vertices",
+            before.triangle_count, after.triangle_count, before.vertex_count, after.vertex_count
+        )?;
+        for (name, before, after) in [
+            ("ACMR", before.vertex_cache.acmr, after.vertex_cache.acmr),
+            ("ATVR", before.vertex_cache.atvr, after.vertex_cache.atvr),
+            (
+                "Overfetch",
+                before.vertex_fetch.overfetch,
+                after.vertex_fetch.overfetch,
+            ),
+            (
+                "Overdraw",
+                before.overdraw.overdraw,
+                after.overdraw.overdraw,
+            ),
+        ] {
+            write!(
+                f,
+                ": {} {:.6} -> {:.6} ({:+.1}%)",
+                name,
+                before,
+                after,
+                relative_change(before, after) * 100.0
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        optimize_vertex_cache, optimize_vertex_fetch_remap, remap_index_buffer, simplify,
+        test_utils::{grid_indices, grid_positions},
+        typed_to_bytes, SimplifyOptions, Vertex,
+    };
+
+    #[test]
+    fn test_simulate_cache_matches_analyzer() {
+        let size = 16;
+        let vertex_count = ((size + 1) * (size + 1)) as usize;
+        let grid = grid_indices(size);
+        let optimized = optimize_vertex_cache(&grid, vertex_count);
+
+        for indices in [&grid, &optimized] {
+            for cache_size in [3, 16, 32] {
+                let expected = analyze_vertex_cache(indices, vertex_count, cache_size, 0, 0);
+                let model = CacheModel {
+                    size: cache_size as usize,
+                    policy: CachePolicy::Fifo,
+                };
+                let stats = simulate_cache(indices, &model);
+                assert_eq!(
+                    stats.vertices_transformed,
+                    expected.vertices_transformed as usize
+                );
+                assert_eq!(stats.acmr, expected.acmr);
+                assert_eq!(stats.atvr, expected.atvr);
+            }
+        }
+    }
+
+    #[test]
+    fn test_simulate_cache_lru() {
+        // vertex 0 is used by every triangle, so LRU keeps it while FIFO evicts it
+        let indices = [0, 1, 2, 0, 3, 4, 0, 5, 6];
+        let fifo = CacheModel {
+            size: 3,
+            policy: CachePolicy::Fifo,
+        };
+        let lru = CacheModel {
+            size: 3,
+            policy: CachePolicy::Lru,
+        };
+        assert_eq!(simulate_cache(&indices, &fifo).vertices_transformed, 8);
+        assert_eq!(simulate_cache(&indices, &lru).vertices_transformed, 7);
+        assert_eq!(simulate_cache(&[], &lru), CacheStats::default());
+    }
+
+    #[test]
+    fn test_analyze_vertex_cache_presets() {
+        let size = 8;
+        let vertex_count = ((size + 1) * (size + 1)) as usize;
+        let indices = optimize_vertex_cache(&grid_indices(size), vertex_count);
+
+        for preset in [
+            GpuPreset::Generic,
+            GpuPreset::NvidiaLike,
+            GpuPreset::AmdLike,
+            GpuPreset::IntelLike,
+            GpuPreset::MobileTiler,
+        ] {
+            let (cache_size, warp_size, prim_group_size) = preset.parameters();
+            let stats = analyze_vertex_cache_preset(&indices, vertex_count, preset);
+            let expected = analyze_vertex_cache(
+                &indices,
+                vertex_count,
+                cache_size,
+                warp_size,
+                prim_group_size,
+            );
+            assert_eq!(stats.vertices_transformed, expected.vertices_transformed);
+            assert_eq!(stats.warps_executed, expected.warps_executed);
+            assert!(stats.atvr >= 1.0);
+            assert_eq!(
+                stats.acmr,
+                stats.vertices_transformed as f32 / (indices.len() / 3) as f32
+            );
+            assert_eq!(
+                stats.atvr,
+                stats.vertices_transformed as f32 / vertex_count as f32
+            );
+        }
+
+        // the whole grid fits into the 128 entry cache, so every vertex is transformed once
+        let intel = analyze_vertex_cache_preset(&indices, vertex_count, GpuPreset::IntelLike);
+        assert_eq!(intel.atvr, 1.0);
+        // with 32 vertex warps, the 81 vertices need at least 3 of them
+        let nvidia = analyze_vertex_cache_preset(&indices, vertex_count, GpuPreset::NvidiaLike);
+        assert!(nvidia.warps_executed >= 3);
+        // a smaller cache can only transform more vertices
+        let mobile = analyze_vertex_cache_preset(&indices, vertex_count, GpuPreset::MobileTiler);
+        let generic = analyze_vertex_cache_preset(&indices, vertex_count, GpuPreset::Generic);
+        assert!(mobile.vertices_transformed >= generic.vertices_transformed);
+    }
+
+    #[test]
+    fn test_analyze_overdraw_variants_agree() {
+        let size = 8;
+        let indices = grid_indices(size);
+        // two stacked layers of the grid, interleaved with a uv
+        let vertices: Vec<[f32; 5]> = (0..=size)
+            .flat_map(|y| (0..=size).map(move |x| [x as f32, y as f32, 0.0, 0.5, 0.25]))
+            .collect();
+        let vertex_count = vertices.len() as u32;
+        let mut layered = indices.clone();
+        layered.extend(indices.iter().map(|&index| index + vertex_count));
+        let mut vertices_layered = vertices.clone();
+        vertices_layered.extend(vertices.iter().map(|v| [v[0], v[1], 1.0, v[3], v[4]]));
+
+        let stride = mem::size_of::<[f32; 5]>();
+        let adapter = VertexDataAdapter::new(typed_to_bytes(&vertices_layered), stride, 0).unwrap();
+        let from_adapter = analyze_overdraw(&layered, &adapter);
+        let from_positions =
+            analyze_overdraw_positions(&layered, vertices_layered.as_flattened(), stride);
+        let positions: Vec<[f32; 3]> = vertices_layered
+            .iter()
+            .map(|v| [v[0], v[1], v[2]])
+            .collect();
+        let from_decoder = analyze_overdraw_decoder(&layered, &positions);
+
+        for stats in [from_positions, from_decoder] {
+            assert_eq!(stats.pixels_covered, from_adapter.pixels_covered);
+            assert_eq!(stats.pixels_shaded, from_adapter.pixels_shaded);
+            assert_eq!(stats.overdraw, from_adapter.overdraw);
+        }
+        assert!(from_adapter.overdraw > 1.0);
+    }
+
+    #[test]
+    fn test_analyze_overdraw_overlapping_triangles() {
+        // positions at offset 4 of a 20 byte vertex, after a packed color
+        #[derive(Clone, Copy)]
+        #[repr(C)]
+        struct Vertex {
+            color: [u8; 4],
+            position: [f32; 3],
+            uv: f32,
+        }
+        let vertex = |x: f32, y: f32, z: f32| Vertex {
+            color: [255; 4],
+            position: [x, y, z],
+            uv: 0.0,
+        };
+        let vertices = [
+            vertex(0.0, 0.0, 0.0),
+            vertex(1.0, 0.0, 0.0),
+            vertex(0.0, 1.0, 0.0),
+            vertex(0.0, 0.0, 0.0),
+            vertex(1.0, 0.0, 0.0),
+            vertex(0.0, 1.0, 0.0),
+        ];
+        let adapter = VertexDataAdapter::new(
+            typed_to_bytes(&vertices),
+            mem::size_of::<Vertex>(),
+            memoffset::offset_of!(Vertex, position),
+        )
+        .unwrap();
+
+        let single = analyze_overdraw(&[0, 1, 2], &adapter);
+        assert!(single.pixels_covered > 0);
+        assert_eq!(single.pixels_shaded, single.pixels_covered);
+        assert_eq!(single.overdraw, 1.0);
+
+        // the same triangle twice covers the same pixels but shades them twice
+        let overlapping = analyze_overdraw(&[0, 1, 2, 3, 4, 5], &adapter);
+        assert_eq!(overlapping.pixels_covered, single.pixels_covered);
+        assert!(overlapping.overdraw > 1.0);
+    }
+
+    #[test]
+    fn test_analyze_vertex_fetch() {
+        // large enough that the vertices don't fit into the modeled cache
+        let size = 64;
+        let vertex_count = ((size + 1) * (size + 1)) as usize;
+        let indices = optimize_vertex_cache(&grid_indices(size), vertex_count);
+
+        // scatter the vertices so that neighboring triangles fetch distant memory
+        let scatter: Vec<u32> = (0..vertex_count as u32)
+            .map(|i| i * 37 % vertex_count as u32)
+            .collect();
+        let shuffled = remap_index_buffer(Some(&indices), vertex_count, &scatter);
+        let (remap, _) = optimize_vertex_fetch_remap(&shuffled, vertex_count);
+        let optimized = remap_index_buffer(Some(&shuffled), vertex_count, &remap);
+
+        let shuffled_stats = analyze_vertex_fetch_for::<Vertex>(&shuffled, vertex_count);
+        let optimized_stats = analyze_vertex_fetch_for::<Vertex>(&optimized, vertex_count);
+        assert!(optimized_stats.bytes_fetched < shuffled_stats.bytes_fetched);
+        assert!(optimized_stats.overfetch < shuffled_stats.overfetch);
+        assert!(optimized_stats.overfetch >= 1.0);
+
+        let sized = analyze_vertex_fetch(&optimized, vertex_count, mem::size_of::<Vertex>());
+        assert_eq!(sized.bytes_fetched, optimized_stats.bytes_fetched);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_analyze_vertex_fetch_invalid_size() {
+        analyze_vertex_fetch(&[0, 1, 2], 3, 0);
+    }
+
+    #[test]
+    fn test_analyze_coverage() {
+        let size = 32;
+        let positions: Vec<[f32; 3]> = (0..=size)
+            .flat_map(|y| {
+                (0..=size).map(move |x| [x as f32, y as f32, ((x + y) as f32 * 0.3).sin()])
+            })
+            .collect();
+        let indices = grid_indices(size);
+        let adapter = VertexDataAdapter::new(typed_to_bytes(&positions), 12, 0).unwrap();
+
+        let original = analyze_coverage(&indices, &adapter);
+        assert!(original.extent >= size as f32);
+        // the grid fills the viewport when seen from above
+        assert!(original.coverage[2] > 0.9);
+
+        let simplified = simplify(
+            &indices,
+            &adapter,
+            indices.len() / 2,
+            0.05,
+            SimplifyOptions::empty(),
+            None,
+        );
+        assert!(simplified.len() < indices.len());
+        let simplified = analyze_coverage(&simplified, &adapter);
+
+        // dropping every other row of quads leaves holes
+        let holes: Vec<u32> = indices
+            .chunks_exact(6 * size as usize)
+            .step_by(2)
+            .flatten()
+            .copied()
+            .collect();
+        let holes = analyze_coverage(&holes, &adapter);
+
+        for axis in 0..3 {
+            assert!((simplified.coverage[axis] - original.coverage[axis]).abs() < 0.02);
+        }
+        assert!(holes.coverage[2] < original.coverage[2] * 0.6);
+    }
+
+    #[test]
+    fn test_analyze_mesh_report() {
+        let size = 16;
+        let vertex_count = ((size + 1) * (size + 1)) as usize;
+        let positions = grid_positions(size);
+        let adapter = VertexDataAdapter::new(typed_to_bytes(&positions), 12, 0).unwrap();
+        let indices = grid_indices(size);
+        // scatter the triangles so the cache optimization has something to do
+        let mut scattered: Vec<u32> = (0..indices.len() / 3)
+            .map(|i| i * 97 % (indices.len() / 3))
+            .flat_map(|t| indices[t * 3..t * 3 + 3].to_vec())
+            .collect();
+
+        let before = analyze_mesh(&scattered, &adapter, 12);
+        assert_eq!(before.triangle_count, indices.len() / 3);
+        assert_eq!(before.vertex_count, vertex_count);
+        assert_eq!(
+            before.vertex_cache.acmr,
+            analyze_vertex_cache(&scattered, vertex_count, 16, 0, 0).acmr
+        );
+        assert_eq!(
+            before.vertex_fetch.bytes_fetched,
+            analyze_vertex_fetch(&scattered, vertex_count, 12).bytes_fetched
+        );
+        assert_eq!(
+            before.overdraw.overdraw,
+            analyze_overdraw(&scattered, &adapter).overdraw
+        );
+        assert_eq!(
+            before.coverage.coverage,
+            analyze_coverage(&scattered, &adapter).coverage
+        );
+
+        scattered = optimize_vertex_cache(&scattered, vertex_count);
+        let after = analyze_mesh(&scattered, &adapter, 12);
+        let comparison = after.compare(&before);
+        assert!(comparison.acmr_change() < 0.0);
+        assert_eq!(comparison.overdraw_change(), 0.0);
+
+        let line = format!("{}", after);
+        assert!(line.starts_with("512 triangles, 289 vertices: ACMR "));
+        let line = format!("{}", comparison);
+        assert!(line.starts_with("512 -> 512 triangles, 289 -> 289 vertices: ACMR "));
+        assert!(line.contains("Overdraw 1.000000 -> 1.000000 (+0.0%)"));
+
+        let columns = MeshReport::csv_header().split(',').count();
+        let row = after.to_csv_row();
+        assert_eq!(row.split(',').count(), columns);
+        assert!(row.starts_with("512,289,"));
+    }
+
+    #[test]
+    fn test_validate_mesh_clean() {
+        let size = 4;
+        let vertices = grid_positions(size);
+        let adapter = VertexDataAdapter::new(typed_to_bytes(&vertices), 12, 0).unwrap();
+        let report = validate_mesh(&grid_indices(size), vertices.len(), Some(&adapter));
+        assert!(report.is_clean(), "{:?}", report);
+    }
+
+    #[test]
+    fn test_validate_mesh_index_errors() {
+        let indices = [0, 1, 2, 2, 1, 7, 9, 5, 3, 0, 1];
+        let report = validate_mesh(&indices, 6, None);
+        assert!(!report.is_clean());
+        assert_eq!(report.trailing_indices, 2);
+        assert_eq!(report.out_of_range_indices, 2);
+        assert_eq!(report.out_of_range_offenders, vec![(5, 7), (6, 9)]);
+        // vertex 4 is unused, 3 and 5 are only used by an invalid triangle
+        assert_eq!(report.unreferenced_vertices, 1);
+        assert_eq!(report.degenerate_triangles, 0);
+        assert_eq!(report.duplicate_triangles, 0);
+
+        let indices: Vec<u32> = (0..100).map(|_| 10).collect();
+        let report = validate_mesh(&indices[..99], 3, None);
+        assert_eq!(report.out_of_range_indices, 99);
+        assert_eq!(report.out_of_range_offenders.len(), MAX_REPORTED_OFFENDERS);
+        assert_eq!(report.unreferenced_vertices, 3);
+    }
+
+    #[test]
+    fn test_validate_mesh_triangle_errors() {
+        let vertices: [[f32; 3]; 5] = [
+            [0.0, 0.0, 0.0],
+            [1.0, 0.0, 0.0],
+            [0.0, 1.0, 0.0],
+            [2.0, 0.0, 0.0],
+            [0.0, 1e-6, 0.0],
+        ];
+        let adapter = VertexDataAdapter::new(typed_to_bytes(&vertices), 12, 0).unwrap();
+        let indices = [
+            0, 1, 2, // valid
+            2, 0, 1, // duplicate with the same winding
+            0, 2, 1, // duplicate with the opposite winding
+            0, 0, 1, // repeated index
+            0, 1, 3, // collinear
+            0, 3, 4, // sliver, but not zero area
+        ];
+
+        let report = validate_mesh(&indices, vertices.len(), Some(&adapter));
+        assert_eq!(report.duplicate_triangles, 2);
+        assert_eq!(report.degenerate_triangles, 1);
+        assert_eq!(report.zero_area_triangles, 1);
+        assert_eq!(report.unreferenced_vertices, 0);
+        assert_eq!(report.out_of_range_indices, 0);
+
+        let report = validate_mesh(&indices, vertices.len(), None);
+        assert_eq!(report.zero_area_triangles, 0);
+        assert_eq!(report.degenerate_triangles, 1);
+    }
+}
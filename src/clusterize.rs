@@ -1,16 +1,205 @@
 use crate::ffi;
-use crate::{DecodePosition, VertexDataAdapter};
+use crate::{DecodePosition, Error, Result, VertexDataAdapter};
+use alloc::{collections::BTreeMap, format, string::String, vec, vec::Vec};
+use core::{fmt, mem, ops::Range};
 
 pub type Bounds = ffi::meshopt_Bounds;
 
-#[derive(Copy, Clone)]
-pub struct Meshlet<'data> {
+impl Bounds {
+    /// Returns true if the cluster is guaranteed to be backfacing when viewed from `camera_position`
+    /// with a perspective projection, using the bounding sphere:
+    ///   `dot(center - camera_position, cone_axis) >= cone_cutoff * length(center - camera_position) + radius`
+    ///
+    /// Clusters with a degenerate cone (`cone_cutoff >= 1`) are never reported as backfacing.
+    pub fn is_backfacing(&self, camera_position: [f32; 3]) -> bool {
+        let offset = [
+            self.center[0] - camera_position[0],
+            self.center[1] - camera_position[1],
+            self.center[2] - camera_position[2],
+        ];
+        cone_test(offset, self.cone_axis, self.cone_cutoff, self.radius)
+    }
+
+    /// Like [`Bounds::is_backfacing`], but uses the cone apex instead of the bounding sphere:
+    ///   `dot(normalize(cone_apex - camera_position), cone_axis) >= cone_cutoff`
+    ///
+    /// This is slightly more accurate; a camera exactly at the apex is never backfacing.
+    pub fn is_backfacing_apex(&self, camera_position: [f32; 3]) -> bool {
+        let offset = [
+            self.cone_apex[0] - camera_position[0],
+            self.cone_apex[1] - camera_position[1],
+            self.cone_apex[2] - camera_position[2],
+        ];
+        if offset == [0f32; 3] {
+            return false;
+        }
+        cone_test(offset, self.cone_axis, self.cone_cutoff, 0f32)
+    }
+
+    /// Returns true if the bounding sphere is entirely outside of the frustum given by six
+    /// planes `[a, b, c, d]`, with points inside satisfying `a * x + b * y + c * z + d >= 0`.
+    ///
+    /// The plane normals `[a, b, c]` must be normalized; the sphere is only culled if it lies
+    /// completely behind one of the planes, so spheres near frustum corners may be kept.
+    pub fn is_outside_frustum(&self, frustum_planes: &[[f32; 4]; 6]) -> bool {
+        frustum_planes.iter().any(|plane| {
+            plane[0] * self.center[0]
+                + plane[1] * self.center[1]
+                + plane[2] * self.center[2]
+                + plane[3]
+                < -self.radius
+        })
+    }
+}
+
+/// Evaluates `dot(offset, axis) - radius >= cutoff * length(offset)` without a square root,
+/// so that it is available without `std`.
+fn cone_test(offset: [f32; 3], axis: [f32; 3], cutoff: f32, radius: f32) -> bool {
+    if cutoff >= 1f32 {
+        return false;
+    }
+    let lhs = offset[0] * axis[0] + offset[1] * axis[1] + offset[2] * axis[2] - radius;
+    let length_sq = offset[0] * offset[0] + offset[1] * offset[1] + offset[2] * offset[2];
+    let rhs_sq = cutoff * cutoff * length_sq;
+    if cutoff >= 0f32 {
+        lhs >= 0f32 && lhs * lhs >= rhs_sq
+    } else {
+        lhs >= 0f32 || lhs * lhs <= rhs_sq
+    }
+}
+
+/// A borrowed view of a single meshlet inside of `Meshlets`.
+#[derive(Debug, Copy, Clone)]
+pub struct MeshletRef<'data> {
+    /// Indices into the original vertex buffer, `vertex_count` entries.
     pub vertices: &'data [u32],
+    /// Micro index buffer indexing into `vertices`, `triangle_count * 3` entries.
     pub triangles: &'data [u8],
 }
 
+impl<'data> MeshletRef<'data> {
+    /// Iterates over the triangles of this meshlet as indices into the original vertex buffer.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a micro index is out of range of `vertices`, which only happens for corrupted
+    /// meshlet data; use [`MeshletRef::checked_global_triangles`] to handle that case instead.
+    pub fn global_triangles(&self) -> impl Iterator<Item = [u32; 3]> + 'data {
+        let vertices = self.vertices;
+        self.triangles.chunks_exact(3).map(move |triangle| {
+            [
+                vertices[triangle[0] as usize],
+                vertices[triangle[1] as usize],
+                vertices[triangle[2] as usize],
+            ]
+        })
+    }
+
+    /// Like [`MeshletRef::global_triangles`], but yields an error for triangles with a micro
+    /// index that is out of range of `vertices`.
+    pub fn checked_global_triangles(&self) -> impl Iterator<Item = Result<[u32; 3]>> + 'data {
+        let vertices = self.vertices;
+        self.triangles.chunks_exact(3).map(move |triangle| {
+            let vertex = |local: u8| {
+                vertices.get(local as usize).copied().ok_or_else(|| {
+                    Error::memory_dynamic(format!(
+                        "meshlet triangle index ({}) must be less than meshlet vertex count ({})",
+                        local,
+                        vertices.len()
+                    ))
+                })
+            };
+            Ok([
+                vertex(triangle[0])?,
+                vertex(triangle[1])?,
+                vertex(triangle[2])?,
+            ])
+        })
+    }
+}
+
+#[deprecated(note = "renamed to `MeshletRef`")]
+pub type Meshlet<'data> = MeshletRef<'data>;
+
+/// Describes where the data of a single meshlet lives inside of the shared `Meshlets` arrays.
+///
+/// This has the same layout as `ffi::meshopt_Meshlet`.
+#[repr(C)]
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct MeshletDescriptor {
+    /// Offset of the first vertex of the meshlet in `Meshlets::vertices`.
+    pub vertex_offset: u32,
+    /// Offset of the first micro index of the meshlet in `Meshlets::triangles`, in bytes.
+    pub triangle_offset: u32,
+    /// Number of vertices in the meshlet.
+    pub vertex_count: u32,
+    /// Number of triangles in the meshlet.
+    pub triangle_count: u32,
+}
+
+const _: () =
+    assert!(mem::size_of::<MeshletDescriptor>() == mem::size_of::<ffi::meshopt_Meshlet>());
+
+#[cfg(feature = "bytemuck")]
+unsafe impl bytemuck::Zeroable for MeshletDescriptor {}
+#[cfg(feature = "bytemuck")]
+unsafe impl bytemuck::Pod for MeshletDescriptor {}
+
+impl MeshletDescriptor {
+    /// Range of the meshlet's vertices in `Meshlets::vertices`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the end of the range overflows `usize`.
+    pub fn vertex_range(&self) -> Range<usize> {
+        let start = self.vertex_offset as usize;
+        let end = start
+            .checked_add(self.vertex_count as usize)
+            .expect("meshlet vertex range overflows");
+        start..end
+    }
+
+    /// Range of the meshlet's micro indices in `Meshlets::triangles`, 3 bytes per triangle.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the end of the range overflows `usize`.
+    pub fn triangle_byte_range(&self) -> Range<usize> {
+        let start = self.triangle_offset as usize;
+        let end = (self.triangle_count as usize)
+            .checked_mul(3)
+            .and_then(|len| start.checked_add(len))
+            .expect("meshlet triangle range overflows");
+        start..end
+    }
+}
+
+#[cfg(feature = "std")]
+const MESHLETS_MAGIC: [u8; 4] = *b"MSHL";
+#[cfg(feature = "std")]
+const MESHLETS_VERSION: u32 = 1;
+
+fn le_words(data: &[u8]) -> impl Iterator<Item = u32> + '_ {
+    data.chunks_exact(4)
+        .map(|word| u32::from_le_bytes([word[0], word[1], word[2], word[3]]))
+}
+
+fn parse_descriptors(data: &[u8]) -> Vec<MeshletDescriptor> {
+    le_words(data)
+        .collect::<Vec<_>>()
+        .chunks_exact(4)
+        .map(|m| MeshletDescriptor {
+            vertex_offset: m[0],
+            triangle_offset: m[1],
+            vertex_count: m[2],
+            triangle_count: m[3],
+        })
+        .collect()
+}
+
+#[derive(Debug, Clone)]
 pub struct Meshlets {
-    pub meshlets: Vec<ffi::meshopt_Meshlet>,
+    pub meshlets: Vec<MeshletDescriptor>,
     pub vertices: Vec<u32>,
     pub triangles: Vec<u8>,
 }
@@ -25,34 +214,690 @@ impl Meshlets {
         self.meshlets.is_empty()
     }
 
-    fn meshlet_from_ffi(&self, meshlet: &ffi::meshopt_Meshlet) -> Meshlet<'_> {
-        Meshlet {
-            vertices: &self.vertices[meshlet.vertex_offset as usize
-                ..meshlet.vertex_offset as usize + meshlet.vertex_count as usize],
-            triangles: &self.triangles[meshlet.triangle_offset as usize
-                ..meshlet.triangle_offset as usize + meshlet.triangle_count as usize * 3],
+    /// Takes the output of one of the meshlet builders, keeping the first `count` meshlets and
+    /// trimming the shared arrays to the data actually used by them.
+    fn from_raw_parts(
+        mut meshlets: Vec<MeshletDescriptor>,
+        mut vertices: Vec<u32>,
+        mut triangles: Vec<u8>,
+        count: usize,
+    ) -> Meshlets {
+        meshlets.truncate(count);
+        let (vertex_end, triangle_end) = meshlets.last().map_or((0, 0), |last| {
+            (last.vertex_range().end, last.triangle_byte_range().end)
+        });
+        vertices.truncate(vertex_end);
+        triangles.truncate(triangle_end);
+        Meshlets {
+            meshlets,
+            vertices,
+            triangles,
         }
     }
 
+    /// Moves the meshlets of `other` to the end of `self`, offsetting their vertex and
+    /// triangle ranges into the combined arrays.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the combined arrays are too large for `u32` offsets.
+    pub fn append(&mut self, other: Meshlets) {
+        let vertex_base = u32::try_from(self.vertices.len()).expect("too many meshlet vertices");
+        let triangle_base =
+            u32::try_from(self.triangles.len()).expect("too many meshlet triangles");
+        u32::try_from(self.vertices.len() + other.vertices.len())
+            .expect("too many meshlet vertices");
+        u32::try_from(self.triangles.len() + other.triangles.len())
+            .expect("too many meshlet triangles");
+        self.meshlets
+            .extend(other.meshlets.into_iter().map(|meshlet| MeshletDescriptor {
+                vertex_offset: meshlet.vertex_offset + vertex_base,
+                triangle_offset: meshlet.triangle_offset + triangle_base,
+                ..meshlet
+            }));
+        self.vertices.extend(other.vertices);
+        self.triangles.extend(other.triangles);
+    }
+
+    fn meshlet_ref(&self, meshlet: &MeshletDescriptor) -> MeshletRef<'_> {
+        MeshletRef {
+            vertices: self
+                .vertices
+                .get(meshlet.vertex_range())
+                .expect("meshlet vertex range must be within the vertex array"),
+            triangles: self
+                .triangles
+                .get(meshlet.triangle_byte_range())
+                .expect("meshlet triangle range must be within the triangle array"),
+        }
+    }
+
+    /// Returns the meshlet at `idx`, borrowing its sub-slices of the shared arrays.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `idx` is out of bounds, or if the meshlet's ranges are outside of the vertex
+    /// or triangle arrays; see [`Meshlets::try_get`].
     #[inline]
-    pub fn get(&self, idx: usize) -> Meshlet<'_> {
-        self.meshlet_from_ffi(&self.meshlets[idx])
+    pub fn get(&self, idx: usize) -> MeshletRef<'_> {
+        self.meshlet_ref(&self.meshlets[idx])
     }
 
-    pub fn iter(&self) -> impl Iterator<Item = Meshlet<'_>> {
+    /// Like [`Meshlets::get`], but returns an error instead of panicking if `idx` is out of
+    /// bounds or the meshlet's ranges are outside of the vertex or triangle arrays.
+    pub fn try_get(&self, idx: usize) -> Result<MeshletRef<'_>> {
+        let meshlet = self.meshlets.get(idx).ok_or_else(|| {
+            Error::memory_dynamic(format!(
+                "meshlet index ({}) must be less than the meshlet count ({})",
+                idx,
+                self.meshlets.len()
+            ))
+        })?;
+        let range = |offset: u32, len: usize| {
+            let start = offset as usize;
+            start..start.saturating_add(len)
+        };
+        let vertices = self
+            .vertices
+            .get(range(meshlet.vertex_offset, meshlet.vertex_count as usize))
+            .ok_or_else(|| Error::memory("meshlet vertex range must be within the vertex array"))?;
+        let triangles = self
+            .triangles
+            .get(range(
+                meshlet.triangle_offset,
+                meshlet.triangle_count as usize * 3,
+            ))
+            .ok_or_else(|| {
+                Error::memory("meshlet triangle range must be within the triangle array")
+            })?;
+        Ok(MeshletRef {
+            vertices,
+            triangles,
+        })
+    }
+
+    /// Iterates over all meshlets in order, borrowing their sub-slices of the shared arrays.
+    pub fn iter(&self) -> impl Iterator<Item = MeshletRef<'_>> {
         self.meshlets
             .iter()
-            .map(|meshlet| self.meshlet_from_ffi(meshlet))
+            .map(|meshlet| self.meshlet_ref(meshlet))
+    }
+
+    /// Flattens the meshlets back into a regular triangle list in meshlet order, with indices
+    /// into the original vertex buffer.
+    pub fn to_index_buffer(&self) -> Vec<u32> {
+        self.to_index_buffer_with_ranges().0
+    }
+
+    /// Like [`Meshlets::to_index_buffer`], but also returns the range of the index buffer
+    /// covered by each meshlet, so that draw calls can still be split per meshlet.
+    pub fn to_index_buffer_with_ranges(&self) -> (Vec<u32>, Vec<Range<usize>>) {
+        let index_count = self
+            .meshlets
+            .iter()
+            .map(|meshlet| meshlet.triangle_count as usize * 3)
+            .sum();
+        let mut indices = Vec::with_capacity(index_count);
+        let mut ranges = Vec::with_capacity(self.len());
+        for meshlet in self.iter() {
+            let start = indices.len();
+            indices.extend(meshlet.global_triangles().flatten());
+            ranges.push(start..indices.len());
+        }
+        (indices, ranges)
+    }
+
+    /// Returns the meshlet index of every triangle, in the flattened meshlet order of
+    /// [`Meshlets::to_index_buffer`]; useful for coloring triangles by meshlet when debugging.
+    ///
+    /// `original_index_count` is the length of the index buffer the meshlets were built from.
+    /// The result has one id per original triangle, so it can replace a per-triangle buffer of
+    /// the original mesh; the entries after the meshlet triangles, for triangles the builder
+    /// dropped, are `u32::MAX`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the meshlets contain more than `original_index_count / 3` triangles.
+    pub fn triangle_meshlet_ids(&self, original_index_count: usize) -> Vec<u32> {
+        let mut ids: Vec<u32> = self
+            .meshlets
+            .iter()
+            .enumerate()
+            .flat_map(|(i, meshlet)| {
+                core::iter::repeat_n(i as u32, meshlet.triangle_count as usize)
+            })
+            .collect();
+        assert!(
+            ids.len() <= original_index_count / 3,
+            "meshlet triangle count ({}) must not exceed the original triangle count ({})",
+            ids.len(),
+            original_index_count / 3
+        );
+        ids.resize(original_index_count / 3, u32::MAX);
+        ids
+    }
+
+    /// Returns the meshlet index of every triangle of the index buffer the meshlets were built from,
+    /// in the original triangle order.
+    ///
+    /// Triangles are matched by their vertices; triangles that are not part of any meshlet
+    /// (for example degenerate triangles) get `None`.
+    pub fn original_triangle_meshlet_ids(&self, indices: &[u32]) -> Vec<Option<u32>> {
+        fn key(triangle: [u32; 3]) -> [u32; 3] {
+            let mut key = triangle;
+            key.sort_unstable();
+            key
+        }
+
+        let mut lookup = BTreeMap::new();
+        for (i, meshlet) in self.iter().enumerate() {
+            for triangle in meshlet.global_triangles() {
+                lookup.entry(key(triangle)).or_insert(i as u32);
+            }
+        }
+        indices
+            .chunks_exact(3)
+            .map(|t| lookup.get(&key([t[0], t[1], t[2]])).copied())
+            .collect()
+    }
+
+    /// Runs `optimize_meshlet` on every meshlet, keeping each one within its own
+    /// sub-slices of the shared vertex and triangle arrays.
+    ///
+    /// Returns an error if a meshlet's ranges are out of bounds of the shared arrays, or if
+    /// `optimize_meshlet` rejects its data; meshlets before it have been optimized by then.
+    pub fn optimize_all(&mut self) -> Result<()> {
+        for meshlet in &self.meshlets {
+            let (Some(vertices), Some(triangles)) = (
+                self.vertices.get_mut(meshlet.vertex_range()),
+                self.triangles.get_mut(meshlet.triangle_byte_range()),
+            ) else {
+                return Err(Error::memory(
+                    "meshlet ranges must be within the vertex and triangle arrays",
+                ));
+            };
+            optimize_meshlet(
+                vertices,
+                triangles,
+                meshlet.triangle_count as usize,
+                meshlet.vertex_count as usize,
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Computes the bounds of every meshlet with `compute_meshlet_bounds`.
+    ///
+    /// The result is in meshlet order, so it can be uploaded directly next to the meshlet data.
+    ///
+    /// Returns an error if [`Meshlets::validate`] fails or if a vertex index is out of range
+    /// of `vertices`.
+    pub fn compute_bounds(&self, vertices: &VertexDataAdapter<'_>) -> Result<Vec<Bounds>> {
+        self.validate_vertices(vertices.vertex_count)?;
+        Ok(self
+            .iter()
+            .map(|meshlet| compute_meshlet_bounds(meshlet, vertices))
+            .collect())
+    }
+
+    // meshopt_computeMeshletBounds reads the positions of the vertices without checking them
+    fn validate_vertices(&self, vertex_count: usize) -> Result<()> {
+        self.validate()?;
+        crate::check_indices_in_range(&self.vertices, vertex_count)
+    }
+
+    /// Computes fill rate and culling statistics for the meshlets, for tuning the builder limits.
+    ///
+    /// `max_vertices` and `max_triangles` should be the limits the meshlets were built with.
+    /// `bounds`, if given, should contain the bounds of each meshlet, as returned by `compute_bounds`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `max_vertices` or `max_triangles` is zero, or if `bounds` is given and its
+    /// length doesn't match the number of meshlets.
+    pub fn analyze(
+        &self,
+        max_vertices: usize,
+        max_triangles: usize,
+        bounds: Option<&[Bounds]>,
+    ) -> MeshletStatistics {
+        assert!(
+            max_vertices > 0 && max_triangles > 0,
+            "max_vertices ({}) and max_triangles ({}) must be greater than 0",
+            max_vertices,
+            max_triangles
+        );
+        if let Some(bounds) = bounds {
+            assert_eq!(
+                bounds.len(),
+                self.len(),
+                "bounds must contain one entry per meshlet"
+            );
+        }
+        if self.is_empty() {
+            return MeshletStatistics {
+                cone_cullable: bounds.map(|_| 0f32),
+                ..Default::default()
+            };
+        }
+
+        let mut result = MeshletStatistics {
+            meshlet_count: self.len(),
+            vertex_fill_min: f32::MAX,
+            triangle_fill_min: f32::MAX,
+            ..Default::default()
+        };
+        for meshlet in &self.meshlets {
+            let vertex_fill = meshlet.vertex_count as f32 / max_vertices as f32;
+            let triangle_fill = meshlet.triangle_count as f32 / max_triangles as f32;
+            result.vertex_fill_avg += vertex_fill;
+            result.vertex_fill_min = result.vertex_fill_min.min(vertex_fill);
+            result.vertex_fill_max = result.vertex_fill_max.max(vertex_fill);
+            result.triangle_fill_avg += triangle_fill;
+            result.triangle_fill_min = result.triangle_fill_min.min(triangle_fill);
+            result.triangle_fill_max = result.triangle_fill_max.max(triangle_fill);
+            if meshlet.vertex_count > 0 {
+                result.vertex_reuse_avg +=
+                    (meshlet.triangle_count * 3) as f32 / meshlet.vertex_count as f32;
+            }
+        }
+        let count = self.len() as f32;
+        result.vertex_fill_avg /= count;
+        result.triangle_fill_avg /= count;
+        result.vertex_reuse_avg /= count;
+        result.cone_cullable = bounds
+            .map(|bounds| bounds.iter().filter(|b| b.cone_cutoff < 1f32).count() as f32 / count);
+        result
+    }
+
+    /// Packs the meshlets into contiguous buffers in a layout that is commonly used by mesh shaders.
+    ///
+    /// `bounds` should contain the bounds of each meshlet, as returned by `compute_bounds`.
+    /// See [`GpuMeshletBuffers`] for the layout of the resulting buffers.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the length of `bounds` doesn't match the number of meshlets.
+    pub fn pack_for_gpu(&self, bounds: &[Bounds]) -> GpuMeshletBuffers {
+        assert_eq!(
+            bounds.len(),
+            self.len(),
+            "bounds must contain one entry per meshlet"
+        );
+
+        let mut result = GpuMeshletBuffers {
+            meshlets: Vec::with_capacity(self.len()),
+            bounds: Vec::with_capacity(self.len()),
+            ..Default::default()
+        };
+
+        for (meshlet, bounds) in self.iter().zip(bounds) {
+            let triangle_count = meshlet.triangles.len() / 3;
+            result.meshlets.push(GpuMeshlet {
+                vertex_offset: result.vertices.len() as u32,
+                triangle_offset: result.triangles.len() as u32,
+                vertex_count: meshlet.vertices.len() as u16,
+                triangle_count: triangle_count as u16,
+            });
+            result.vertices.extend_from_slice(meshlet.vertices);
+            result.triangles.extend_from_slice(meshlet.triangles);
+            let padded_len = (result.triangles.len() + 3) & !3;
+            result.triangles.resize(padded_len, 0);
+
+            result.bounds.push(GpuMeshletBounds {
+                center: bounds.center,
+                radius: bounds.radius,
+                cone_axis: bounds.cone_axis_s8,
+                cone_cutoff: bounds.cone_cutoff_s8,
+            });
+        }
+
+        result
+    }
+
+    fn extend_descriptors_and_vertices(&self, data: &mut Vec<u8>) {
+        for meshlet in &self.meshlets {
+            data.extend_from_slice(&meshlet.vertex_offset.to_le_bytes());
+            data.extend_from_slice(&meshlet.triangle_offset.to_le_bytes());
+            data.extend_from_slice(&meshlet.vertex_count.to_le_bytes());
+            data.extend_from_slice(&meshlet.triangle_count.to_le_bytes());
+        }
+        for vertex in &self.vertices {
+            data.extend_from_slice(&vertex.to_le_bytes());
+        }
+    }
+
+    /// Checks that every meshlet lies within the vertex and triangle arrays, respects the
+    /// meshlet size limits (256 vertices, 512 triangles), and only references its own vertices.
+    ///
+    /// Meshlets built by this crate are always valid; this is useful after modifying the
+    /// public fields or loading the data from elsewhere, since [`Meshlets::get`] and
+    /// [`Meshlets::iter`] panic on out of bounds ranges.
+    pub fn validate(&self) -> Result<()> {
+        self.validate_with(Error::memory_dynamic)
+    }
+
+    // `read_from` and `unpack` report invalid meshlets as `Error::Parse` like the rest of
+    // their input errors
+    fn validate_with(&self, error: fn(String) -> Error) -> Result<()> {
+        for (i, meshlet) in self.meshlets.iter().enumerate() {
+            let invalid = |what: &str| Err(error(format!("meshlet {} has an invalid {}", i, what)));
+            if meshlet.vertex_count > 256 || meshlet.triangle_count > 512 {
+                return invalid("size");
+            }
+            let vertex_range = meshlet.vertex_offset as u64
+                ..meshlet.vertex_offset as u64 + meshlet.vertex_count as u64;
+            let triangle_range = meshlet.triangle_offset as u64
+                ..meshlet.triangle_offset as u64 + meshlet.triangle_count as u64 * 3;
+            if vertex_range.end > self.vertices.len() as u64 {
+                return invalid("vertex range");
+            }
+            if triangle_range.end > self.triangles.len() as u64 {
+                return invalid("triangle range");
+            }
+            let micro_indices =
+                &self.triangles[triangle_range.start as usize..triangle_range.end as usize];
+            if micro_indices
+                .iter()
+                .any(|&index| index as u32 >= meshlet.vertex_count)
+            {
+                return invalid("micro index");
+            }
+        }
+        Ok(())
+    }
+
+    /// Packs the meshlets into a single little-endian buffer that can be copied directly into
+    /// a GPU storage buffer; see [`PackedMeshlets`] for the layout.
+    pub fn pack(&self) -> PackedMeshlets {
+        let mut packed = PackedMeshlets {
+            data: Vec::new(),
+            meshlet_count: self.meshlets.len(),
+            vertex_count: self.vertices.len(),
+            triangle_byte_count: self.triangles.len(),
+        };
+        let mut data = Vec::with_capacity(packed.triangle_range().end.next_multiple_of(4));
+        self.extend_descriptors_and_vertices(&mut data);
+        data.extend_from_slice(&self.triangles);
+        data.resize(data.len().next_multiple_of(4), 0);
+        packed.data = data;
+        packed
+    }
+
+    /// Writes the meshlets in a compact little-endian binary format that can be read back with
+    /// [`Meshlets::read_from`].
+    ///
+    /// The format is the magic `MSHL`, a version, the meshlet, vertex and triangle byte counts
+    /// as `u32`, followed by the meshlet descriptors, the vertex indices and the micro indices.
+    #[cfg(feature = "std")]
+    pub fn write_to<W: std::io::Write>(&self, mut writer: W) -> Result<()> {
+        let count = |len: usize| {
+            u32::try_from(len)
+                .map_err(|_overflow| Error::memory("meshlet data is too large to serialize"))
+        };
+        let mut header = Vec::with_capacity(20);
+        header.extend_from_slice(&MESHLETS_MAGIC);
+        header.extend_from_slice(&MESHLETS_VERSION.to_le_bytes());
+        header.extend_from_slice(&count(self.meshlets.len())?.to_le_bytes());
+        header.extend_from_slice(&count(self.vertices.len())?.to_le_bytes());
+        header.extend_from_slice(&count(self.triangles.len())?.to_le_bytes());
+        writer.write_all(&header)?;
+
+        let mut data = Vec::with_capacity(self.meshlets.len() * 16 + self.vertices.len() * 4);
+        self.extend_descriptors_and_vertices(&mut data);
+        writer.write_all(&data)?;
+        writer.write_all(&self.triangles)?;
+        Ok(())
+    }
+
+    /// Reads meshlets written by [`Meshlets::write_to`].
+    ///
+    /// The data is validated before it is returned: every meshlet must lie within the vertex and
+    /// triangle arrays, respect the meshlet size limits, and only reference its own vertices.
+    /// Truncated or corrupted input results in an error.
+    #[cfg(feature = "std")]
+    pub fn read_from<R: std::io::Read>(mut reader: R) -> Result<Meshlets> {
+        // reads exactly `len` bytes without trusting `len` for the allocation up front
+        fn read_bytes<R: std::io::Read>(reader: &mut R, len: u64) -> Result<Vec<u8>> {
+            use std::io::Read as _;
+
+            let mut data = Vec::new();
+            reader.by_ref().take(len).read_to_end(&mut data)?;
+            if data.len() as u64 != len {
+                return Err(Error::Parse("meshlet data is truncated".into()));
+            }
+            Ok(data)
+        }
+        let header = read_bytes(&mut reader, 20)?;
+        if header[0..4] != MESHLETS_MAGIC {
+            return Err(Error::Parse("invalid meshlet data magic".into()));
+        }
+        let header: Vec<u32> = le_words(&header[4..]).collect();
+        if header[0] != MESHLETS_VERSION {
+            return Err(Error::Parse(format!(
+                "unsupported meshlet data version ({})",
+                header[0]
+            )));
+        }
+        let (meshlet_count, vertex_count, triangle_count) =
+            (header[1] as u64, header[2] as u64, header[3] as u64);
+
+        let meshlets = parse_descriptors(&read_bytes(&mut reader, meshlet_count * 16)?);
+        let vertices: Vec<u32> = le_words(&read_bytes(&mut reader, vertex_count * 4)?).collect();
+        let triangles = read_bytes(&mut reader, triangle_count)?;
+
+        let meshlets = Meshlets {
+            meshlets,
+            vertices,
+            triangles,
+        };
+        meshlets.validate_with(Error::Parse)?;
+        Ok(meshlets)
+    }
+
+    /// Parallel version of [`Meshlets::compute_bounds`], producing the same result in the same order.
+    #[cfg(feature = "rayon")]
+    pub fn par_compute_bounds(&self, vertices: &VertexDataAdapter<'_>) -> Result<Vec<Bounds>> {
+        use rayon::prelude::*;
+
+        self.validate_vertices(vertices.vertex_count)?;
+        Ok(self
+            .meshlets
+            .par_iter()
+            .map(|meshlet| compute_meshlet_bounds(self.meshlet_ref(meshlet), vertices))
+            .collect())
+    }
+}
+
+/// Statistics about the fill rate and culling efficiency of a set of meshlets,
+/// as returned by [`Meshlets::analyze`].
+///
+/// Fill ratios are relative to the `max_vertices` and `max_triangles` limits the meshlets
+/// were built with; all values are 0 for an empty set of meshlets.
+#[derive(Debug, Default, Copy, Clone, PartialEq)]
+pub struct MeshletStatistics {
+    pub meshlet_count: usize,
+
+    pub vertex_fill_avg: f32,
+    pub vertex_fill_min: f32,
+    pub vertex_fill_max: f32,
+
+    pub triangle_fill_avg: f32,
+    pub triangle_fill_min: f32,
+    pub triangle_fill_max: f32,
+
+    /// Average number of triangle corners referencing each meshlet vertex.
+    pub vertex_reuse_avg: f32,
+
+    /// Fraction of meshlets whose normal cone allows backface culling at all
+    /// (`cone_cutoff < 1`); `None` if no bounds were given.
+    pub cone_cullable: Option<f32>,
+}
+
+impl fmt::Display for MeshletStatistics {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} meshlets, vertex fill {:.1}% (min {:.1}%, max {:.1}%), triangle fill {:.1}% (min {:.1}%, max {:.1}%), vertex reuse {:.2}",
+            self.meshlet_count,
+            self.vertex_fill_avg * 100.0,
+            self.vertex_fill_min * 100.0,
+            self.vertex_fill_max * 100.0,
+            self.triangle_fill_avg * 100.0,
+            self.triangle_fill_min * 100.0,
+            self.triangle_fill_max * 100.0,
+            self.vertex_reuse_avg,
+        )?;
+        if let Some(cone_cullable) = self.cone_cullable {
+            write!(f, ", cone cullable {:.1}%", cone_cullable * 100.0)?;
+        }
+        Ok(())
+    }
+}
+
+/// GPU meshlet descriptor, as produced by [`Meshlets::pack_for_gpu`].
+///
+/// The layout is `#[repr(C)]` with no padding (12 bytes), so the descriptor array can be uploaded
+/// as a structured buffer directly.
+#[repr(C)]
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub struct GpuMeshlet {
+    /// Offset of the first vertex of the meshlet in `GpuMeshletBuffers::vertices`.
+    pub vertex_offset: u32,
+    /// Offset of the first micro index of the meshlet in `GpuMeshletBuffers::triangles`, in bytes;
+    /// always a multiple of 4.
+    pub triangle_offset: u32,
+    /// Number of vertices in the meshlet (<= 256).
+    pub vertex_count: u16,
+    /// Number of triangles in the meshlet (<= 512).
+    pub triangle_count: u16,
+}
+
+/// Per-meshlet culling data, as produced by [`Meshlets::pack_for_gpu`].
+///
+/// The layout is `#[repr(C)]` with no padding (20 bytes). The cone uses the 8-bit quantized
+/// `cone_axis_s8` and `cone_cutoff_s8` values of `Bounds`.
+#[repr(C)]
+#[derive(Debug, Default, Copy, Clone, PartialEq)]
+pub struct GpuMeshletBounds {
+    /// Center of the bounding sphere.
+    pub center: [f32; 3],
+    /// Radius of the bounding sphere.
+    pub radius: f32,
+    /// Normal cone axis, quantized to 8-bit SNORM.
+    pub cone_axis: [i8; 3],
+    /// Normal cone cutoff, quantized to 8-bit SNORM.
+    pub cone_cutoff: i8,
+}
+
+#[cfg(feature = "bytemuck")]
+unsafe impl bytemuck::Zeroable for GpuMeshlet {}
+#[cfg(feature = "bytemuck")]
+unsafe impl bytemuck::Pod for GpuMeshlet {}
+#[cfg(feature = "bytemuck")]
+unsafe impl bytemuck::Zeroable for GpuMeshletBounds {}
+#[cfg(feature = "bytemuck")]
+unsafe impl bytemuck::Pod for GpuMeshletBounds {}
+
+/// Meshlet data packed into a single buffer, as produced by [`Meshlets::pack`].
+///
+/// `data` holds, in order and without padding between sections:
+///
+/// - `meshlet_count` descriptors of 16 bytes each, with the [`MeshletDescriptor`] fields as
+///   little-endian `u32`s,
+/// - `vertex_count` little-endian `u32` indices into the original vertex buffer,
+/// - `triangle_byte_count` bytes of micro indices, zero-padded to a multiple of 4 bytes.
+///
+/// Every section starts at a multiple of 4 bytes, so the buffer can be read as `u32` words.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct PackedMeshlets {
+    pub data: Vec<u8>,
+    pub meshlet_count: usize,
+    pub vertex_count: usize,
+    pub triangle_byte_count: usize,
+}
+
+impl PackedMeshlets {
+    /// Byte range of the meshlet descriptors in `data`.
+    pub fn meshlet_range(&self) -> Range<usize> {
+        0..self.meshlet_count * 16
+    }
+
+    /// Byte range of the meshlet vertex indices in `data`.
+    pub fn vertex_range(&self) -> Range<usize> {
+        let start = self.meshlet_range().end;
+        start..start + self.vertex_count * 4
+    }
+
+    /// Byte range of the micro indices in `data`, excluding the trailing padding.
+    pub fn triangle_range(&self) -> Range<usize> {
+        let start = self.vertex_range().end;
+        start..start + self.triangle_byte_count
+    }
+
+    /// Unpacks the buffer into [`Meshlets`].
+    ///
+    /// Returns an error if `data` is shorter than the counts imply, or if a meshlet lies
+    /// outside the vertex or triangle sections, exceeds the meshlet size limits, or references
+    /// vertices outside of its own range.
+    pub fn unpack(&self) -> Result<Meshlets> {
+        let end = self
+            .meshlet_count
+            .checked_mul(16)
+            .zip(self.vertex_count.checked_mul(4))
+            .and_then(|(meshlets, vertices)| meshlets.checked_add(vertices))
+            .and_then(|len| len.checked_add(self.triangle_byte_count));
+        if end.is_none_or(|end| end > self.data.len()) {
+            return Err(Error::Parse("packed meshlet data is truncated".into()));
+        }
+
+        let meshlets = Meshlets {
+            meshlets: parse_descriptors(&self.data[self.meshlet_range()]),
+            vertices: le_words(&self.data[self.vertex_range()]).collect(),
+            triangles: self.data[self.triangle_range()].to_vec(),
+        };
+        meshlets.validate_with(Error::Parse)?;
+        Ok(meshlets)
     }
 }
 
+/// Contiguous meshlet buffers ready for GPU upload, as produced by [`Meshlets::pack_for_gpu`].
+#[derive(Debug, Default, Clone)]
+pub struct GpuMeshletBuffers {
+    /// One descriptor per meshlet, in meshlet order.
+    pub meshlets: Vec<GpuMeshlet>,
+    /// Indices into the original vertex buffer, referenced by `GpuMeshlet::vertex_offset`.
+    pub vertices: Vec<u32>,
+    /// Micro index buffer with 3 bytes per triangle indexing into the meshlet vertices; the
+    /// data of every meshlet is zero-padded to a multiple of 4 bytes, so it can also be read
+    /// as `u32` words.
+    pub triangles: Vec<u8>,
+    /// Culling data for each meshlet, in meshlet order.
+    pub bounds: Vec<GpuMeshletBounds>,
+}
+
+/// Maps meshlet ids (for example from [`Meshlets::triangle_meshlet_ids`]) to distinct RGB colors
+/// for visualization.
+pub fn meshlet_id_colors(ids: &[u32]) -> Vec<[u8; 3]> {
+    ids.iter()
+        .map(|&id| {
+            // cheap integer hash so that neighbouring ids get very different colors
+            let mut h = id.wrapping_mul(0x9e37_79b1);
+            h ^= h >> 15;
+            h = h.wrapping_mul(0x85eb_ca6b);
+            h ^= h >> 13;
+            [h as u8, (h >> 8) as u8, (h >> 16) as u8]
+        })
+        .collect()
+}
+
 /// Splits the mesh into a set of meshlets where each meshlet has a micro index buffer
 /// indexing into meshlet vertices that refer to the original vertex buffer.
 ///
 /// The resulting data can be used to render meshes using `NVidia programmable mesh shading`
 /// pipeline, or in other cluster-based renderers.
 ///
-/// Note: `max_vertices` must be <= 255 and `max_triangles` must be <= 512 and divisible by 4.
+/// Note: `max_vertices` must be <= 256 and `max_triangles` must be <= 512 and divisible by 4.
 pub fn build_meshlets(
     indices: &[u32],
     vertices: &VertexDataAdapter<'_>,
@@ -62,15 +907,14 @@ pub fn build_meshlets(
 ) -> Meshlets {
     let meshlet_count =
         unsafe { ffi::meshopt_buildMeshletsBound(indices.len(), max_vertices, max_triangles) };
-    let mut meshlets: Vec<ffi::meshopt_Meshlet> =
-        vec![unsafe { ::std::mem::zeroed() }; meshlet_count];
+    let mut meshlets: Vec<MeshletDescriptor> = vec![MeshletDescriptor::default(); meshlet_count];
 
     let mut meshlet_verts: Vec<u32> = vec![0; meshlet_count * max_vertices];
     let mut meshlet_tris: Vec<u8> = vec![0; meshlet_count * max_triangles * 3];
 
     let count = unsafe {
         ffi::meshopt_buildMeshlets(
-            meshlets.as_mut_ptr(),
+            meshlets.as_mut_ptr().cast(),
             meshlet_verts.as_mut_ptr(),
             meshlet_tris.as_mut_ptr(),
             indices.as_ptr(),
@@ -83,25 +927,309 @@ pub fn build_meshlets(
             cone_weight,
         )
     };
-    meshlets.truncate(count);
-
-    for i in 0..count {
-        let meshlet = &mut meshlets[i];
-        unsafe {
-            ffi::meshopt_optimizeMeshlet(
-                &mut meshlet_verts[meshlet.vertex_offset as usize],
-                &mut meshlet_tris[meshlet.triangle_offset as usize],
-                meshlet.triangle_count as usize,
-                meshlet.vertex_count as usize,
-            )
-        };
+    let mut meshlets = Meshlets::from_raw_parts(meshlets, meshlet_verts, meshlet_tris, count);
+    meshlets
+        .optimize_all()
+        .expect("meshlet builder output must be valid");
+    meshlets
+}
+
+/// Parallel version of [`build_meshlets`] for large meshes, which splits the index buffer into
+/// chunks of `chunk_triangles` triangles, builds the meshlets of each chunk on the rayon thread
+/// pool, and concatenates the results in chunk order.
+///
+/// Meshlets never cross chunk boundaries, so each chunk usually ends with a partially filled
+/// meshlet, and triangles that are adjacent in the mesh but in different chunks can't share a
+/// meshlet; use large chunks (tens of thousands of triangles) to keep the quality close to
+/// [`build_meshlets`]. The result only depends on `chunk_triangles`, not on the thread count.
+///
+/// # Panics
+///
+/// Panics if `chunk_triangles` is zero.
+#[cfg(feature = "rayon")]
+pub fn build_meshlets_par(
+    indices: &[u32],
+    vertices: &VertexDataAdapter<'_>,
+    max_vertices: usize,
+    max_triangles: usize,
+    cone_weight: f32,
+    chunk_triangles: usize,
+) -> Meshlets {
+    use rayon::prelude::*;
+
+    assert!(chunk_triangles > 0, "chunk_triangles must be non-zero");
+    let chunks: Vec<Meshlets> = indices
+        .par_chunks(chunk_triangles * 3)
+        .map(|chunk| build_meshlets(chunk, vertices, max_vertices, max_triangles, cone_weight))
+        .collect();
+    let mut result = Meshlets {
+        meshlets: Vec::new(),
+        vertices: Vec::new(),
+        triangles: Vec::new(),
+    };
+    for chunk in chunks {
+        result.append(chunk);
     }
+    result
+}
+
+/// Splits the mesh into a set of meshlets using a fast scan over the index buffer,
+/// without looking at vertex positions.
+///
+/// Produces the same `Meshlets` layout as `build_meshlets`, but only groups triangles
+/// that are adjacent in the index buffer; for maximum efficiency the index buffer being
+/// converted has to be optimized for vertex cache first. Unlike `build_meshlets`, the
+/// resulting meshlets are not optimized; call `Meshlets::optimize_all` if needed.
+///
+/// Returns an error if `max_vertices` is not in `3..=256`, if `max_triangles` is not a
+/// multiple of 4 in `4..=512`, if the index count is not a multiple of 3, or if any index is
+/// not less than `vertex_count`.
+pub fn build_meshlets_scan(
+    indices: &[u32],
+    vertex_count: usize,
+    max_vertices: usize,
+    max_triangles: usize,
+) -> Result<Meshlets> {
+    validate_meshlet_input(
+        indices,
+        vertex_count,
+        max_vertices,
+        max_triangles,
+        max_triangles,
+    )?;
+    let meshlet_count =
+        unsafe { ffi::meshopt_buildMeshletsBound(indices.len(), max_vertices, max_triangles) };
+    let mut meshlets: Vec<MeshletDescriptor> = vec![MeshletDescriptor::default(); meshlet_count];
+
+    let mut meshlet_verts: Vec<u32> = vec![0; meshlet_count * max_vertices];
+    let mut meshlet_tris: Vec<u8> = vec![0; meshlet_count * max_triangles * 3];
 
-    Meshlets {
+    let count = unsafe {
+        ffi::meshopt_buildMeshletsScan(
+            meshlets.as_mut_ptr().cast(),
+            meshlet_verts.as_mut_ptr(),
+            meshlet_tris.as_mut_ptr(),
+            indices.as_ptr(),
+            indices.len(),
+            vertex_count,
+            max_vertices,
+            max_triangles,
+        )
+    };
+    Ok(Meshlets::from_raw_parts(
         meshlets,
-        vertices: meshlet_verts,
-        triangles: meshlet_tris,
+        meshlet_verts,
+        meshlet_tris,
+        count,
+    ))
+}
+
+/// Splits the mesh into a set of meshlets, similarly to `build_meshlets`, but allows to specify
+/// the minimum and maximum number of triangles per meshlet.
+///
+/// Meshlets between `min_triangles` and `max_triangles` are split when the meshlet would have
+/// exceeded the expected size by more than `split_factor`; a value of 0 disables splitting.
+/// Meshes that are smaller than `min_triangles` still produce a single meshlet.
+///
+/// `cone_weight` should be between 0 and 1 to balance cluster size and cone culling efficiency,
+/// or negative to prioritize axis aligned clusters (for raytracing) instead.
+///
+/// Note: `max_vertices` must be <= 256, `min_triangles <= max_triangles <= 512`, and both
+/// triangle limits must be divisible by 4.
+pub fn build_meshlets_flex(
+    indices: &[u32],
+    vertices: &VertexDataAdapter<'_>,
+    max_vertices: usize,
+    min_triangles: usize,
+    max_triangles: usize,
+    cone_weight: f32,
+    split_factor: f32,
+) -> Result<Meshlets> {
+    validate_meshlet_input(
+        indices,
+        vertices.vertex_count,
+        max_vertices,
+        min_triangles,
+        max_triangles,
+    )?;
+    if cone_weight > 1f32 || split_factor < 0f32 {
+        return Err(Error::memory(
+            "cone_weight must be <= 1 and split_factor must be >= 0",
+        ));
+    }
+
+    // the worst case meshlet count is bounded by the minimum meshlet size
+    let meshlet_count =
+        unsafe { ffi::meshopt_buildMeshletsBound(indices.len(), max_vertices, min_triangles) };
+    let mut meshlets: Vec<MeshletDescriptor> = vec![MeshletDescriptor::default(); meshlet_count];
+
+    let mut meshlet_verts: Vec<u32> = vec![0; meshlet_count * max_vertices];
+    let mut meshlet_tris: Vec<u8> = vec![0; meshlet_count * max_triangles * 3];
+
+    let count = unsafe {
+        ffi::meshopt_buildMeshletsFlex(
+            meshlets.as_mut_ptr().cast(),
+            meshlet_verts.as_mut_ptr(),
+            meshlet_tris.as_mut_ptr(),
+            indices.as_ptr(),
+            indices.len(),
+            vertices.pos_ptr(),
+            vertices.vertex_count,
+            vertices.vertex_stride,
+            max_vertices,
+            min_triangles,
+            max_triangles,
+            cone_weight,
+            split_factor,
+        )
+    };
+    let mut meshlets = Meshlets::from_raw_parts(meshlets, meshlet_verts, meshlet_tris, count);
+    meshlets.optimize_all()?;
+    Ok(meshlets)
+}
+
+/// Splits the mesh into a set of meshlets, similarly to `build_meshlets`, but optimizes cluster
+/// subdivision for raytracing (spatial coherence) instead of rasterization and cone culling.
+///
+/// Like `build_meshlets_flex`, meshlets have between `min_triangles` and `max_triangles` triangles.
+/// `fill_weight` allows to prioritize clusters that are closer to maximum size at some cost to
+/// SAH quality; 0.5 is a safe default.
+///
+/// Note: `max_vertices` must be <= 256, `min_triangles <= max_triangles <= 512`, and both
+/// triangle limits must be divisible by 4.
+pub fn build_meshlets_spatial(
+    indices: &[u32],
+    vertices: &VertexDataAdapter<'_>,
+    max_vertices: usize,
+    min_triangles: usize,
+    max_triangles: usize,
+    fill_weight: f32,
+) -> Result<Meshlets> {
+    validate_meshlet_input(
+        indices,
+        vertices.vertex_count,
+        max_vertices,
+        min_triangles,
+        max_triangles,
+    )?;
+
+    // the worst case meshlet count is bounded by the minimum meshlet size
+    let meshlet_count =
+        unsafe { ffi::meshopt_buildMeshletsBound(indices.len(), max_vertices, min_triangles) };
+    let mut meshlets: Vec<MeshletDescriptor> = vec![MeshletDescriptor::default(); meshlet_count];
+
+    let mut meshlet_verts: Vec<u32> = vec![0; meshlet_count * max_vertices];
+    let mut meshlet_tris: Vec<u8> = vec![0; meshlet_count * max_triangles * 3];
+
+    let count = unsafe {
+        ffi::meshopt_buildMeshletsSpatial(
+            meshlets.as_mut_ptr().cast(),
+            meshlet_verts.as_mut_ptr(),
+            meshlet_tris.as_mut_ptr(),
+            indices.as_ptr(),
+            indices.len(),
+            vertices.pos_ptr(),
+            vertices.vertex_count,
+            vertices.vertex_stride,
+            max_vertices,
+            min_triangles,
+            max_triangles,
+            fill_weight,
+        )
+    };
+    let mut meshlets = Meshlets::from_raw_parts(meshlets, meshlet_verts, meshlet_tris, count);
+    meshlets.optimize_all()?;
+    Ok(meshlets)
+}
+
+fn validate_meshlet_input(
+    indices: &[u32],
+    vertex_count: usize,
+    max_vertices: usize,
+    min_triangles: usize,
+    max_triangles: usize,
+) -> Result<()> {
+    if !(3..=256).contains(&max_vertices) {
+        return Err(Error::memory_dynamic(format!(
+            "max_vertices ({}) must be in 3..=256",
+            max_vertices
+        )));
+    }
+    if min_triangles == 0 || min_triangles > max_triangles || max_triangles > 512 {
+        return Err(Error::memory_dynamic(format!(
+            "triangle limits must satisfy 0 < min_triangles ({}) <= max_triangles ({}) <= 512",
+            min_triangles, max_triangles
+        )));
+    }
+    if !min_triangles.is_multiple_of(4) || !max_triangles.is_multiple_of(4) {
+        return Err(Error::memory(
+            "min_triangles and max_triangles must be divisible by 4",
+        ));
+    }
+    if !indices.len().is_multiple_of(3) {
+        return Err(Error::memory("index count must be a multiple of 3"));
+    }
+    if let Some(&index) = indices
+        .iter()
+        .find(|&&index| index as usize >= vertex_count)
+    {
+        return Err(Error::memory_dynamic(format!(
+            "index ({}) must be less than the vertex count ({})",
+            index, vertex_count
+        )));
+    }
+
+    Ok(())
+}
+
+/// Reorders meshlet vertices and triangles to maximize locality to improve rasterizer throughput.
+///
+/// `meshlet_vertices` and `meshlet_triangles` must start at the data of a single meshlet
+/// (for example the sub-slices at `vertex_offset` and `triangle_offset` of a `Meshlets` buffer);
+/// only the first `vertex_count` vertices and `triangle_count * 3` triangle bytes are touched.
+///
+/// Returns an error if `triangle_count` is larger than 512, `vertex_count` is larger than 256,
+/// if the slices are too short to hold the given counts, or if a micro index is not less
+/// than `vertex_count`.
+pub fn optimize_meshlet(
+    meshlet_vertices: &mut [u32],
+    meshlet_triangles: &mut [u8],
+    triangle_count: usize,
+    vertex_count: usize,
+) -> Result<()> {
+    if triangle_count > 512 || vertex_count > 256 {
+        return Err(Error::memory_dynamic(format!(
+            "meshlet counts must satisfy triangle_count ({}) <= 512 and vertex_count ({}) <= 256",
+            triangle_count, vertex_count
+        )));
+    }
+    if meshlet_vertices.len() < vertex_count || meshlet_triangles.len() < triangle_count * 3 {
+        return Err(Error::memory_dynamic(format!(
+            "meshlet slices ({} vertices, {} micro indices) must hold {} vertices and {} triangles",
+            meshlet_vertices.len(),
+            meshlet_triangles.len(),
+            vertex_count,
+            triangle_count
+        )));
     }
+    if let Some(&index) = meshlet_triangles[..triangle_count * 3]
+        .iter()
+        .find(|&&index| usize::from(index) >= vertex_count)
+    {
+        return Err(Error::memory_dynamic(format!(
+            "micro index ({}) must be less than the meshlet vertex count ({})",
+            index, vertex_count
+        )));
+    }
+    unsafe {
+        ffi::meshopt_optimizeMeshlet(
+            meshlet_vertices.as_mut_ptr(),
+            meshlet_triangles.as_mut_ptr(),
+            triangle_count,
+            vertex_count,
+        );
+    }
+    Ok(())
 }
 
 /// Creates bounding volumes that can be used for frustum, backface and occlusion culling.
@@ -166,12 +1294,92 @@ pub fn compute_cluster_bounds_decoder<T: DecodePosition>(
             indices.len(),
             vertices.as_ptr().cast(),
             vertices.len() * 3,
-            ::std::mem::size_of::<f32>() * 3,
+            ::core::mem::size_of::<f32>() * 3,
         )
     }
 }
 
-pub fn compute_meshlet_bounds(meshlet: Meshlet<'_>, vertices: &VertexDataAdapter<'_>) -> Bounds {
+/// Like `compute_cluster_bounds`, but reads the vertex positions from a plain float array.
+///
+/// `positions` should have a float3 position in the first 12 bytes of each vertex, with
+/// `position_stride` bytes between vertices, as in `optimize_overdraw_positions`.
+///
+/// # Panics
+///
+/// Panics if the index count is not a multiple of 3 or exceeds 512 triangles, if
+/// `position_stride` is not a multiple of 4 in `12..=256`, or if any index is out of range
+/// of `positions`.
+pub fn compute_cluster_bounds_positions(
+    indices: &[u32],
+    positions: &[f32],
+    position_stride: usize,
+) -> Bounds {
+    assert!(
+        indices.len().is_multiple_of(3) && indices.len() <= 512 * 3,
+        "index count must be a multiple of 3 and at most 512 * 3"
+    );
+    crate::optimize::check_position_stride(position_stride);
+    let vertex_count = crate::optimize::position_count(positions, position_stride);
+    assert!(
+        indices.iter().all(|&index| (index as usize) < vertex_count),
+        "indices must be less than the vertex count"
+    );
+    unsafe {
+        ffi::meshopt_computeClusterBounds(
+            indices.as_ptr(),
+            indices.len(),
+            positions.as_ptr(),
+            vertex_count,
+            position_stride,
+        )
+    }
+}
+
+/// Creates a bounding sphere around a set of points, or a set of spheres when `radii` is given.
+///
+/// Returns the center and radius of the sphere, with other fields of the result set to 0.
+///
+/// `positions` should have a float3 position in the first 12 bytes of each element, with
+/// `position_stride` bytes between elements, as in `optimize_overdraw_positions`. `radii` can be `None`; otherwise it should have a
+/// non-negative radius for each element, with `radii_stride` bytes between elements.
+///
+/// # Panics
+///
+/// Panics if `position_stride` is not in `12..=256`, if `radii_stride` is not in `4..=256`
+/// when `radii` is given, if a stride is not a multiple of 4, or if `radii` is too short.
+pub fn compute_sphere_bounds(
+    positions: &[f32],
+    position_stride: usize,
+    radii: Option<&[f32]>,
+    radii_stride: usize,
+) -> Bounds {
+    assert!(
+        (12..=256).contains(&position_stride) && position_stride.is_multiple_of(4),
+        "position_stride must be a multiple of 4 in 12..=256"
+    );
+    let count = crate::optimize::position_count(positions, position_stride);
+    if let Some(radii) = radii {
+        assert!(
+            (4..=256).contains(&radii_stride) && radii_stride.is_multiple_of(4),
+            "radii_stride must be a multiple of 4 in 4..=256"
+        );
+        assert!(
+            count == 0 || mem::size_of_val(radii) >= (count - 1) * radii_stride + 4,
+            "radii must contain a radius for every position"
+        );
+    }
+    unsafe {
+        ffi::meshopt_computeSphereBounds(
+            positions.as_ptr(),
+            count,
+            position_stride,
+            radii.map_or(core::ptr::null(), <[f32]>::as_ptr),
+            radii_stride,
+        )
+    }
+}
+
+pub fn compute_meshlet_bounds(meshlet: MeshletRef<'_>, vertices: &VertexDataAdapter<'_>) -> Bounds {
     unsafe {
         ffi::meshopt_computeMeshletBounds(
             meshlet.vertices.as_ptr(),
@@ -185,7 +1393,7 @@ pub fn compute_meshlet_bounds(meshlet: Meshlet<'_>, vertices: &VertexDataAdapter
 }
 
 pub fn compute_meshlet_bounds_decoder<T: DecodePosition>(
-    meshlet: Meshlet<'_>,
+    meshlet: MeshletRef<'_>,
     vertices: &[T],
 ) -> Bounds {
     let vertices = vertices
@@ -199,7 +1407,960 @@ pub fn compute_meshlet_bounds_decoder<T: DecodePosition>(
             meshlet.triangles.len() / 3,
             vertices.as_ptr().cast(),
             vertices.len() * 3,
-            std::mem::size_of::<f32>() * 3,
+            core::mem::size_of::<f32>() * 3,
         )
     }
 }
+
+/// Partitions clusters into groups of similar size, prioritizing grouping clusters that share vertices.
+///
+/// `cluster_indices` should have the vertex indices referenced by each cluster, stored sequentially
+/// (for example the vertex lists of a set of meshlets), and `cluster_index_counts` should have the
+/// number of indices in each cluster.
+///
+/// `target_partition_size` is a target size for each partition, in clusters; the resulting partitions
+/// may be smaller or larger.
+///
+/// Returns the partition id for each cluster; partition ids are sequential starting from 0.
+///
+/// # Panics
+///
+/// Panics if `target_partition_size` is 0, if any cluster is empty, if the sum of
+/// `cluster_index_counts` is not equal to the length of `cluster_indices`, or if any index
+/// is out of range of `vertex_count`.
+pub fn partition_clusters(
+    cluster_indices: &[u32],
+    cluster_index_counts: &[u32],
+    vertex_count: usize,
+    target_partition_size: usize,
+) -> Vec<u32> {
+    assert!(
+        target_partition_size > 0,
+        "target_partition_size must be greater than 0"
+    );
+    assert!(
+        cluster_index_counts.iter().all(|&count| count > 0),
+        "clusters must not be empty"
+    );
+    assert_eq!(
+        cluster_index_counts
+            .iter()
+            .map(|&count| count as usize)
+            .sum::<usize>(),
+        cluster_indices.len(),
+        "sum of cluster_index_counts must be equal to the number of cluster indices"
+    );
+    assert!(
+        cluster_indices
+            .iter()
+            .all(|&index| (index as usize) < vertex_count),
+        "cluster indices must be less than vertex_count"
+    );
+
+    let mut partitions: Vec<u32> = vec![0; cluster_index_counts.len()];
+    unsafe {
+        ffi::meshopt_partitionClusters(
+            partitions.as_mut_ptr(),
+            cluster_indices.as_ptr(),
+            cluster_indices.len(),
+            cluster_index_counts.as_ptr(),
+            cluster_index_counts.len(),
+            core::ptr::null(),
+            vertex_count,
+            0,
+            target_partition_size,
+        );
+    }
+    partitions
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        any_as_u8_slice,
+        test_utils::{grid_indices, rotate_triangle, sorted_triangles},
+        typed_to_bytes, Vertex,
+    };
+
+    fn grid_mesh(size: u32) -> (Vec<Vertex>, Vec<u32>) {
+        let mut vertices = Vec::new();
+        for y in 0..=size {
+            for x in 0..=size {
+                vertices.push(Vertex {
+                    p: [x as f32, y as f32, ((x * y) as f32 * 0.1).sin()],
+                    n: [0.0, 0.0, 1.0],
+                    t: [0.0, 0.0],
+                });
+            }
+        }
+        (vertices, grid_indices(size))
+    }
+
+    fn global_indices(meshlet: MeshletRef<'_>) -> Vec<u32> {
+        meshlet.global_triangles().flatten().collect()
+    }
+
+    #[test]
+    fn test_optimize_all() {
+        let (vertices, indices) = grid_mesh(16);
+        let adapter =
+            VertexDataAdapter::new(typed_to_bytes(&vertices), core::mem::size_of::<Vertex>(), 0)
+                .unwrap();
+        let mut meshlets = build_meshlets(&indices, &adapter, 64, 124, 0.5);
+        // the builders trim the shared arrays, so add padding past the last meshlet by hand
+        meshlets.vertices.extend([u32::MAX; 8]);
+        meshlets.triangles.extend([0xAA; 24]);
+        let original_meshlets = meshlets.meshlets.clone();
+        let original_vertices = meshlets.vertices.clone();
+        let original_triangles = meshlets.triangles.clone();
+
+        // scramble each meshlet's triangle order so there is something to optimize
+        for meshlet in &meshlets.meshlets {
+            let start = meshlet.triangle_offset as usize;
+            let end = start + meshlet.triangle_count as usize * 3;
+            let triangles = &mut meshlets.triangles[start..end];
+            let count = triangles.len() / 3;
+            for i in 0..count / 2 {
+                for k in 0..3 {
+                    triangles.swap(i * 3 + k, (count - 1 - i) * 3 + k);
+                }
+            }
+        }
+
+        meshlets.optimize_all().unwrap();
+
+        assert_eq!(original_meshlets.len(), meshlets.meshlets.len());
+        for (i, meshlet) in original_meshlets.iter().enumerate() {
+            let before = Meshlets {
+                meshlets: vec![*meshlet],
+                vertices: original_vertices.clone(),
+                triangles: original_triangles.clone(),
+            };
+            let expected = sorted_triangles(&global_indices(before.get(0)));
+            let actual = sorted_triangles(&global_indices(meshlets.get(i)));
+            assert_eq!(expected, actual);
+
+            let mut expected_vertices = before.get(0).vertices.to_vec();
+            let mut actual_vertices = meshlets.get(i).vertices.to_vec();
+            expected_vertices.sort_unstable();
+            actual_vertices.sort_unstable();
+            assert_eq!(expected_vertices, actual_vertices);
+        }
+
+        // data outside of the meshlets (the padding added above) is left alone
+        let last = meshlets.meshlets.last().unwrap();
+        assert_eq!(
+            &meshlets.vertices[last.vertex_range().end..],
+            &[u32::MAX; 8]
+        );
+        assert_eq!(
+            &meshlets.triangles[last.triangle_byte_range().end..],
+            &[0xAA; 24]
+        );
+    }
+
+    #[test]
+    fn test_meshlet_descriptor_layout() {
+        use memoffset::offset_of;
+
+        assert_eq!(
+            core::mem::size_of::<MeshletDescriptor>(),
+            core::mem::size_of::<ffi::meshopt_Meshlet>()
+        );
+        assert_eq!(
+            core::mem::align_of::<MeshletDescriptor>(),
+            core::mem::align_of::<ffi::meshopt_Meshlet>()
+        );
+        assert_eq!(
+            offset_of!(MeshletDescriptor, vertex_offset),
+            offset_of!(ffi::meshopt_Meshlet, vertex_offset)
+        );
+        assert_eq!(
+            offset_of!(MeshletDescriptor, triangle_offset),
+            offset_of!(ffi::meshopt_Meshlet, triangle_offset)
+        );
+        assert_eq!(
+            offset_of!(MeshletDescriptor, vertex_count),
+            offset_of!(ffi::meshopt_Meshlet, vertex_count)
+        );
+        assert_eq!(
+            offset_of!(MeshletDescriptor, triangle_count),
+            offset_of!(ffi::meshopt_Meshlet, triangle_count)
+        );
+    }
+
+    #[test]
+    fn test_optimize_meshlet_rejects_invalid_input() {
+        let mut vertices = [10u32, 11, 12, 13];
+        let mut triangles = [0u8, 1, 2, 1, 3, 2];
+        assert!(optimize_meshlet(&mut vertices, &mut triangles, 2, 4).is_ok());
+
+        // micro index 3 is out of range of a 3 vertex meshlet
+        assert!(optimize_meshlet(&mut vertices, &mut triangles, 2, 3).is_err());
+        assert!(optimize_meshlet(&mut vertices, &mut triangles, 3, 4).is_err());
+        assert!(optimize_meshlet(&mut vertices, &mut triangles, 2, 5).is_err());
+        assert!(optimize_meshlet(&mut vertices, &mut triangles, 513, 4).is_err());
+
+        let mut meshlets = Meshlets {
+            meshlets: vec![MeshletDescriptor {
+                vertex_offset: 2,
+                triangle_offset: 0,
+                vertex_count: 4,
+                triangle_count: 2,
+            }],
+            vertices: vertices.to_vec(),
+            triangles: triangles.to_vec(),
+        };
+        assert!(meshlets.optimize_all().is_err());
+    }
+
+    #[test]
+    fn test_build_meshlets_scan_coverage() {
+        let (vertices, indices) = grid_mesh(20);
+        let adapter =
+            VertexDataAdapter::new(typed_to_bytes(&vertices), core::mem::size_of::<Vertex>(), 0)
+                .unwrap();
+
+        let expected = sorted_triangles(&indices);
+
+        for meshlets in [
+            build_meshlets(&indices, &adapter, 64, 124, 0.0),
+            build_meshlets_scan(&indices, vertices.len(), 64, 124).unwrap(),
+        ] {
+            let actual = sorted_triangles(&meshlets.to_index_buffer());
+            assert_eq!(expected, actual);
+        }
+    }
+
+    #[test]
+    fn test_build_meshlets_scan_rejects_invalid_input() {
+        let (vertices, indices) = grid_mesh(4);
+        let vertex_count = vertices.len();
+        assert!(build_meshlets_scan(&indices, vertex_count, 2, 124).is_err());
+        assert!(build_meshlets_scan(&indices, vertex_count, 257, 124).is_err());
+        assert!(build_meshlets_scan(&indices, vertex_count, 64, 0).is_err());
+        assert!(build_meshlets_scan(&indices, vertex_count, 64, 126).is_err());
+        assert!(build_meshlets_scan(&indices, vertex_count, 64, 516).is_err());
+        assert!(build_meshlets_scan(&indices[..4], vertex_count, 64, 124).is_err());
+        assert!(build_meshlets_scan(&indices, vertex_count - 1, 64, 124).is_err());
+        assert!(build_meshlets_scan(&[], 0, 64, 124).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_build_meshlets_flex() {
+        let (vertices, indices) = grid_mesh(20);
+        let adapter =
+            VertexDataAdapter::new(typed_to_bytes(&vertices), core::mem::size_of::<Vertex>(), 0)
+                .unwrap();
+
+        let meshlets = build_meshlets_flex(&indices, &adapter, 64, 32, 124, 0.25, 2.0).unwrap();
+        let expected = sorted_triangles(&indices);
+        let actual = sorted_triangles(&meshlets.to_index_buffer());
+        assert_eq!(expected, actual);
+        assert!(meshlets
+            .meshlets
+            .iter()
+            .all(|m| m.vertex_count <= 64 && m.triangle_count <= 124));
+
+        build_meshlets_flex(&indices, &adapter, 64, 128, 124, 0.25, 2.0).expect_err("min > max");
+        build_meshlets_flex(&indices, &adapter, 64, 30, 124, 0.25, 2.0).expect_err("min % 4");
+        build_meshlets_flex(&indices, &adapter, 300, 32, 124, 0.25, 2.0).expect_err("max verts");
+    }
+
+    #[test]
+    fn test_build_meshlets_flex_small_mesh() {
+        let (vertices, indices) = grid_mesh(2);
+        let adapter =
+            VertexDataAdapter::new(typed_to_bytes(&vertices), core::mem::size_of::<Vertex>(), 0)
+                .unwrap();
+
+        // the whole mesh (8 triangles) is below min_triangles but still forms a meshlet
+        let meshlets = build_meshlets_flex(&indices, &adapter, 64, 32, 64, 0.0, 0.0).unwrap();
+        assert_eq!(meshlets.len(), 1);
+        assert_eq!(
+            meshlets.meshlets[0].triangle_count as usize,
+            indices.len() / 3
+        );
+    }
+
+    #[test]
+    fn test_meshlet_ref() {
+        let (vertices, indices) = grid_mesh(12);
+        let adapter =
+            VertexDataAdapter::new(typed_to_bytes(&vertices), core::mem::size_of::<Vertex>(), 0)
+                .unwrap();
+        let meshlets = build_meshlets(&indices, &adapter, 32, 64, 0.0);
+
+        assert_eq!(meshlets.iter().count(), meshlets.len());
+        for (meshlet, desc) in meshlets.iter().zip(&meshlets.meshlets) {
+            assert_eq!(meshlet.vertices.len(), desc.vertex_count as usize);
+            assert_eq!(meshlet.triangles.len(), desc.triangle_count as usize * 3);
+            assert!(meshlet
+                .triangles
+                .iter()
+                .all(|&t| (t as usize) < meshlet.vertices.len()));
+        }
+
+        let expected = sorted_triangles(&indices);
+        let actual = sorted_triangles(&meshlets.to_index_buffer());
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn test_partition_clusters_separated() {
+        // two disconnected grids, each split into one cluster per row
+        let (vertices, indices) = grid_mesh(8);
+        let offset = vertices.len() as u32;
+        let mut cluster_indices = indices.clone();
+        cluster_indices.extend(indices.iter().map(|&i| i + offset));
+        let row_indices = 8 * 6;
+        let cluster_index_counts = vec![row_indices; cluster_indices.len() / row_indices as usize];
+
+        let partitions = partition_clusters(
+            &cluster_indices,
+            &cluster_index_counts,
+            vertices.len() * 2,
+            4,
+        );
+        assert_eq!(partitions.len(), cluster_index_counts.len());
+
+        let (first, second) = partitions.split_at(partitions.len() / 2);
+        assert!(first.iter().all(|p| !second.contains(p)));
+        let partition_count = partitions.iter().max().unwrap() + 1;
+        assert!(partition_count >= 2);
+        assert!((0..partition_count).all(|p| partitions.contains(&p)));
+    }
+
+    #[test]
+    fn test_checked_global_triangles() {
+        let vertices = [10, 20, 30];
+        let triangles = [0, 1, 2, 2, 1, 0];
+        let meshlet = MeshletRef {
+            vertices: &vertices,
+            triangles: &triangles,
+        };
+        let checked = meshlet
+            .checked_global_triangles()
+            .collect::<Result<Vec<_>>>()
+            .unwrap();
+        assert_eq!(checked, vec![[10, 20, 30], [30, 20, 10]]);
+        assert_eq!(meshlet.global_triangles().collect::<Vec<_>>(), checked);
+
+        let corrupted = [0, 1, 3];
+        let meshlet = MeshletRef {
+            vertices: &vertices,
+            triangles: &corrupted,
+        };
+        assert!(meshlet.checked_global_triangles().next().unwrap().is_err());
+    }
+
+    #[test]
+    fn test_compute_bounds() {
+        let (vertices, indices) = grid_mesh(16);
+        let adapter =
+            VertexDataAdapter::new(typed_to_bytes(&vertices), core::mem::size_of::<Vertex>(), 0)
+                .unwrap();
+        let meshlets = build_meshlets(&indices, &adapter, 32, 64, 0.5);
+
+        let bounds = meshlets.compute_bounds(&adapter).unwrap();
+        assert_eq!(bounds.len(), meshlets.len());
+        for (meshlet, bounds) in meshlets.iter().zip(&bounds) {
+            let expected = compute_meshlet_bounds(meshlet, &adapter);
+            assert_eq!(any_as_u8_slice(bounds), any_as_u8_slice(&expected));
+        }
+
+        #[cfg(feature = "rayon")]
+        {
+            let par_bounds = meshlets.par_compute_bounds(&adapter).unwrap();
+            assert_eq!(typed_to_bytes(&par_bounds), typed_to_bytes(&bounds));
+        }
+    }
+
+    #[test]
+    fn test_compute_bounds_invalid() {
+        let (vertices, indices) = grid_mesh(16);
+        let adapter =
+            VertexDataAdapter::new(typed_to_bytes(&vertices), core::mem::size_of::<Vertex>(), 0)
+                .unwrap();
+        let meshlets = build_meshlets(&indices, &adapter, 32, 64, 0.5);
+
+        let mut out_of_range = meshlets.clone();
+        out_of_range.vertices[0] = vertices.len() as u32;
+        let mut bad_micro_index = meshlets.clone();
+        bad_micro_index.triangles[0] = u8::MAX;
+        for invalid in [out_of_range, bad_micro_index] {
+            assert!(invalid.compute_bounds(&adapter).is_err());
+            #[cfg(feature = "rayon")]
+            assert!(invalid.par_compute_bounds(&adapter).is_err());
+        }
+    }
+
+    #[test]
+    fn test_compute_cluster_bounds_positions() {
+        let (vertices, indices) = grid_mesh(8);
+        let adapter =
+            VertexDataAdapter::new(typed_to_bytes(&vertices), core::mem::size_of::<Vertex>(), 0)
+                .unwrap();
+        let interleaved: Vec<f32> = vertices
+            .iter()
+            .flat_map(|v| v.p.iter().chain(&v.n).chain(&v.t).copied())
+            .collect();
+        let positions: Vec<f32> = vertices.iter().flat_map(|v| v.p).collect();
+
+        let cluster = &indices[..96];
+        let expected = compute_cluster_bounds(cluster, &adapter);
+        let deinterleaved = compute_cluster_bounds_positions(cluster, &positions, 12);
+        assert_eq!(any_as_u8_slice(&deinterleaved), any_as_u8_slice(&expected));
+        let strided = compute_cluster_bounds_positions(cluster, &interleaved, 32);
+        assert_eq!(any_as_u8_slice(&strided), any_as_u8_slice(&expected));
+    }
+
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn test_build_meshlets_par() {
+        let (vertices, indices) = grid_mesh(32);
+        let adapter =
+            VertexDataAdapter::new(typed_to_bytes(&vertices), core::mem::size_of::<Vertex>(), 0)
+                .unwrap();
+
+        let meshlets = build_meshlets_par(&indices, &adapter, 64, 124, 0.5, 300);
+        assert!(meshlets.validate().is_ok());
+        let expected = sorted_triangles(&indices);
+        let actual = sorted_triangles(&meshlets.to_index_buffer());
+        assert_eq!(expected, actual);
+
+        // chunk boundaries cost a few meshlets compared to the single threaded version
+        let single = build_meshlets(&indices, &adapter, 64, 124, 0.5);
+        assert!(meshlets.len() >= single.len());
+
+        // with a single chunk the result matches exactly
+        let whole = build_meshlets_par(&indices, &adapter, 64, 124, 0.5, indices.len() / 3);
+        assert_eq!(whole.meshlets.len(), single.meshlets.len());
+        assert_eq!(whole.vertices, single.vertices);
+        assert_eq!(whole.triangles, single.triangles);
+    }
+
+    #[test]
+    fn test_build_meshlets_spatial() {
+        let (vertices, indices) = grid_mesh(16);
+        // scatter the triangles so that index order carries no spatial locality
+        let triangle_count = indices.len() / 3;
+        let scattered: Vec<u32> = (0..triangle_count)
+            .flat_map(|i| {
+                let t = (i * 7919) % triangle_count;
+                indices[t * 3..t * 3 + 3].to_vec()
+            })
+            .collect();
+        let adapter =
+            VertexDataAdapter::new(typed_to_bytes(&vertices), core::mem::size_of::<Vertex>(), 0)
+                .unwrap();
+
+        let meshlets = build_meshlets_spatial(&scattered, &adapter, 64, 16, 64, 0.5).unwrap();
+        let expected = sorted_triangles(&scattered);
+        let actual = sorted_triangles(&meshlets.to_index_buffer());
+        assert_eq!(expected, actual);
+
+        let volume = |meshlets: &Meshlets| -> f32 {
+            meshlets
+                .iter()
+                .map(|meshlet| {
+                    let mut min = [f32::MAX; 3];
+                    let mut max = [f32::MIN; 3];
+                    for &v in meshlet.vertices {
+                        for axis in 0..3 {
+                            min[axis] = min[axis].min(vertices[v as usize].p[axis]);
+                            max[axis] = max[axis].max(vertices[v as usize].p[axis]);
+                        }
+                    }
+                    (0..3).map(|axis| max[axis] - min[axis]).product::<f32>()
+                })
+                .sum()
+        };
+        let scan = build_meshlets_scan(&scattered, vertices.len(), 64, 64).unwrap();
+        assert!(volume(&meshlets) < volume(&scan));
+
+        assert!(build_meshlets_spatial(&scattered, &adapter, 64, 15, 64, 0.5).is_err());
+    }
+
+    #[test]
+    fn test_pack_for_gpu() {
+        let (vertices, indices) = grid_mesh(16);
+        let adapter =
+            VertexDataAdapter::new(typed_to_bytes(&vertices), core::mem::size_of::<Vertex>(), 0)
+                .unwrap();
+        let meshlets = build_meshlets(&indices, &adapter, 64, 124, 0.5);
+        let bounds = meshlets.compute_bounds(&adapter).unwrap();
+        let packed = meshlets.pack_for_gpu(&bounds);
+
+        assert_eq!(core::mem::size_of::<GpuMeshlet>(), 12);
+        assert_eq!(core::mem::size_of::<GpuMeshletBounds>(), 20);
+        assert_eq!(packed.meshlets.len(), meshlets.len());
+        assert_eq!(packed.bounds.len(), meshlets.len());
+        assert!(packed.triangles.len().is_multiple_of(4));
+
+        for (i, (meshlet, gpu)) in meshlets.iter().zip(&packed.meshlets).enumerate() {
+            let vertex_offset = gpu.vertex_offset as usize;
+            let triangle_offset = gpu.triangle_offset as usize;
+            assert!(triangle_offset.is_multiple_of(4));
+            assert_eq!(
+                &packed.vertices[vertex_offset..vertex_offset + gpu.vertex_count as usize],
+                meshlet.vertices
+            );
+            assert_eq!(
+                &packed.triangles
+                    [triangle_offset..triangle_offset + gpu.triangle_count as usize * 3],
+                meshlet.triangles
+            );
+            assert_eq!(packed.bounds[i].center, bounds[i].center);
+            assert_eq!(packed.bounds[i].cone_cutoff, bounds[i].cone_cutoff_s8);
+        }
+    }
+
+    #[test]
+    fn test_build_meshlets_flex_fill() {
+        // a closed uv sphere, 64 rings of 128 segments
+        let (rings, segments) = (64, 128);
+        let mut vertices = Vec::new();
+        for ring in 0..=rings {
+            let theta = ring as f32 / rings as f32 * core::f32::consts::PI;
+            for segment in 0..=segments {
+                let phi = segment as f32 / segments as f32 * core::f32::consts::TAU;
+                vertices.push(Vertex {
+                    p: [
+                        theta.sin() * phi.cos(),
+                        theta.cos(),
+                        theta.sin() * phi.sin(),
+                    ],
+                    n: [0.0; 3],
+                    t: [0.0; 2],
+                });
+            }
+        }
+        let indices: Vec<u32> = (0..rings)
+            .flat_map(|ring| (0..segments).map(move |segment| ring * (segments + 1) + segment))
+            .flat_map(|i| {
+                let j = i + segments + 1;
+                [i, i + 1, j, i + 1, j + 1, j]
+            })
+            .collect();
+        let adapter =
+            VertexDataAdapter::new(typed_to_bytes(&vertices), core::mem::size_of::<Vertex>(), 0)
+                .unwrap();
+        let triangle_counts = |meshlets: &Meshlets| {
+            meshlets
+                .meshlets
+                .iter()
+                .map(|meshlet| meshlet.triangle_count)
+                .collect::<Vec<_>>()
+        };
+
+        // with the same limits, the flexible builder may also end a meshlet once it has
+        // min_triangles if the nearest remaining triangle is far away; on this mesh that
+        // leaves fewer partially filled meshlets than the fixed builder
+        let fixed = build_meshlets(&indices, &adapter, 32, 64, 0.5);
+        let flex = build_meshlets_flex(&indices, &adapter, 32, 4, 64, 0.5, 2.0).unwrap();
+        assert_ne!(triangle_counts(&flex), triangle_counts(&fixed));
+        assert!(flex.len() <= fixed.len());
+
+        // without splitting, the flexible builder is the fixed one
+        let unsplit = build_meshlets_flex(&indices, &adapter, 32, 4, 64, 0.5, 0.0).unwrap();
+        assert_eq!(triangle_counts(&unsplit), triangle_counts(&fixed));
+    }
+
+    #[test]
+    fn test_meshlet_ranges() {
+        let (vertices, indices) = grid_mesh(16);
+        let adapter =
+            VertexDataAdapter::new(typed_to_bytes(&vertices), core::mem::size_of::<Vertex>(), 0)
+                .unwrap();
+
+        for meshlets in [
+            build_meshlets(&indices, &adapter, 64, 124, 0.5),
+            build_meshlets_scan(&indices, vertices.len(), 64, 124).unwrap(),
+            build_meshlets_flex(&indices, &adapter, 64, 32, 128, 0.5, 2.0).unwrap(),
+            build_meshlets_spatial(&indices, &adapter, 64, 32, 128, 0.5).unwrap(),
+        ] {
+            // the shared arrays are trimmed to exactly the data of the last meshlet
+            let last = meshlets.meshlets.last().unwrap();
+            assert_eq!(last.vertex_range().end, meshlets.vertices.len());
+            assert_eq!(last.triangle_byte_range().end, meshlets.triangles.len());
+
+            for (meshlet, meshlet_ref) in meshlets.meshlets.iter().zip(meshlets.iter()) {
+                assert_eq!(meshlet.vertex_range().len(), meshlet_ref.vertices.len());
+                assert_eq!(
+                    meshlet.triangle_byte_range().len(),
+                    meshlet_ref.triangles.len()
+                );
+                assert_eq!(
+                    meshlet.triangle_byte_range().len(),
+                    meshlet.triangle_count as usize * 3
+                );
+            }
+        }
+
+        let empty = build_meshlets(&[], &adapter, 64, 124, 0.5);
+        assert!(empty.is_empty());
+        assert!(empty.vertices.is_empty());
+        assert!(empty.triangles.is_empty());
+    }
+
+    #[test]
+    fn test_partition_clusters_shared_vertices() {
+        // two single-quad clusters sharing the edge 1-2
+        let cluster_indices = [0, 1, 2, 3, 1, 2, 4, 5];
+        let cluster_index_counts = [4, 4];
+        let partitions = partition_clusters(&cluster_indices, &cluster_index_counts, 6, 2);
+        assert_eq!(partitions, vec![0, 0]);
+    }
+
+    #[test]
+    fn test_to_index_buffer() {
+        let (vertices, indices) = grid_mesh(16);
+        let adapter =
+            VertexDataAdapter::new(typed_to_bytes(&vertices), core::mem::size_of::<Vertex>(), 0)
+                .unwrap();
+        let meshlets = build_meshlets(&indices, &adapter, 64, 124, 0.5);
+
+        let sorted = |indices: &[u32]| {
+            let mut triangles: Vec<[u32; 3]> = indices
+                .chunks(3)
+                .map(|t| {
+                    let mut t = [t[0], t[1], t[2]];
+                    t.sort_unstable();
+                    t
+                })
+                .collect();
+            triangles.sort_unstable();
+            triangles
+        };
+        let flattened = meshlets.to_index_buffer();
+        assert_eq!(sorted(&flattened), sorted(&indices));
+
+        let (with_ranges, ranges) = meshlets.to_index_buffer_with_ranges();
+        assert_eq!(with_ranges, flattened);
+        assert_eq!(ranges.len(), meshlets.len());
+        assert_eq!(ranges.last().unwrap().end, flattened.len());
+        for (range, meshlet) in ranges.iter().zip(meshlets.iter()) {
+            assert_eq!(range.len(), meshlet.triangles.len());
+        }
+    }
+
+    #[test]
+    fn test_compute_sphere_bounds() {
+        let corners: Vec<f32> = (0..8)
+            .flat_map(|i| {
+                [
+                    (i & 1) as f32 * 2.0,
+                    ((i >> 1) & 1) as f32 * 2.0,
+                    ((i >> 2) & 1) as f32 * 2.0,
+                ]
+            })
+            .collect();
+        let bounds = compute_sphere_bounds(&corners, 12, None, 0);
+        for axis in 0..3 {
+            assert!((bounds.center[axis] - 1.0).abs() < 1e-3);
+        }
+        assert!((bounds.radius - 3f32.sqrt()).abs() < 1e-3);
+
+        let radii = [0.5f32; 8];
+        let bounds = compute_sphere_bounds(&corners, 12, Some(&radii), 4);
+        assert!((bounds.radius - (3f32.sqrt() + 0.5)).abs() < 1e-3);
+
+        let empty = compute_sphere_bounds(&[], 12, None, 0);
+        assert_eq!(empty.radius, 0.0);
+    }
+
+    #[test]
+    #[should_panic(expected = "must be greater than 0")]
+    fn test_analyze_zero_limits() {
+        let (vertices, indices) = grid_mesh(4);
+        let adapter =
+            VertexDataAdapter::new(typed_to_bytes(&vertices), core::mem::size_of::<Vertex>(), 0)
+                .unwrap();
+        let meshlets = build_meshlets(&indices, &adapter, 64, 124, 0.0);
+        meshlets.analyze(64, 0, None);
+    }
+
+    #[test]
+    fn test_analyze() {
+        let (vertices, indices) = grid_mesh(16);
+        let adapter =
+            VertexDataAdapter::new(typed_to_bytes(&vertices), core::mem::size_of::<Vertex>(), 0)
+                .unwrap();
+        let meshlets = build_meshlets(&indices, &adapter, 64, 124, 0.0);
+        let bounds = meshlets.compute_bounds(&adapter).unwrap();
+
+        let stats = meshlets.analyze(64, 124, Some(&bounds));
+        assert_eq!(stats.meshlet_count, meshlets.len());
+        assert!(stats.vertex_fill_max <= 1.0);
+        assert!(stats.vertex_fill_min <= stats.vertex_fill_avg);
+        assert!(stats.vertex_fill_avg <= stats.vertex_fill_max);
+        assert!(stats.triangle_fill_min <= stats.triangle_fill_avg);
+        assert!(stats.triangle_fill_avg <= stats.triangle_fill_max);
+        let triangles = indices.len() as f32 / 3.0;
+        assert!((stats.triangle_fill_avg * 124.0 * meshlets.len() as f32 - triangles).abs() < 0.5);
+        // a regular grid reuses every interior vertex close to 6 times, less at meshlet borders
+        assert!(stats.vertex_reuse_avg > 3.0 && stats.vertex_reuse_avg < 6.0);
+        // the grid is almost flat, so every meshlet can be cone culled
+        assert_eq!(stats.cone_cullable, Some(1.0));
+
+        let summary = stats.to_string();
+        assert!(summary.starts_with(&format!("{} meshlets", meshlets.len())));
+        assert!(summary.contains("cone cullable 100.0%"));
+        assert!(meshlets.analyze(64, 124, None).cone_cullable.is_none());
+    }
+
+    #[test]
+    fn test_bounds_outside_frustum() {
+        // an axis aligned box frustum from -10 to 10 on every axis
+        let planes = [
+            [1.0, 0.0, 0.0, 10.0],
+            [-1.0, 0.0, 0.0, 10.0],
+            [0.0, 1.0, 0.0, 10.0],
+            [0.0, -1.0, 0.0, 10.0],
+            [0.0, 0.0, 1.0, 10.0],
+            [0.0, 0.0, -1.0, 10.0],
+        ];
+        let at = |center: [f32; 3]| Bounds {
+            center,
+            radius: 1.0,
+            cone_apex: [0.0; 3],
+            cone_axis: [0.0, 0.0, 1.0],
+            cone_cutoff: 1.0,
+            cone_axis_s8: [0, 0, 127],
+            cone_cutoff_s8: 127,
+        };
+
+        assert!(!at([0.0, 0.0, 0.0]).is_outside_frustum(&planes));
+        // intersecting a plane is still visible
+        assert!(!at([10.5, 0.0, 0.0]).is_outside_frustum(&planes));
+        assert!(at([11.5, 0.0, 0.0]).is_outside_frustum(&planes));
+        assert!(at([0.0, -12.0, 0.0]).is_outside_frustum(&planes));
+        assert!(at([0.0, 0.0, 20.0]).is_outside_frustum(&planes));
+    }
+
+    #[test]
+    fn test_bounds_backfacing() {
+        // a cluster facing +z at the origin, with normals within ~60 degrees of the axis
+        let bounds = Bounds {
+            center: [0.0, 0.0, 0.0],
+            radius: 1.0,
+            cone_apex: [0.0, 0.0, -1.0],
+            cone_axis: [0.0, 0.0, 1.0],
+            cone_cutoff: 0.5,
+            cone_axis_s8: [0, 0, 127],
+            cone_cutoff_s8: 64,
+        };
+
+        // cone_axis points away from the viewer for backfacing clusters
+        assert!(bounds.is_backfacing([0.0, 0.0, -10.0]));
+        assert!(bounds.is_backfacing_apex([0.0, 0.0, -10.0]));
+        assert!(!bounds.is_backfacing([0.0, 0.0, 10.0]));
+        assert!(!bounds.is_backfacing_apex([0.0, 0.0, 10.0]));
+        // viewed from the side, some triangles may be facing the camera
+        assert!(!bounds.is_backfacing([10.0, 0.0, 0.0]));
+        assert!(!bounds.is_backfacing_apex([10.0, 0.0, 0.0]));
+
+        // exactly at the apex nothing can be decided
+        assert!(!bounds.is_backfacing_apex(bounds.cone_apex));
+
+        // a degenerate cone never culls
+        let degenerate = Bounds {
+            cone_cutoff: 1.0,
+            ..bounds
+        };
+        assert!(!degenerate.is_backfacing([0.0, 0.0, -10.0]));
+        assert!(!degenerate.is_backfacing_apex([0.0, 0.0, -10.0]));
+
+        // a negative cutoff (wide cone) only culls from within the narrow region behind it
+        let wide = Bounds {
+            cone_cutoff: -0.5,
+            ..bounds
+        };
+        assert!(wide.is_backfacing_apex([0.0, 0.0, -10.0]));
+        assert!(wide.is_backfacing_apex([10.0, 0.0, -1.0]));
+        assert!(!wide.is_backfacing_apex([0.0, 0.0, 10.0]));
+    }
+
+    #[test]
+    fn test_triangle_meshlet_ids() {
+        let (vertices, indices) = grid_mesh(16);
+        let adapter =
+            VertexDataAdapter::new(typed_to_bytes(&vertices), core::mem::size_of::<Vertex>(), 0)
+                .unwrap();
+        let meshlets = build_meshlets(&indices, &adapter, 64, 124, 0.5);
+
+        let ids = meshlets.triangle_meshlet_ids(indices.len());
+        let (flattened, ranges) = meshlets.to_index_buffer_with_ranges();
+        assert_eq!(ids.len(), flattened.len() / 3);
+        for (i, range) in ranges.iter().enumerate() {
+            assert!(ids[range.start / 3..range.end / 3]
+                .iter()
+                .all(|&id| id as usize == i));
+        }
+
+        let original = meshlets.original_triangle_meshlet_ids(&indices);
+        assert_eq!(original.len(), indices.len() / 3);
+        for (t, id) in indices.chunks(3).zip(&original) {
+            let meshlet = meshlets.get(id.unwrap() as usize);
+            assert!(sorted_triangles(&global_indices(meshlet)).contains(&rotate_triangle(t)));
+        }
+
+        let colors = meshlet_id_colors(&ids);
+        assert_eq!(colors.len(), ids.len());
+        assert_ne!(meshlet_id_colors(&[0])[0], meshlet_id_colors(&[1])[0]);
+
+        // a single meshlet still produces one id per triangle
+        let single = build_meshlets(&indices[..30], &adapter, 64, 124, 0.5);
+        assert_eq!(single.len(), 1);
+        assert_eq!(single.triangle_meshlet_ids(30), vec![0; 10]);
+
+        // triangles the builder didn't place into a meshlet get u32::MAX
+        let padded = single.triangle_meshlet_ids(36);
+        assert_eq!(padded[..10], [0; 10]);
+        assert_eq!(padded[10..], [u32::MAX; 2]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_triangle_meshlet_ids_short_count() {
+        let (vertices, indices) = grid_mesh(4);
+        let adapter =
+            VertexDataAdapter::new(typed_to_bytes(&vertices), core::mem::size_of::<Vertex>(), 0)
+                .unwrap();
+        let meshlets = build_meshlets(&indices, &adapter, 64, 124, 0.5);
+        meshlets.triangle_meshlet_ids(indices.len() - 3);
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_meshlets_serialization() {
+        let (vertices, indices) = grid_mesh(16);
+        let adapter =
+            VertexDataAdapter::new(typed_to_bytes(&vertices), core::mem::size_of::<Vertex>(), 0)
+                .unwrap();
+        let meshlets = build_meshlets(&indices, &adapter, 64, 124, 0.5);
+
+        let mut data = Vec::new();
+        meshlets.write_to(&mut data).unwrap();
+        let read = Meshlets::read_from(data.as_slice()).unwrap();
+        assert_eq!(read.meshlets, meshlets.meshlets);
+        assert_eq!(read.vertices, meshlets.vertices);
+        assert_eq!(read.triangles, meshlets.triangles);
+
+        // truncated input
+        for len in [0, 10, 20, data.len() / 2, data.len() - 1] {
+            assert!(Meshlets::read_from(&data[..len]).is_err());
+        }
+
+        // bad magic and version
+        let mut corrupted = data.clone();
+        corrupted[0] = b'X';
+        assert!(Meshlets::read_from(corrupted.as_slice()).is_err());
+        let mut corrupted = data.clone();
+        corrupted[4] = 2;
+        assert!(Meshlets::read_from(corrupted.as_slice()).is_err());
+
+        // meshlet vertex range past the end of the vertex array
+        let mut corrupted = data.clone();
+        let last = 20 + (meshlets.len() - 1) * 16;
+        corrupted[last..last + 4].copy_from_slice(&u32::MAX.to_le_bytes());
+        assert!(Meshlets::read_from(corrupted.as_slice()).is_err());
+
+        // micro index past the meshlet's vertex count
+        let mut corrupted = data.clone();
+        let triangles_start = data.len() - meshlets.triangles.len();
+        corrupted[triangles_start] = 255;
+        assert!(Meshlets::read_from(corrupted.as_slice()).is_err());
+
+        // huge counts in the header must not allocate up front
+        let mut corrupted = data.clone();
+        corrupted[8..12].copy_from_slice(&u32::MAX.to_le_bytes());
+        assert!(Meshlets::read_from(corrupted.as_slice()).is_err());
+    }
+
+    #[test]
+    fn test_meshlets_pack() {
+        let (vertices, indices) = grid_mesh(16);
+        let adapter =
+            VertexDataAdapter::new(typed_to_bytes(&vertices), core::mem::size_of::<Vertex>(), 0)
+                .unwrap();
+        let meshlets = build_meshlets(&indices, &adapter, 64, 124, 0.5);
+
+        let packed = meshlets.pack();
+        assert_eq!(packed.meshlet_range(), 0..meshlets.len() * 16);
+        assert_eq!(packed.vertex_range().start % 4, 0);
+        assert_eq!(packed.triangle_range().start % 4, 0);
+        assert_eq!(packed.data.len() % 4, 0);
+        assert_eq!(
+            packed.data.len(),
+            packed.triangle_range().end.next_multiple_of(4)
+        );
+        assert_eq!(
+            &packed.data[packed.triangle_range()],
+            &meshlets.triangles[..]
+        );
+
+        let unpacked = packed.unpack().unwrap();
+        assert_eq!(unpacked.meshlets, meshlets.meshlets);
+        assert_eq!(unpacked.vertices, meshlets.vertices);
+        assert_eq!(unpacked.triangles, meshlets.triangles);
+
+        let mut truncated = packed.clone();
+        truncated.data.truncate(packed.triangle_range().end - 1);
+        assert!(truncated.unpack().is_err());
+
+        let mut corrupted = packed.clone();
+        corrupted.meshlet_count = usize::MAX;
+        assert!(corrupted.unpack().is_err());
+
+        let mut corrupted = packed.clone();
+        let start = packed.triangle_range().start;
+        corrupted.data[start] = 255;
+        assert!(corrupted.unpack().is_err());
+
+        let empty = Meshlets {
+            meshlets: Vec::new(),
+            vertices: Vec::new(),
+            triangles: Vec::new(),
+        }
+        .pack();
+        assert!(empty.data.is_empty());
+        assert!(empty.unpack().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_meshlets_truncated_triangles() {
+        let (vertices, indices) = grid_mesh(16);
+        let adapter =
+            VertexDataAdapter::new(typed_to_bytes(&vertices), core::mem::size_of::<Vertex>(), 0)
+                .unwrap();
+        let mut meshlets = build_meshlets(&indices, &adapter, 64, 124, 0.5);
+        assert!(meshlets.validate().is_ok());
+        for i in 0..meshlets.len() {
+            assert_eq!(
+                meshlets.try_get(i).unwrap().triangles,
+                meshlets.get(i).triangles
+            );
+        }
+        assert!(meshlets.try_get(meshlets.len()).is_err());
+
+        let last = meshlets.len() - 1;
+        let new_len = meshlets.triangles.len() - 3;
+        meshlets.triangles.truncate(new_len);
+        assert!(matches!(meshlets.validate(), Err(Error::Memory(_))));
+        assert!(meshlets.try_get(last).is_err());
+        assert!(meshlets.try_get(0).is_ok());
+
+        let packed = meshlets.pack();
+        assert!(matches!(packed.unpack(), Err(Error::Parse(_))));
+
+        let mut serialized = Vec::new();
+        meshlets.write_to(&mut serialized).unwrap();
+        assert!(matches!(
+            Meshlets::read_from(&serialized[..]),
+            Err(Error::Parse(_))
+        ));
+    }
+}
@@ -1,14 +1,43 @@
-use crate::{error_or, ffi, utilities::rcp_safe, Result};
-use std::mem;
+use crate::{
+    any_as_u8_slice, error_or, ffi, quantize_snorm, quantize_unorm, utilities::rcp_safe, Error,
+    PackedVertex, Result, Vertex, VertexStream,
+};
+use alloc::{format, vec, vec::Vec};
+use core::{mem, ops::RangeInclusive};
 
 /// Encodes index data into an array of bytes that is generally much smaller (<1.5 bytes/triangle)
 /// and compresses better (<1 bytes/triangle) compared to original.
 ///
 /// For maximum efficiency the index buffer being encoded has to be optimized for vertex cache and
 /// vertex fetch first.
+///
+/// Returns an error if the index count is not a multiple of 3, or if the indices reference
+/// vertices beyond `vertex_count` so that the encoded data exceeds the bound estimate.
 pub fn encode_index_buffer(indices: &[u32], vertex_count: usize) -> Result<Vec<u8>> {
+    let mut result = Vec::new();
+    encode_index_buffer_impl(&mut result, indices, vertex_count)?;
+    Ok(result)
+}
+
+/// Returns the size of the data `encode_index_buffer` produces for `indices`, encoding into
+/// a reused scratch buffer (per thread with the `std` feature) instead of returning the bytes.
+///
+/// Returns the same errors as `encode_index_buffer`.
+pub fn encoded_index_buffer_size(indices: &[u32], vertex_count: usize) -> Result<usize> {
+    with_scratch(|scratch| encode_index_buffer_impl(scratch, indices, vertex_count))
+}
+
+fn encode_index_buffer_impl(
+    result: &mut Vec<u8>,
+    indices: &[u32],
+    vertex_count: usize,
+) -> Result<usize> {
+    if !indices.len().is_multiple_of(3) {
+        return Err(Error::memory("index count must be a multiple of 3"));
+    }
     let bounds = unsafe { ffi::meshopt_encodeIndexBufferBound(indices.len(), vertex_count) };
-    let mut result: Vec<u8> = vec![0; bounds];
+    result.clear();
+    result.resize(bounds, 0);
     let size = unsafe {
         ffi::meshopt_encodeIndexBuffer(
             result.as_mut_ptr(),
@@ -17,13 +46,52 @@ pub fn encode_index_buffer(indices: &[u32], vertex_count: usize) -> Result<Vec<u
             indices.len(),
         )
     };
-    result.resize(size, 0u8);
-    Ok(result)
+    if size == 0 {
+        return Err(Error::memory(
+            "encoded index buffer exceeds the bound estimate; is vertex_count too small?",
+        ));
+    }
+    result.truncate(size);
+    Ok(size)
+}
+
+/// Like `encode_index_buffer`, but writes the encoded data to `writer` and returns the number
+/// of bytes written, encoding into a reused per thread scratch buffer.
+///
+/// Returns the same errors as `encode_index_buffer`, and `Error::Io` if writing fails.
+#[cfg(feature = "std")]
+pub fn encode_index_buffer_to<W: std::io::Write>(
+    indices: &[u32],
+    vertex_count: usize,
+    writer: &mut W,
+) -> Result<usize> {
+    with_scratch(|scratch| {
+        let size = encode_index_buffer_impl(scratch, indices, vertex_count)?;
+        writer.write_all(scratch)?;
+        Ok(size)
+    })
+}
+
+// Runs `f` with a scratch buffer that is reused between calls on the same thread.
+#[cfg(feature = "std")]
+fn with_scratch<R>(f: impl FnOnce(&mut Vec<u8>) -> R) -> R {
+    std::thread_local! {
+        static SCRATCH: core::cell::RefCell<Vec<u8>> = const { core::cell::RefCell::new(Vec::new()) };
+    }
+    SCRATCH.with(|scratch| f(&mut scratch.borrow_mut()))
+}
+
+#[cfg(not(feature = "std"))]
+fn with_scratch<R>(f: impl FnOnce(&mut Vec<u8>) -> R) -> R {
+    f(&mut Vec::new())
 }
 
 /// Decodes index data from an array of bytes generated by `encode_index_buffer`.
 /// The decoder is safe to use for untrusted input, but it may produce garbage
 /// data (e.g. out of range indices).
+///
+/// Returns an error if `index_count` is not a multiple of 3, and `Error::BufferTooSmall` if
+/// `encoded` is too small to hold `index_count` indices.
 pub fn decode_index_buffer<T: Clone + Default + Sized>(
     encoded: &[u8],
     index_count: usize,
@@ -37,6 +105,8 @@ pub fn decode_index_buffer<T: Clone + Default + Sized>(
 
     assert_valid_size::<T>();
 
+    check_encoded_index_buffer(encoded, index_count)?;
+
     let mut result: Vec<T> = vec![Default::default(); index_count];
     let result_code = unsafe {
         ffi::meshopt_decodeIndexBuffer(
@@ -51,15 +121,209 @@ pub fn decode_index_buffer<T: Clone + Default + Sized>(
     error_or(result_code, result)
 }
 
+fn check_encoded_index_buffer(encoded: &[u8], index_count: usize) -> Result<()> {
+    if !index_count.is_multiple_of(3) {
+        return Err(Error::memory("index count must be a multiple of 3"));
+    }
+    // header, at least 1 byte per triangle and the 16 byte codeaux table
+    let min_size = (index_count / 3).saturating_add(17);
+    if encoded.len() < min_size {
+        return Err(Error::BufferTooSmall);
+    }
+    Ok(())
+}
+
+/// Checks that `encoded` can be an index buffer generated by `encode_index_buffer` with
+/// `index_count` indices, without decoding it: the header byte must name a supported format
+/// version, and the data must not be shorter than any encoding of `index_count` indices.
+///
+/// Passing this check doesn't guarantee that decoding succeeds, only that obviously corrupt
+/// or truncated data is rejected cheaply. Returns the same errors as `decode_index_buffer`,
+/// and `Error::UnsupportedVersion` for an invalid header.
+pub fn validate_encoded_index_buffer(encoded: &[u8], index_count: usize) -> Result<()> {
+    check_encoded_index_buffer(encoded, index_count)?;
+    // 0xe0 marks index buffers, as opposed to index sequences
+    let version = unsafe { ffi::meshopt_decodeIndexVersion(encoded.as_ptr(), encoded.len()) };
+    if version < 0 || encoded[0] & 0xf0 != 0xe0 {
+        return Err(Error::UnsupportedVersion);
+    }
+    Ok(())
+}
+
+/// Like `decode_index_buffer`, but also checks that every decoded index is less than
+/// `vertex_count`, so corrupt data can't make a renderer read beyond the vertex buffer.
+///
+/// Returns an error describing the first out of range index in addition to the errors
+/// of `decode_index_buffer`.
+pub fn decode_index_buffer_checked<T: Clone + Default + Into<u32>>(
+    encoded: &[u8],
+    index_count: usize,
+    vertex_count: usize,
+) -> Result<Vec<T>> {
+    let result: Vec<T> = decode_index_buffer(encoded, index_count)?;
+    if let Some((location, index)) = result
+        .iter()
+        .map(|index| index.clone().into())
+        .enumerate()
+        .find(|&(_, index)| index as usize >= vertex_count)
+    {
+        return Err(Error::memory_dynamic(format!(
+            "decoded index ({}) at {} must be less than vertex count ({})",
+            index, location, vertex_count
+        )));
+    }
+    Ok(result)
+}
+
+/// Like `encode_index_buffer`, but for 8-bit indices such as the meshlet-local triangle
+/// indices in `Meshlets::triangles`.
+///
+/// The indices are widened to 32 bits for the codec, which handles them as referencing
+/// up to 256 vertices; decode the result with `decode_index_buffer_u8`.
+///
+/// Returns an error if the index count is not a multiple of 3.
+pub fn encode_index_buffer_u8(indices: &[u8]) -> Result<Vec<u8>> {
+    let indices: Vec<u32> = indices.iter().map(|&index| u32::from(index)).collect();
+    encode_index_buffer(&indices, 256)
+}
+
+/// Decodes 8-bit index data from an array of bytes generated by `encode_index_buffer_u8`.
+///
+/// Returns the same errors as `decode_index_buffer`, and `Error::CorruptData` if a decoded
+/// index doesn't fit into 8 bits.
+pub fn decode_index_buffer_u8(encoded: &[u8], index_count: usize) -> Result<Vec<u8>> {
+    let indices: Vec<u32> = decode_index_buffer(encoded, index_count)?;
+    indices
+        .into_iter()
+        .map(|index| u8::try_from(index).map_err(|_overflow| Error::CorruptData))
+        .collect()
+}
+
+/// Incremental decoder for index data generated by `encode_index_buffer`, for
+/// decoding as the encoded bytes arrive (e.g. over the network).
+///
+/// The index codec stores a lookup table at the end of the encoded data, so no
+/// indices can be decoded before the whole buffer has been received. The decoder
+/// buffers the bytes passed to `feed` until `encoded_len` bytes are available,
+/// decodes them all at once, and exposes the download progress in the meantime.
+#[derive(Debug, Clone)]
+pub struct IndexDecoder {
+    index_count: usize,
+    encoded_len: usize,
+    received: usize,
+    buffer: Vec<u8>,
+    decoded: Option<Vec<u32>>,
+    complete: bool,
+}
+
+impl IndexDecoder {
+    /// Creates a decoder for `index_count` indices encoded into `encoded_len` bytes.
+    pub fn new(index_count: usize, encoded_len: usize) -> Self {
+        Self {
+            index_count,
+            encoded_len,
+            received: 0,
+            buffer: Vec::with_capacity(encoded_len),
+            decoded: None,
+            complete: false,
+        }
+    }
+
+    /// Appends the next chunk of encoded data, decoding the index buffer once all
+    /// `encoded_len` bytes have been fed.
+    ///
+    /// Returns an error if more than `encoded_len` bytes are fed in total, or if the
+    /// complete buffer fails to decode.
+    pub fn feed(&mut self, bytes: &[u8]) -> Result<()> {
+        if bytes.len() > self.remaining_len() {
+            return Err(Error::memory_dynamic(format!(
+                "fed {} bytes but only {} of {} encoded bytes remain",
+                bytes.len(),
+                self.remaining_len(),
+                self.encoded_len
+            )));
+        }
+        self.buffer.extend_from_slice(bytes);
+        self.received += bytes.len();
+
+        if self.received == self.encoded_len && !self.complete {
+            let buffer = mem::take(&mut self.buffer);
+            self.decoded = Some(decode_index_buffer(&buffer, self.index_count)?);
+            self.complete = true;
+        }
+        Ok(())
+    }
+
+    /// Returns the decoded indices that have not been taken yet; this is empty until
+    /// the whole encoded buffer has been fed, and after the indices have been taken.
+    pub fn take_decoded(&mut self) -> Vec<u32> {
+        self.decoded.take().unwrap_or_default()
+    }
+
+    /// Returns the number of indices `take_decoded` would return.
+    pub fn decodable_index_count(&self) -> usize {
+        self.decoded.as_ref().map_or(0, Vec::len)
+    }
+
+    /// Returns the number of encoded bytes fed so far.
+    pub fn received_len(&self) -> usize {
+        self.received
+    }
+
+    /// Returns the number of encoded bytes still expected.
+    pub fn remaining_len(&self) -> usize {
+        self.encoded_len - self.received
+    }
+
+    /// Returns `true` once all encoded bytes have been fed and decoded.
+    pub fn is_complete(&self) -> bool {
+        self.complete
+    }
+}
+
 /// Encodes vertex data into an array of bytes that is generally smaller and compresses better
 /// compared to original.
 ///
 /// This function works for a single vertex stream; for multiple vertex streams,
 /// call `encode_vertex_buffer` for each stream.
+///
+/// Returns an error if the size of `T` is not a multiple of 4 in `4..=256`.
 pub fn encode_vertex_buffer<T>(vertices: &[T]) -> Result<Vec<u8>> {
+    let mut result = Vec::new();
+    encode_vertex_buffer_impl(&mut result, vertices)?;
+    Ok(result)
+}
+
+/// Returns the size of the data `encode_vertex_buffer` produces for `vertices`, encoding into
+/// a reused scratch buffer (per thread with the `std` feature) instead of returning the bytes.
+///
+/// Returns the same errors as `encode_vertex_buffer`.
+pub fn encoded_vertex_buffer_size<T>(vertices: &[T]) -> Result<usize> {
+    with_scratch(|scratch| encode_vertex_buffer_impl(scratch, vertices))
+}
+
+/// Like `encode_vertex_buffer`, but writes the encoded data to `writer` and returns the number
+/// of bytes written, encoding into a reused per thread scratch buffer.
+///
+/// Returns the same errors as `encode_vertex_buffer`, and `Error::Io` if writing fails.
+#[cfg(feature = "std")]
+pub fn encode_vertex_buffer_to<T, W: std::io::Write>(
+    vertices: &[T],
+    writer: &mut W,
+) -> Result<usize> {
+    with_scratch(|scratch| {
+        let size = encode_vertex_buffer_impl(scratch, vertices)?;
+        writer.write_all(scratch)?;
+        Ok(size)
+    })
+}
+
+fn encode_vertex_buffer_impl<T>(result: &mut Vec<u8>, vertices: &[T]) -> Result<usize> {
+    check_vertex_size(mem::size_of::<T>())?;
     let bounds =
         unsafe { ffi::meshopt_encodeVertexBufferBound(vertices.len(), mem::size_of::<T>()) };
-    let mut result: Vec<u8> = vec![0; bounds];
+    result.clear();
+    result.resize(bounds, 0);
     let size = unsafe {
         ffi::meshopt_encodeVertexBuffer(
             result.as_mut_ptr(),
@@ -69,16 +333,32 @@ pub fn encode_vertex_buffer<T>(vertices: &[T]) -> Result<Vec<u8>> {
             mem::size_of::<T>(),
         )
     };
-    result.resize(size, 0u8);
-    Ok(result)
+    if size == 0 {
+        return Err(Error::memory(
+            "encoded vertex buffer exceeds the bound estimate",
+        ));
+    }
+    result.truncate(size);
+    Ok(size)
+}
+
+/// Like `encode_vertex_buffer`, but also returns the number of encoded vertices, which is
+/// needed to decode the data again (e.g. for `EncodeHeader::vertex_count`).
+pub fn encode_vertex_buffer_with_count<T>(vertices: &[T]) -> Result<(Vec<u8>, usize)> {
+    let encoded = encode_vertex_buffer(vertices)?;
+    Ok((encoded, vertices.len()))
 }
 
 /// Decodes vertex data from an array of bytes generated by `encode_vertex_buffer`.
 /// The decoder is safe to use for untrusted input, but it may produce garbage data.
+///
+/// Returns an error if the size of `T` is not a multiple of 4 in `4..=256`, and
+/// `Error::BufferTooSmall` if `encoded` is too small to hold `vertex_count` vertices.
 pub fn decode_vertex_buffer<T: Clone + Default>(
     encoded: &[u8],
     vertex_count: usize,
 ) -> Result<Vec<T>> {
+    check_encoded_vertex_buffer(encoded, vertex_count, mem::size_of::<T>())?;
     let mut result: Vec<T> = vec![Default::default(); vertex_count];
     let result_code = unsafe {
         ffi::meshopt_decodeVertexBuffer(
@@ -93,6 +373,239 @@ pub fn decode_vertex_buffer<T: Clone + Default>(
     error_or(result_code, result)
 }
 
+/// Like `decode_vertex_buffer`, but decodes `vertex_count` vertices of `vertex_size` bytes
+/// into the raw byte slice `out`, e.g. the mapped range of a GPU buffer, without an
+/// intermediate allocation.
+///
+/// Returns the same errors as `decode_vertex_buffer`, and an error if the length of `out`
+/// is not `vertex_count * vertex_size`. The contents of `out` are unspecified on error.
+pub fn decode_vertex_buffer_into_bytes(
+    encoded: &[u8],
+    out: &mut [u8],
+    vertex_count: usize,
+    vertex_size: usize,
+) -> Result<()> {
+    if vertex_count.checked_mul(vertex_size) != Some(out.len()) {
+        return Err(Error::memory_dynamic(format!(
+            "output length ({}) must be vertex_count ({}) * vertex_size ({})",
+            out.len(),
+            vertex_count,
+            vertex_size
+        )));
+    }
+    check_encoded_vertex_buffer(encoded, vertex_count, vertex_size)?;
+    let result_code = unsafe {
+        ffi::meshopt_decodeVertexBuffer(
+            out.as_mut_ptr().cast(),
+            vertex_count,
+            vertex_size,
+            encoded.as_ptr(),
+            encoded.len(),
+        )
+    };
+
+    error_or(result_code, ())
+}
+
+/// Like `decode_vertex_buffer`, but reads the `byte_len` bytes of encoded data from `reader`,
+/// e.g. a file or network stream, into a reused per thread scratch buffer.
+///
+/// The scratch buffer grows as the data arrives, so an untrusted `byte_len` doesn't cause a
+/// large allocation up front.
+///
+/// Returns the same errors as `decode_vertex_buffer`, and `Error::Io` if reading fails or the
+/// reader ends before `byte_len` bytes.
+#[cfg(feature = "std")]
+pub fn decode_vertex_buffer_from<T: Clone + Default, R: std::io::Read>(
+    reader: &mut R,
+    vertex_count: usize,
+    byte_len: usize,
+) -> Result<Vec<T>> {
+    use std::io::Read;
+
+    with_scratch(|scratch| {
+        scratch.clear();
+        reader.take(byte_len as u64).read_to_end(scratch)?;
+        if scratch.len() < byte_len {
+            return Err(std::io::Error::from(std::io::ErrorKind::UnexpectedEof).into());
+        }
+        decode_vertex_buffer(scratch, vertex_count)
+    })
+}
+
+/// Decodes several vertex buffers generated by `encode_vertex_buffer` in parallel.
+///
+/// `vertex_counts[i]` is the number of vertices encoded in `encoded[i]`; the results are returned
+/// in the same order and match what `decode_vertex_buffer` produces for each buffer. Returns an
+/// error if the two slices have different lengths, or the first error hit by any of the decodes.
+#[cfg(feature = "rayon")]
+pub fn decode_vertex_buffers_par<T: Clone + Default + Send>(
+    encoded: &[&[u8]],
+    vertex_counts: &[usize],
+) -> Result<Vec<Vec<T>>> {
+    use rayon::prelude::*;
+
+    if encoded.len() != vertex_counts.len() {
+        return Err(Error::memory_dynamic(format!(
+            "got {} encoded buffers but {} vertex counts",
+            encoded.len(),
+            vertex_counts.len()
+        )));
+    }
+
+    encoded
+        .par_iter()
+        .zip(vertex_counts.par_iter())
+        .map(|(encoded, &vertex_count)| decode_vertex_buffer(encoded, vertex_count))
+        .collect()
+}
+
+fn check_vertex_size(vertex_size: usize) -> Result<()> {
+    if vertex_size == 0 || vertex_size > 256 || !vertex_size.is_multiple_of(4) {
+        return Err(Error::memory_dynamic(format!(
+            "vertex size must be a multiple of 4 in 4..=256, got {}",
+            vertex_size
+        )));
+    }
+    Ok(())
+}
+
+// Checks the vertex size and rejects encoded data that is smaller than the smallest possible
+// encoding of `vertex_count` vertices: a header byte, a control byte per 4 bytes of vertex for
+// every block (format version 1; version 0 needs more), and the tail.
+fn check_encoded_vertex_buffer(
+    encoded: &[u8],
+    vertex_count: usize,
+    vertex_size: usize,
+) -> Result<()> {
+    check_vertex_size(vertex_size)?;
+    let block_size = ((8192 / vertex_size) & !15).min(256);
+    let min_size = vertex_count
+        .div_ceil(block_size)
+        .saturating_mul(vertex_size / 4)
+        .saturating_add(1 + vertex_size.max(24));
+    if encoded.len() < min_size {
+        return Err(Error::BufferTooSmall);
+    }
+    Ok(())
+}
+
+/// Checks that `encoded` can be a vertex buffer generated by `encode_vertex_buffer` with
+/// `vertex_count` vertices of `vertex_size` bytes, without decoding it: the header byte
+/// must name a supported format version, and the data must not be shorter than any
+/// encoding of that many vertices.
+///
+/// Passing this check doesn't guarantee that decoding succeeds, only that obviously corrupt
+/// or truncated data is rejected cheaply. Returns the same errors as `decode_vertex_buffer`,
+/// and `Error::UnsupportedVersion` for an invalid header.
+pub fn validate_encoded_vertex_buffer(
+    encoded: &[u8],
+    vertex_count: usize,
+    vertex_size: usize,
+) -> Result<()> {
+    check_vertex_size(vertex_size)?;
+    if encoded.is_empty() {
+        return Err(Error::BufferTooSmall);
+    }
+    if unsafe { ffi::meshopt_decodeVertexVersion(encoded.as_ptr(), encoded.len()) } < 0 {
+        return Err(Error::UnsupportedVersion);
+    }
+    check_encoded_vertex_buffer(encoded, vertex_count, vertex_size)
+}
+
+/// Encodes each vertex stream separately with the vertex codec, so that attributes can be
+/// stored, loaded and post-processed (e.g. with general purpose compression) independently,
+/// like gltfpack does.
+///
+/// The codec encodes every byte of a vertex separately, so the encoded streams together are
+/// about as large as the encoded interleaved vertices, plus a small fixed overhead per
+/// stream; any savings come from compressing attributes of similar kind together afterwards.
+///
+/// Each stream contributes `size` bytes per vertex for `vertex_count` vertices, read with
+/// its `stride`; the result has one encoded buffer per stream, to be decoded with
+/// `decode_vertex_streams`. Returns an error if a stream size is not a multiple of 4
+/// in `4..=256` or larger than its stride, or if a stream created from a slice doesn't
+/// have `vertex_count` vertices.
+pub fn encode_vertex_streams(
+    vertex_count: usize,
+    streams: &[VertexStream<'_>],
+) -> Result<Vec<Vec<u8>>> {
+    streams
+        .iter()
+        .map(|stream| {
+            check_vertex_size(stream.size())?;
+            crate::check_stream(stream, vertex_count)?;
+            let mut data: Vec<u8> = Vec::with_capacity(vertex_count * stream.size());
+            for i in 0..vertex_count {
+                let element = unsafe {
+                    core::slice::from_raw_parts(
+                        stream.data().add(i * stream.stride()),
+                        stream.size(),
+                    )
+                };
+                data.extend_from_slice(element);
+            }
+
+            let bounds =
+                unsafe { ffi::meshopt_encodeVertexBufferBound(vertex_count, stream.size()) };
+            let mut result: Vec<u8> = vec![0; bounds];
+            let size = unsafe {
+                ffi::meshopt_encodeVertexBuffer(
+                    result.as_mut_ptr(),
+                    result.len(),
+                    data.as_ptr().cast(),
+                    vertex_count,
+                    stream.size(),
+                )
+            };
+            if size == 0 {
+                return Err(Error::memory(
+                    "encoded vertex buffer exceeds the bound estimate",
+                ));
+            }
+            result.resize(size, 0u8);
+            Ok(result)
+        })
+        .collect()
+}
+
+/// Decodes vertex streams encoded by `encode_vertex_streams`.
+///
+/// `stream_sizes[i]` is the size in bytes of one vertex of `encoded[i]`; each decoded
+/// stream is returned tightly packed, with `vertex_count * stream_sizes[i]` bytes.
+pub fn decode_vertex_streams(
+    encoded: &[&[u8]],
+    vertex_count: usize,
+    stream_sizes: &[usize],
+) -> Result<Vec<Vec<u8>>> {
+    if encoded.len() != stream_sizes.len() {
+        return Err(Error::memory_dynamic(format!(
+            "got {} encoded streams but {} stream sizes",
+            encoded.len(),
+            stream_sizes.len()
+        )));
+    }
+
+    encoded
+        .iter()
+        .zip(stream_sizes)
+        .map(|(encoded, &size)| {
+            check_encoded_vertex_buffer(encoded, vertex_count, size)?;
+            let mut result: Vec<u8> = vec![0; vertex_count * size];
+            let result_code = unsafe {
+                ffi::meshopt_decodeVertexBuffer(
+                    result.as_mut_ptr().cast(),
+                    vertex_count,
+                    size,
+                    encoded.as_ptr(),
+                    encoded.len(),
+                )
+            };
+            error_or(result_code, result)
+        })
+        .collect()
+}
+
 #[repr(C)]
 #[derive(Debug, Copy, Clone)]
 pub struct EncodeHeader {
@@ -121,23 +634,36 @@ pub struct EncodeObject {
     pub reserved: u32,
 }
 
+/// Computes the minimum corner and the largest extent of a set of positions,
+/// given as a flat array of `x, y, z` triples.
+///
+/// Non-finite components (NaN or infinity) are ignored, so that a single broken vertex
+/// doesn't ruin quantization for the whole mesh; an axis without any finite component
+/// gets a zero offset.
+///
+/// Returns `([0.0; 3], 0.0)` for an empty input. The length of `positions` is expected
+/// to be a multiple of 3; a trailing partial position is ignored.
 pub fn calc_pos_offset_and_scale(positions: &[f32]) -> ([f32; 3], f32) {
-    const MAX: f32 = f32::MAX;
-    let pos_offset = positions
-        .chunks(3)
-        .fold([MAX, MAX, MAX], |result, position| {
-            [
-                result[0].min(position[0]),
-                result[1].min(position[1]),
-                result[2].min(position[2]),
-            ]
-        });
-
-    let pos_scale = positions.chunks(3).fold(0f32, |result, position| {
-        result
-            .max(position[0] - pos_offset[0])
-            .max(position[1] - pos_offset[1])
-            .max(position[2] - pos_offset[2])
+    debug_assert!(
+        positions.len().is_multiple_of(3),
+        "positions length must be a multiple of 3"
+    );
+
+    let pos_offset: [f32; 3] = core::array::from_fn(|axis| {
+        positions
+            .chunks_exact(3)
+            .map(|position| position[axis])
+            .filter(|value| value.is_finite())
+            .reduce(f32::min)
+            .unwrap_or(0f32)
+    });
+
+    let pos_scale = positions.chunks_exact(3).fold(0f32, |result, position| {
+        (0..3)
+            .filter(|&axis| position[axis].is_finite())
+            .fold(result, |result, axis| {
+                result.max(position[axis] - pos_offset[axis])
+            })
     });
 
     (pos_offset, pos_scale)
@@ -149,18 +675,32 @@ pub fn calc_pos_offset_and_scale_inverse(positions: &[f32]) -> ([f32; 3], f32) {
     (pos_offset, pos_scale_inverse)
 }
 
+/// Computes the minimum corner and the per-axis extent of a set of texture coordinates,
+/// given as a flat array of `u, v` pairs.
+///
+/// Non-finite components (NaN or infinity) are ignored; an axis without any finite
+/// component gets a zero offset and scale.
+///
+/// Returns `([0.0; 2], [0.0; 2])` for an empty input. The length of `coords` is expected
+/// to be a multiple of 2; a trailing partial coordinate is ignored.
 pub fn calc_uv_offset_and_scale(coords: &[f32]) -> ([f32; 2], [f32; 2]) {
-    const MAX: f32 = f32::MAX;
+    debug_assert!(
+        coords.len().is_multiple_of(2),
+        "coords length must be a multiple of 2"
+    );
 
-    let uv_offset = coords.chunks(2).fold([MAX, MAX], |result, coord| {
-        [result[0].min(coord[0]), result[1].min(coord[1])]
-    });
+    let finite = |axis: usize| {
+        coords
+            .chunks_exact(2)
+            .map(move |coord| coord[axis])
+            .filter(|value| value.is_finite())
+    };
 
-    let uv_scale = coords.chunks(2).fold([MAX, MAX], |result, coord| {
-        [
-            result[0].max(coord[0] - uv_offset[0]),
-            result[1].max(coord[1] - uv_offset[1]),
-        ]
+    let uv_offset: [f32; 2] =
+        core::array::from_fn(|axis| finite(axis).reduce(f32::min).unwrap_or(0f32));
+
+    let uv_scale: [f32; 2] = core::array::from_fn(|axis| {
+        finite(axis).fold(0f32, |result, value| result.max(value - uv_offset[axis]))
     });
 
     (uv_offset, uv_scale)
@@ -171,3 +711,996 @@ pub fn calc_uv_offset_and_scale_inverse(coords: &[f32]) -> ([f32; 2], [f32; 2])
     let uv_scale_inverse = [rcp_safe(uv_scale[0]), rcp_safe(uv_scale[1])];
     (uv_offset, uv_scale_inverse)
 }
+
+impl EncodeHeader {
+    /// Creates a header for an `OPTM` container from the position and texture coordinate
+    /// ranges returned by `calc_pos_offset_and_scale`/`calc_uv_offset_and_scale`.
+    ///
+    /// The stored scales are divided by `(1 << bits) - 1` so that a quantized component
+    /// `q` reconstructs as `offset + q * scale`. Counts and data sizes are left as zero.
+    pub fn new(
+        pos_offset: [f32; 3],
+        pos_scale: f32,
+        pos_bits: i32,
+        uv_offset: [f32; 2],
+        uv_scale: [f32; 2],
+        uv_bits: i32,
+    ) -> Self {
+        let pos_max = ((1 << pos_bits) - 1) as f32;
+        let uv_max = ((1 << uv_bits) - 1) as f32;
+        Self {
+            magic: *b"OPTM",
+            group_count: 0,
+            vertex_count: 0,
+            index_count: 0,
+            vertex_data_size: 0,
+            index_data_size: 0,
+            pos_offset,
+            pos_scale: pos_scale / pos_max,
+            uv_offset,
+            uv_scale: [uv_scale[0] / uv_max, uv_scale[1] / uv_max],
+            reserved: [0, 0],
+        }
+    }
+
+    /// Reconstructs a position quantized relative to `pos_offset`/`pos_scale`,
+    /// as `offset + quantized * scale`.
+    #[inline]
+    pub fn dequantize_position(&self, quantized: [u32; 3]) -> [f32; 3] {
+        core::array::from_fn(|axis| self.pos_offset[axis] + quantized[axis] as f32 * self.pos_scale)
+    }
+
+    /// Reconstructs a texture coordinate quantized relative to `uv_offset`/`uv_scale`,
+    /// as `offset + quantized * scale`.
+    #[inline]
+    pub fn dequantize_uv(&self, quantized: [u32; 2]) -> [f32; 2] {
+        core::array::from_fn(|axis| {
+            self.uv_offset[axis] + quantized[axis] as f32 * self.uv_scale[axis]
+        })
+    }
+}
+
+/// Error introduced by quantizing positions or texture coordinates, as returned by
+/// `analyze_quantization` and `analyze_uv_quantization`.
+#[derive(Debug, Copy, Clone, PartialEq, Default)]
+pub struct QuantizationError {
+    /// Largest absolute error of a single component.
+    pub max_abs: f32,
+    /// Root mean square of the component errors.
+    pub rms: f32,
+    /// Index of the vertex with the largest component error.
+    pub worst_vertex_index: usize,
+}
+
+/// Quantizes and dequantizes each `N` component vertex in `values` and returns the largest
+/// component error, the index of the vertex it belongs to, and the sum of squared errors
+/// and the number of finite components.
+fn measure_quantization<const N: usize>(
+    values: &[f32],
+    round_trip: impl Fn(usize, f32) -> f32,
+) -> (f32, usize, f64, usize) {
+    let mut max_abs = 0f32;
+    let mut worst_vertex_index = 0;
+    let mut sum_squared = 0f64;
+    let mut count = 0;
+    for (vertex, value) in values.chunks_exact(N).enumerate() {
+        for (axis, &component) in value.iter().enumerate() {
+            if !component.is_finite() {
+                continue;
+            }
+            let error = (round_trip(axis, component) - component).abs();
+            // a NaN error (from an infinite scale) is never below any target
+            if error > max_abs || error.is_nan() {
+                max_abs = error;
+                worst_vertex_index = vertex;
+            }
+            sum_squared += f64::from(error) * f64::from(error);
+            count += 1;
+        }
+    }
+    (max_abs, worst_vertex_index, sum_squared, count)
+}
+
+fn measure_pos_quantization(positions: &[f32], pos_bits: u32) -> (f32, usize, f64, usize) {
+    let pos_bits = check_quantization_bits(pos_bits);
+    let (pos_offset, pos_scale) = calc_pos_offset_and_scale(positions);
+    let header = EncodeHeader::new(pos_offset, pos_scale, pos_bits, [0.0; 2], [0.0; 2], 1);
+    let pos_scale_inv = rcp_safe(pos_scale);
+    measure_quantization::<3>(positions, |axis, value| {
+        let q = quantize_unorm((value - pos_offset[axis]) * pos_scale_inv, pos_bits) as u32;
+        header.pos_offset[axis] + q as f32 * header.pos_scale
+    })
+}
+
+fn measure_uv_quantization(coords: &[f32], uv_bits: u32) -> (f32, usize, f64, usize) {
+    let uv_bits = check_quantization_bits(uv_bits);
+    let (uv_offset, uv_scale) = calc_uv_offset_and_scale(coords);
+    let header = EncodeHeader::new([0.0; 3], 0.0, 1, uv_offset, uv_scale, uv_bits);
+    let uv_scale_inv = [rcp_safe(uv_scale[0]), rcp_safe(uv_scale[1])];
+    measure_quantization::<2>(coords, |axis, value| {
+        let q = quantize_unorm((value - uv_offset[axis]) * uv_scale_inv[axis], uv_bits) as u32;
+        header.uv_offset[axis] + q as f32 * header.uv_scale[axis]
+    })
+}
+
+#[cfg(feature = "std")]
+fn quantization_error(
+    (max_abs, worst_vertex_index, sum_squared, count): (f32, usize, f64, usize),
+) -> QuantizationError {
+    QuantizationError {
+        max_abs,
+        rms: if count == 0 {
+            0.0
+        } else {
+            (sum_squared / count as f64).sqrt() as f32
+        },
+        worst_vertex_index,
+    }
+}
+
+fn check_quantization_bits(bits: u32) -> i32 {
+    assert!(
+        (1..=16).contains(&bits),
+        "bit count ({}) must be in 1..=16",
+        bits
+    );
+    bits as i32
+}
+
+/// Measures the error of quantizing `positions` (a flat array of `x, y, z` triples) to
+/// `pos_bits` bits, using the offset and scale from `calc_pos_offset_and_scale` and the
+/// same math as `EncodedMesh` and `EncodeHeader`.
+///
+/// The scale is shared by all axes, so a single outlier vertex increases the error of the
+/// whole mesh. Non-finite components are ignored.
+///
+/// # Panics
+///
+/// Panics if `pos_bits` is not in `1..=16`.
+#[cfg(feature = "std")]
+pub fn analyze_quantization(positions: &[f32], pos_bits: u32) -> QuantizationError {
+    quantization_error(measure_pos_quantization(positions, pos_bits))
+}
+
+/// Like `analyze_quantization`, but for texture coordinates (a flat array of `u, v` pairs),
+/// using the per-axis offset and scale from `calc_uv_offset_and_scale`.
+///
+/// # Panics
+///
+/// Panics if `uv_bits` is not in `1..=16`.
+#[cfg(feature = "std")]
+pub fn analyze_uv_quantization(coords: &[f32], uv_bits: u32) -> QuantizationError {
+    quantization_error(measure_uv_quantization(coords, uv_bits))
+}
+
+/// Returns the smallest position bit count in `bit_range` for which the largest component
+/// error of `positions` (as computed by `analyze_quantization`) is at most `max_error`, or
+/// `None` if no bit count in the range is precise enough, e.g. for an infinite scale.
+///
+/// # Panics
+///
+/// Panics if `bit_range` is not within `1..=16`.
+pub fn select_pos_bits(
+    positions: &[f32],
+    max_error: f32,
+    bit_range: RangeInclusive<u32>,
+) -> Option<u32> {
+    bit_range
+        .into_iter()
+        .find(|&bits| measure_pos_quantization(positions, bits).0 <= max_error)
+}
+
+/// Like `select_pos_bits`, but for texture coordinates, with the error computed by
+/// `analyze_uv_quantization`.
+///
+/// # Panics
+///
+/// Panics if `bit_range` is not within `1..=16`.
+pub fn select_uv_bits(
+    coords: &[f32],
+    max_error: f32,
+    bit_range: RangeInclusive<u32>,
+) -> Option<u32> {
+    bit_range
+        .into_iter()
+        .find(|&bits| measure_uv_quantization(coords, bits).0 <= max_error)
+}
+
+/// Builder for a complete `OPTM` container: an `EncodeHeader`, a single `EncodeObject`,
+/// followed by the encoded vertex and index data.
+///
+/// Vertices are quantized into `PackedVertex` (positions and texture coordinates as
+/// unorm values relative to the mesh bounds, normals as 8-bit snorm) before encoding.
+pub struct EncodedMesh<'a> {
+    vertices: &'a [Vertex],
+    indices: &'a [u32],
+    pos_bits: i32,
+    uv_bits: i32,
+}
+
+/// Mesh data parsed back from an `OPTM` container produced by `EncodedMesh::encode`.
+#[derive(Debug, Clone)]
+pub struct DecodedMesh {
+    pub header: EncodeHeader,
+    pub objects: Vec<EncodeObject>,
+    /// Vertices with dequantized positions, normals and texture coordinates.
+    pub vertices: Vec<Vertex>,
+    pub indices: Vec<u32>,
+}
+
+impl<'a> EncodedMesh<'a> {
+    /// Creates a builder for the given vertices and triangle list indices,
+    /// using 14 bits for positions and 12 bits for texture coordinates.
+    pub fn new(vertices: &'a [Vertex], indices: &'a [u32]) -> Self {
+        Self {
+            vertices,
+            indices,
+            pos_bits: 14,
+            uv_bits: 12,
+        }
+    }
+
+    /// Sets the number of bits used to quantize positions (1..=16).
+    pub fn pos_bits(mut self, pos_bits: i32) -> Self {
+        self.pos_bits = pos_bits;
+        self
+    }
+
+    /// Sets the number of bits used to quantize texture coordinates (1..=16).
+    pub fn uv_bits(mut self, uv_bits: i32) -> Self {
+        self.uv_bits = uv_bits;
+        self
+    }
+
+    /// Quantizes and encodes the mesh, returning the serialized container.
+    pub fn encode(&self) -> Result<Vec<u8>> {
+        if !(1..=16).contains(&self.pos_bits) || !(1..=16).contains(&self.uv_bits) {
+            return Err(Error::Config(format!(
+                "pos_bits ({}) and uv_bits ({}) must be in 1..=16",
+                self.pos_bits, self.uv_bits
+            )));
+        }
+        if !self.indices.len().is_multiple_of(3) {
+            return Err(Error::memory("index count must be a multiple of 3"));
+        }
+
+        let positions: Vec<f32> = self.vertices.iter().flat_map(|v| v.p).collect();
+        let coords: Vec<f32> = self.vertices.iter().flat_map(|v| v.t).collect();
+
+        let (pos_offset, pos_scale) = calc_pos_offset_and_scale(&positions);
+        let (uv_offset, uv_scale) = calc_uv_offset_and_scale(&coords);
+
+        let pos_scale_inv = rcp_safe(pos_scale);
+        let uv_scale_inv = [rcp_safe(uv_scale[0]), rcp_safe(uv_scale[1])];
+
+        let quantized: Vec<PackedVertex> = self
+            .vertices
+            .iter()
+            .map(|v| {
+                let p = |i: usize| {
+                    quantize_unorm((v.p[i] - pos_offset[i]) * pos_scale_inv, self.pos_bits) as u16
+                };
+                let n = |i: usize| quantize_snorm(v.n[i], 8) as i8;
+                let t = |i: usize| {
+                    quantize_unorm((v.t[i] - uv_offset[i]) * uv_scale_inv[i], self.uv_bits) as u16
+                };
+                PackedVertex {
+                    p: [p(0), p(1), p(2), 0],
+                    n: [n(0), n(1), n(2), 0],
+                    t: [t(0), t(1)],
+                }
+            })
+            .collect();
+
+        let encoded_vertices = encode_vertex_buffer(&quantized)?;
+        let encoded_indices = encode_index_buffer(self.indices, self.vertices.len())?;
+
+        let mut header = EncodeHeader::new(
+            pos_offset,
+            pos_scale,
+            self.pos_bits,
+            uv_offset,
+            uv_scale,
+            self.uv_bits,
+        );
+        header.group_count = 1;
+        header.vertex_count = self.vertices.len() as u32;
+        header.index_count = self.indices.len() as u32;
+        header.vertex_data_size = encoded_vertices.len() as u32;
+        header.index_data_size = encoded_indices.len() as u32;
+
+        let object = EncodeObject {
+            index_offset: 0,
+            index_count: self.indices.len() as u32,
+            material_length: 0,
+            reserved: 0,
+        };
+
+        let mut result = Vec::with_capacity(
+            mem::size_of::<EncodeHeader>()
+                + mem::size_of::<EncodeObject>()
+                + encoded_vertices.len()
+                + encoded_indices.len(),
+        );
+        result.extend_from_slice(any_as_u8_slice(&header));
+        result.extend_from_slice(any_as_u8_slice(&object));
+        result.extend_from_slice(&encoded_vertices);
+        result.extend_from_slice(&encoded_indices);
+        Ok(result)
+    }
+
+    /// Parses a container produced by `encode`, decoding the index buffer and
+    /// dequantizing the vertices using the offsets and scales stored in the header.
+    pub fn decode(data: &[u8]) -> Result<DecodedMesh> {
+        let header: EncodeHeader = read_unaligned_at(data, 0)?;
+        if &header.magic != b"OPTM" {
+            return Err(Error::Parse("invalid magic, expected OPTM".into()));
+        }
+
+        let mut offset = mem::size_of::<EncodeHeader>();
+        let mut objects = Vec::with_capacity(header.group_count as usize);
+        for _ in 0..header.group_count {
+            objects.push(read_unaligned_at::<EncodeObject>(data, offset)?);
+            offset += mem::size_of::<EncodeObject>();
+        }
+        for object in &objects {
+            offset += object.material_length as usize;
+        }
+
+        let vertex_data = byte_range(data, offset, header.vertex_data_size as usize)?;
+        offset += vertex_data.len();
+        let index_data = byte_range(data, offset, header.index_data_size as usize)?;
+
+        let packed: Vec<PackedVertex> =
+            decode_vertex_buffer(vertex_data, header.vertex_count as usize)?;
+        let indices: Vec<u32> = decode_index_buffer(index_data, header.index_count as usize)?;
+
+        let vertices = packed
+            .iter()
+            .map(|v| Vertex {
+                p: header.dequantize_position([0, 1, 2].map(|i| u32::from(v.p[i]))),
+                n: [
+                    f32::from(v.n[0]) / 127f32,
+                    f32::from(v.n[1]) / 127f32,
+                    f32::from(v.n[2]) / 127f32,
+                ],
+                t: header.dequantize_uv(v.t.map(u32::from)),
+            })
+            .collect();
+
+        Ok(DecodedMesh {
+            header,
+            objects,
+            vertices,
+            indices,
+        })
+    }
+}
+
+fn byte_range(data: &[u8], offset: usize, len: usize) -> Result<&[u8]> {
+    offset
+        .checked_add(len)
+        .and_then(|end| data.get(offset..end))
+        .ok_or_else(|| {
+            Error::Parse(format!(
+                "unexpected end of data: need {} bytes at offset {}, have {}",
+                len,
+                offset,
+                data.len()
+            ))
+        })
+}
+
+fn read_unaligned_at<T: Copy>(data: &[u8], offset: usize) -> Result<T> {
+    let bytes = byte_range(data, offset, mem::size_of::<T>())?;
+    Ok(unsafe { core::ptr::read_unaligned(bytes.as_ptr().cast::<T>()) })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{grid_indices, rotate_triangle};
+
+    #[test]
+    fn test_calc_uv_offset_and_scale_extent() {
+        // the scale is the extent of the coordinates on each axis, not the `f32::MAX` the fold
+        // used to start from
+        let coords = [2.0, -1.0, 3.0, 1.0, 2.5, 0.0];
+        assert_eq!(calc_uv_offset_and_scale(&coords), ([2.0, -1.0], [1.0, 2.0]));
+        assert_eq!(
+            calc_uv_offset_and_scale_inverse(&coords),
+            ([2.0, -1.0], [1.0, 0.5])
+        );
+    }
+
+    #[test]
+    fn test_encoded_mesh_round_trip() {
+        let vertices: Vec<Vertex> = (0..16)
+            .map(|i| {
+                let x = (i % 4) as f32;
+                let y = (i / 4) as f32;
+                Vertex {
+                    p: [x * 0.5 - 1.0, y * 0.25, (x * y).sin()],
+                    n: [0.0, 0.0, 1.0],
+                    t: [x / 3.0, y / 3.0],
+                }
+            })
+            .collect();
+        let indices = grid_indices(3);
+
+        let encoded = EncodedMesh::new(&vertices, &indices).encode().unwrap();
+        let decoded = EncodedMesh::decode(&encoded).unwrap();
+
+        // the index codec may rotate triangles, but preserves their order and winding
+        assert!(decoded
+            .indices
+            .chunks(3)
+            .map(rotate_triangle)
+            .eq(indices.chunks(3).map(rotate_triangle)));
+        assert_eq!(decoded.objects.len(), 1);
+        assert_eq!(decoded.vertices.len(), vertices.len());
+
+        let pos_tolerance = decoded.header.pos_scale;
+        let uv_tolerance = decoded.header.uv_scale[0].max(decoded.header.uv_scale[1]);
+        for (original, decoded) in vertices.iter().zip(&decoded.vertices) {
+            for i in 0..3 {
+                assert!((original.p[i] - decoded.p[i]).abs() <= pos_tolerance);
+                assert!((original.n[i] - decoded.n[i]).abs() <= 1.0 / 127.0);
+            }
+            for i in 0..2 {
+                assert!((original.t[i] - decoded.t[i]).abs() <= uv_tolerance);
+            }
+        }
+
+        EncodedMesh::decode(&encoded[..encoded.len() - 1]).expect_err("should fail");
+    }
+
+    #[test]
+    fn test_calc_offset_and_scale_empty() {
+        assert_eq!(calc_pos_offset_and_scale(&[]), ([0.0; 3], 0.0));
+        assert_eq!(calc_uv_offset_and_scale(&[]), ([0.0; 2], [0.0; 2]));
+    }
+
+    #[test]
+    fn test_calc_offset_and_scale() {
+        let positions = [1.0, 2.0, 3.0, -1.0, 4.0, 3.5];
+        assert_eq!(
+            calc_pos_offset_and_scale(&positions),
+            ([-1.0, 2.0, 3.0], 2.0)
+        );
+        let coords = [0.25, 0.5, 0.75, 0.0];
+        assert_eq!(calc_uv_offset_and_scale(&coords), ([0.25, 0.0], [0.5, 0.5]));
+    }
+
+    #[test]
+    fn test_calc_offset_and_scale_inverse_small_extent() {
+        // extents below one unit must not quantize everything to zero
+        let positions = [0.0, 0.0, 0.0, 0.25, 0.125, 0.0];
+        assert_eq!(
+            calc_pos_offset_and_scale_inverse(&positions),
+            ([0.0; 3], 4.0)
+        );
+        let coords = [0.0, 0.0, 0.5, 0.25];
+        assert_eq!(
+            calc_uv_offset_and_scale_inverse(&coords),
+            ([0.0; 2], [2.0, 4.0])
+        );
+    }
+
+    #[test]
+    fn test_calc_offset_and_scale_non_finite() {
+        let positions = [1.0, 2.0, 3.0, -1.0, 4.0, 3.5];
+        let mut with_nan = positions.to_vec();
+        with_nan.extend_from_slice(&[f32::NAN, f32::NAN, f32::NAN]);
+        with_nan.extend_from_slice(&[f32::INFINITY, 2.5, f32::NEG_INFINITY]);
+        assert_eq!(
+            calc_pos_offset_and_scale(&with_nan),
+            calc_pos_offset_and_scale(&positions)
+        );
+
+        let coords = [0.25, 0.5, 0.75, 0.0];
+        let mut with_nan = coords.to_vec();
+        with_nan.extend_from_slice(&[f32::NAN, f32::NAN, f32::INFINITY, 0.25]);
+        assert_eq!(
+            calc_uv_offset_and_scale(&with_nan),
+            calc_uv_offset_and_scale(&coords)
+        );
+
+        assert_eq!(calc_pos_offset_and_scale(&[f32::NAN; 3]), ([0.0; 3], 0.0));
+    }
+
+    #[test]
+    #[cfg_attr(debug_assertions, should_panic)]
+    fn test_calc_pos_offset_and_scale_malformed() {
+        // without debug assertions, the trailing partial position is ignored
+        let positions = [1.0, 2.0, 3.0, -1.0, 4.0, 3.5, 100.0];
+        assert_eq!(
+            calc_pos_offset_and_scale(&positions),
+            ([-1.0, 2.0, 3.0], 2.0)
+        );
+    }
+
+    #[test]
+    #[cfg_attr(debug_assertions, should_panic)]
+    fn test_calc_uv_offset_and_scale_malformed() {
+        let coords = [0.25, 0.5, 0.75, 0.0, 100.0];
+        assert_eq!(calc_uv_offset_and_scale(&coords), ([0.25, 0.0], [0.5, 0.5]));
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_analyze_quantization() {
+        let mut positions: Vec<f32> = (0..100)
+            .flat_map(|i| {
+                let f = i as f32 * 0.01;
+                [f, (f * 7.0).sin(), 1.0 - f]
+            })
+            .collect();
+
+        let error = analyze_quantization(&positions, 14);
+        assert!(error.max_abs > 0.0 && error.max_abs <= 1.0 / 16383.0);
+        assert!(error.rms > 0.0 && error.rms <= error.max_abs);
+        assert!(analyze_quantization(&positions, 8).max_abs > error.max_abs);
+
+        // an outlier increases the scale, and with it the error of every other vertex
+        positions.extend_from_slice(&[1000.0, 0.0, 0.0, f32::NAN, 0.5, 0.5]);
+        let outlier = analyze_quantization(&positions, 14);
+        assert!(outlier.max_abs > error.max_abs * 100.0);
+        assert!(outlier.worst_vertex_index < 100);
+
+        assert_eq!(analyze_quantization(&[], 14), QuantizationError::default());
+        assert_eq!(analyze_quantization(&[1.0, 2.0, 3.0], 14).max_abs, 0.0);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_analyze_uv_quantization() {
+        let coords: Vec<f32> = (0..100)
+            .flat_map(|i| [i as f32 / 99.0, 2.0 + i as f32 * 0.05])
+            .collect();
+
+        let error = analyze_uv_quantization(&coords, 12);
+        assert!(error.max_abs > 0.0 && error.max_abs <= 4.95 / 4095.0);
+        assert!(error.rms > 0.0 && error.rms <= error.max_abs);
+        assert!(analyze_uv_quantization(&coords, 6).max_abs > error.max_abs);
+    }
+
+    #[test]
+    fn test_select_pos_bits() {
+        let positions: Vec<f32> = (0..100)
+            .flat_map(|i| {
+                let f = i as f32 * 0.01;
+                [f, 0.5 - f * 0.25, 1.0 - f]
+            })
+            .collect();
+
+        let bits = select_pos_bits(&positions, 1e-3, 1..=16).unwrap();
+        assert!(measure_pos_quantization(&positions, bits).0 <= 1e-3);
+        assert!(measure_pos_quantization(&positions, bits - 1).0 > 1e-3);
+        assert_eq!(
+            select_pos_bits(&positions, 1e-3, bits + 2..=16),
+            Some(bits + 2)
+        );
+        assert_eq!(select_pos_bits(&positions, 1e-9, 1..=16), None);
+
+        // a mesh whose extent overflows to infinity can't be quantized
+        let huge = [-f32::MAX, 0.0, 0.0, f32::MAX, 0.0, 0.0];
+        assert_eq!(select_pos_bits(&huge, 1.0, 1..=16), None);
+    }
+
+    #[test]
+    fn test_select_uv_bits() {
+        let coords: Vec<f32> = (0..256).flat_map(|i| [i as f32 / 255.0, 0.5]).collect();
+        // every coordinate is exactly representable with 8 bits
+        assert_eq!(select_uv_bits(&coords, 1e-6, 1..=16), Some(8));
+        assert_eq!(select_uv_bits(&coords, 1e-6, 1..=7), None);
+    }
+
+    #[test]
+    fn test_encode_header_dequantize() {
+        let positions = [-1.5f32, 2.0, 0.25, 3.0, -0.5, 4.0, 0.1, 0.2, 0.3];
+        let coords = [0.0f32, 0.5, 1.0, 0.25, 0.75, 1.0];
+        let (pos_offset, pos_scale) = calc_pos_offset_and_scale(&positions);
+        let (uv_offset, uv_scale) = calc_uv_offset_and_scale(&coords);
+        let header = EncodeHeader::new(pos_offset, pos_scale, 14, uv_offset, uv_scale, 12);
+
+        let pos_scale_inv = rcp_safe(pos_scale);
+        for position in positions.chunks_exact(3) {
+            let quantized: [u32; 3] = core::array::from_fn(|i| {
+                quantize_unorm((position[i] - pos_offset[i]) * pos_scale_inv, 14) as u32
+            });
+            let dequantized = header.dequantize_position(quantized);
+            for i in 0..3 {
+                assert!((dequantized[i] - position[i]).abs() <= pos_scale / 16383.0);
+            }
+        }
+
+        for coord in coords.chunks_exact(2) {
+            let quantized: [u32; 2] = core::array::from_fn(|i| {
+                quantize_unorm((coord[i] - uv_offset[i]) * rcp_safe(uv_scale[i]), 12) as u32
+            });
+            let dequantized = header.dequantize_uv(quantized);
+            for i in 0..2 {
+                assert!((dequantized[i] - coord[i]).abs() <= uv_scale[i] / 4095.0);
+            }
+        }
+    }
+
+    #[test]
+    fn test_encode_vertex_buffer_with_count() {
+        let vertices: Vec<[f32; 3]> = (0..100)
+            .map(|i| [i as f32, (i * 2) as f32, (i % 7) as f32])
+            .collect();
+        let (encoded, count) = encode_vertex_buffer_with_count(&vertices).unwrap();
+        assert_eq!(count, vertices.len());
+        assert_eq!(encoded, encode_vertex_buffer(&vertices).unwrap());
+
+        let decoded: Vec<[f32; 3]> = decode_vertex_buffer(&encoded, count).unwrap();
+        assert_eq!(decoded, vertices);
+    }
+
+    #[test]
+    fn test_decode_vertex_buffer_into_bytes() {
+        let vertices: Vec<[f32; 3]> = (0..100)
+            .map(|i| [i as f32, (i * 2) as f32, (i % 7) as f32])
+            .collect();
+        let encoded = encode_vertex_buffer(&vertices).unwrap();
+        let decoded: Vec<[f32; 3]> = decode_vertex_buffer(&encoded, vertices.len()).unwrap();
+
+        let mut out = vec![0u8; vertices.len() * 12];
+        decode_vertex_buffer_into_bytes(&encoded, &mut out, vertices.len(), 12).unwrap();
+        assert_eq!(out, crate::typed_to_bytes(&decoded));
+
+        let mut short = vec![0u8; vertices.len() * 12 - 4];
+        assert!(decode_vertex_buffer_into_bytes(&encoded, &mut short, vertices.len(), 12).is_err());
+        assert!(matches!(
+            decode_vertex_buffer_into_bytes(&encoded[..8], &mut out, vertices.len(), 12),
+            Err(Error::BufferTooSmall)
+        ));
+    }
+
+    #[test]
+    fn test_decode_index_buffer_checked() {
+        // encoded for a larger vertex buffer than the one it is decoded for
+        let indices = [0u32, 1, 2, 2, 1, 3, 2, 3, 6];
+        let encoded = encode_index_buffer(&indices, 7).unwrap();
+
+        let decoded: Vec<u16> = decode_index_buffer_checked(&encoded, indices.len(), 7).unwrap();
+        assert_eq!(decoded.len(), indices.len());
+
+        let error = decode_index_buffer_checked::<u32>(&encoded, indices.len(), 4).unwrap_err();
+        match error {
+            Error::Memory(message) => assert!(message.contains("(6)"), "{}", message),
+            error => panic!("unexpected error {:?}", error),
+        }
+
+        assert!(matches!(
+            decode_index_buffer_checked::<u32>(&encoded[..8], indices.len(), 7),
+            Err(Error::BufferTooSmall)
+        ));
+    }
+
+    #[test]
+    fn test_validate_encoded_buffers() {
+        let vertices: Vec<[f32; 4]> = (0..300).map(|i| [i as f32, 0.5, -1.0, 2.0]).collect();
+        let encoded = encode_vertex_buffer(&vertices).unwrap();
+        assert!(validate_encoded_vertex_buffer(&encoded, vertices.len(), 16).is_ok());
+        assert!(matches!(
+            validate_encoded_vertex_buffer(&encoded[..8], vertices.len(), 16),
+            Err(Error::BufferTooSmall)
+        ));
+        assert!(matches!(
+            validate_encoded_vertex_buffer(&[], 0, 16),
+            Err(Error::BufferTooSmall)
+        ));
+        let mut garbled = encoded.clone();
+        garbled[0] = 0x12;
+        assert!(matches!(
+            validate_encoded_vertex_buffer(&garbled, vertices.len(), 16),
+            Err(Error::UnsupportedVersion)
+        ));
+        assert!(validate_encoded_vertex_buffer(&encoded, vertices.len(), 6).is_err());
+
+        let indices = [0u32, 1, 2, 2, 1, 3, 2, 3, 4];
+        let encoded = encode_index_buffer(&indices, 5).unwrap();
+        assert!(validate_encoded_index_buffer(&encoded, indices.len()).is_ok());
+        assert!(matches!(
+            validate_encoded_index_buffer(&encoded[..10], indices.len()),
+            Err(Error::BufferTooSmall)
+        ));
+        assert!(validate_encoded_index_buffer(&encoded, 4).is_err());
+        let mut garbled = encoded.clone();
+        garbled[0] = 0xd1; // an index sequence header
+        assert!(matches!(
+            validate_encoded_index_buffer(&garbled, indices.len()),
+            Err(Error::UnsupportedVersion)
+        ));
+        garbled[0] = 0xef;
+        assert!(matches!(
+            validate_encoded_index_buffer(&garbled, indices.len()),
+            Err(Error::UnsupportedVersion)
+        ));
+    }
+
+    #[test]
+    fn test_encode_index_buffer_errors() {
+        // indices far out of range of vertex_count overflow the bound estimate
+        let indices: Vec<u32> = (0..300u32).map(|i| i.wrapping_mul(2_654_435_761)).collect();
+        assert!(encode_index_buffer(&indices, 1).is_err());
+        assert!(encode_index_buffer(&indices, u32::MAX as usize).is_ok());
+
+        assert!(encode_index_buffer(&[0, 1], 2).is_err());
+    }
+
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn test_decode_vertex_buffers_par() {
+        let buffers: Vec<Vec<[f32; 4]>> = (0..16)
+            .map(|b| {
+                (0..64 + b * 7)
+                    .map(|i| {
+                        let f = (i + b) as f32;
+                        [f, f * 0.5, (f * 0.1).sin(), 1.0]
+                    })
+                    .collect()
+            })
+            .collect();
+        let encoded: Vec<Vec<u8>> = buffers
+            .iter()
+            .map(|vertices| encode_vertex_buffer(vertices).unwrap())
+            .collect();
+        let encoded_refs: Vec<&[u8]> = encoded.iter().map(Vec::as_slice).collect();
+        let counts: Vec<usize> = buffers.iter().map(Vec::len).collect();
+
+        let serial: Vec<Vec<[f32; 4]>> = encoded_refs
+            .iter()
+            .zip(&counts)
+            .map(|(encoded, &count)| decode_vertex_buffer(encoded, count).unwrap())
+            .collect();
+        let parallel = decode_vertex_buffers_par::<[f32; 4]>(&encoded_refs, &counts).unwrap();
+        assert_eq!(parallel, serial);
+        assert_eq!(parallel, buffers);
+
+        assert!(decode_vertex_buffers_par::<[f32; 4]>(&encoded_refs, &counts[1..]).is_err());
+        let mut truncated = encoded_refs.clone();
+        truncated[3] = &encoded[3][..4];
+        assert!(decode_vertex_buffers_par::<[f32; 4]>(&truncated, &counts).is_err());
+    }
+
+    #[test]
+    fn test_index_buffer_u8_meshlets() {
+        let indices = grid_indices(8);
+        let meshlets = crate::build_meshlets_scan(&indices, 81, 64, 124).unwrap();
+        for meshlet in meshlets.iter() {
+            let encoded = encode_index_buffer_u8(meshlet.triangles).unwrap();
+            let decoded = decode_index_buffer_u8(&encoded, meshlet.triangles.len()).unwrap();
+            // the codec may rotate triangles, but keeps their order and winding
+            for (original, decoded) in meshlet
+                .triangles
+                .chunks_exact(3)
+                .zip(decoded.chunks_exact(3))
+            {
+                assert_eq!(rotate_triangle(original), rotate_triangle(decoded));
+            }
+        }
+
+        assert!(encode_index_buffer_u8(&[0, 1]).is_err());
+        let wide = encode_index_buffer(&[0, 1, 300], 301).unwrap();
+        assert!(matches!(
+            decode_index_buffer_u8(&wide, 3),
+            Err(Error::CorruptData)
+        ));
+    }
+
+    #[test]
+    fn test_encoded_sizes() {
+        let mut indices = Vec::new();
+        for y in 0..8u32 {
+            for x in 0..8u32 {
+                let i = y * 9 + x;
+                indices.extend_from_slice(&[i, i + 1, i + 9, i + 1, i + 10, i + 9]);
+            }
+        }
+        let vertices: Vec<[f32; 3]> = (0..81)
+            .map(|i| [(i % 9) as f32, (i / 9) as f32, 0.0])
+            .collect();
+
+        // repeated calls reuse the scratch buffer for inputs of different sizes
+        for count in [81, 9, 81] {
+            assert_eq!(
+                encoded_vertex_buffer_size(&vertices[..count]).unwrap(),
+                encode_vertex_buffer(&vertices[..count]).unwrap().len()
+            );
+        }
+        for count in [indices.len(), 6, indices.len()] {
+            assert_eq!(
+                encoded_index_buffer_size(&indices[..count], 81).unwrap(),
+                encode_index_buffer(&indices[..count], 81).unwrap().len()
+            );
+        }
+
+        assert!(encoded_vertex_buffer_size(&[[0u8; 3]]).is_err());
+        assert!(encoded_index_buffer_size(&indices[..4], 81).is_err());
+    }
+
+    #[test]
+    fn test_encode_to_writer() {
+        let indices: Vec<u32> = (0..8u32)
+            .flat_map(|i| [i, i + 1, i + 9, i + 1, i + 10, i + 9])
+            .collect();
+        let vertices: Vec<[f32; 3]> = (0..18)
+            .map(|i| [(i % 9) as f32, (i / 9) as f32, 0.5])
+            .collect();
+
+        // both encoders append to the same sink, as when serializing a container
+        let mut sink = vec![0xAAu8; 3];
+        let vertex_size = encode_vertex_buffer_to(&vertices, &mut sink).unwrap();
+        let index_size = encode_index_buffer_to(&indices, 18, &mut sink).unwrap();
+
+        let encoded_vertices = encode_vertex_buffer(&vertices).unwrap();
+        let encoded_indices = encode_index_buffer(&indices, 18).unwrap();
+        assert_eq!(vertex_size, encoded_vertices.len());
+        assert_eq!(index_size, encoded_indices.len());
+        assert_eq!(sink[..3], [0xAA; 3]);
+        assert_eq!(sink[3..3 + vertex_size], encoded_vertices[..]);
+        assert_eq!(sink[3 + vertex_size..], encoded_indices[..]);
+
+        let mut full = [0u8; 4];
+        assert!(matches!(
+            encode_vertex_buffer_to(&vertices, &mut &mut full[..]),
+            Err(Error::Io(_))
+        ));
+        assert!(encode_index_buffer_to(&indices[..4], 18, &mut sink).is_err());
+    }
+
+    #[test]
+    fn test_decode_vertex_buffer_from_reader() {
+        let vertices: Vec<[f32; 3]> = (0..40)
+            .map(|i| [(i % 8) as f32, (i / 8) as f32, 0.25])
+            .collect();
+        let encoded = encode_vertex_buffer(&vertices).unwrap();
+
+        // the encoded data sits between other bytes in the stream
+        let mut stream = vec![1u8, 2, 3];
+        stream.extend_from_slice(&encoded);
+        stream.push(4);
+        let mut reader = std::io::Cursor::new(&stream);
+        reader.set_position(3);
+        let decoded: Vec<[f32; 3]> =
+            decode_vertex_buffer_from(&mut reader, vertices.len(), encoded.len()).unwrap();
+        assert_eq!(
+            decoded,
+            decode_vertex_buffer::<[f32; 3]>(&encoded, vertices.len()).unwrap()
+        );
+        assert_eq!(decoded, vertices);
+        assert_eq!(reader.position() as usize, 3 + encoded.len());
+
+        let mut short = std::io::Cursor::new(&encoded[..encoded.len() - 1]);
+        assert!(matches!(
+            decode_vertex_buffer_from::<[f32; 3], _>(&mut short, vertices.len(), encoded.len()),
+            Err(Error::Io(_))
+        ));
+    }
+
+    #[test]
+    fn test_index_decoder_byte_at_a_time() {
+        let mut indices = Vec::new();
+        for y in 0..8u32 {
+            for x in 0..8u32 {
+                let i = y * 9 + x;
+                indices.extend_from_slice(&[i, i + 1, i + 9, i + 1, i + 10, i + 9]);
+            }
+        }
+        let encoded = encode_index_buffer(&indices, 81).unwrap();
+        let expected = decode_index_buffer::<u32>(&encoded, indices.len()).unwrap();
+
+        let mut decoder = IndexDecoder::new(indices.len(), encoded.len());
+        for (i, byte) in encoded.iter().enumerate() {
+            assert!(!decoder.is_complete());
+            assert_eq!(decoder.decodable_index_count(), 0);
+            assert!(decoder.take_decoded().is_empty());
+            decoder.feed(core::slice::from_ref(byte)).unwrap();
+            assert_eq!(decoder.received_len(), i + 1);
+        }
+        assert!(decoder.is_complete());
+        assert_eq!(decoder.remaining_len(), 0);
+        assert_eq!(decoder.decodable_index_count(), indices.len());
+        assert_eq!(decoder.take_decoded(), expected);
+        assert!(decoder.take_decoded().is_empty());
+
+        assert!(decoder.feed(&[0]).is_err());
+    }
+
+    #[test]
+    fn test_index_decoder_corrupt() {
+        let indices = [0u32, 1, 2, 2, 1, 3];
+        let mut encoded = encode_index_buffer(&indices, 4).unwrap();
+        encoded[0] = 0xff;
+
+        let mut decoder = IndexDecoder::new(indices.len(), encoded.len());
+        let (head, tail) = encoded.split_at(3);
+        decoder.feed(head).unwrap();
+        assert!(decoder.feed(tail).is_err());
+        assert!(!decoder.is_complete());
+        assert!(decoder.take_decoded().is_empty());
+    }
+
+    #[test]
+    fn test_vertex_streams() {
+        let size = 32;
+        let vertices: Vec<Vertex> = (0..size * size)
+            .map(|i| {
+                let x = (i % size) as f32 / size as f32;
+                let y = (i / size) as f32 / size as f32;
+                let z = (x * 6.0).sin() * (y * 4.0).cos() * 0.1;
+                Vertex {
+                    p: [x, y, z],
+                    n: [-z, 0.2, 1.0],
+                    t: [x, 1.0 - y],
+                }
+            })
+            .collect();
+        let streams = [
+            unsafe { VertexStream::from_slice_with_offset::<[f32; 3], _>(&vertices, 0) },
+            unsafe { VertexStream::from_slice_with_offset::<[f32; 3], _>(&vertices, 12) },
+            unsafe { VertexStream::from_slice_with_offset::<[f32; 2], _>(&vertices, 24) },
+        ];
+
+        let separate = encode_vertex_streams(vertices.len(), &streams).unwrap();
+        let interleaved = encode_vertex_buffer(&vertices).unwrap();
+        let separate_size: usize = separate.iter().map(Vec::len).sum();
+        // the codec already encodes every byte of a vertex as its own column, so splitting
+        // the streams only adds the fixed header and tail (1 + 32 bytes) of each extra buffer
+        assert!(separate_size <= interleaved.len() + (streams.len() - 1) * 33);
+
+        let encoded: Vec<&[u8]> = separate.iter().map(Vec::as_slice).collect();
+        let decoded = decode_vertex_streams(&encoded, vertices.len(), &[12, 12, 8]).unwrap();
+        let positions: Vec<[f32; 3]> = vertices.iter().map(|v| v.p).collect();
+        let normals: Vec<[f32; 3]> = vertices.iter().map(|v| v.n).collect();
+        let uvs: Vec<[f32; 2]> = vertices.iter().map(|v| v.t).collect();
+        assert_eq!(decoded[0], crate::typed_to_bytes(&positions));
+        assert_eq!(decoded[1], crate::typed_to_bytes(&normals));
+        assert_eq!(decoded[2], crate::typed_to_bytes(&uvs));
+
+        assert!(decode_vertex_streams(&encoded, vertices.len(), &[12, 12]).is_err());
+        assert!(decode_vertex_streams(&encoded, vertices.len(), &[12, 12, 6]).is_err());
+        let odd = [unsafe { VertexStream::from_slice_with_offset::<[u8; 3], _>(&vertices, 0) }];
+        assert!(encode_vertex_streams(vertices.len(), &odd).is_err());
+
+        // more vertices than a slice-backed stream holds would read past its end
+        let positions_stream = [unsafe { VertexStream::from_slice(&positions) }];
+        assert!(encode_vertex_streams(vertices.len(), &positions_stream).is_ok());
+        assert!(encode_vertex_streams(vertices.len() + 1, &positions_stream).is_err());
+    }
+
+    #[test]
+    fn test_decode_undersized_buffers() {
+        let vertices: Vec<[f32; 4]> = (0..100).map(|i| [i as f32, 0.0, 1.0, 2.0]).collect();
+        let encoded = encode_vertex_buffer(&vertices).unwrap();
+        assert!(decode_vertex_buffer::<[f32; 4]>(&encoded, vertices.len()).is_ok());
+        for vertex_count in [100_000, usize::MAX] {
+            assert!(matches!(
+                decode_vertex_buffer::<[f32; 4]>(&encoded, vertex_count),
+                Err(Error::BufferTooSmall)
+            ));
+            assert!(matches!(
+                decode_vertex_streams(&[&encoded], vertex_count, &[16]),
+                Err(Error::BufferTooSmall)
+            ));
+        }
+        assert!(decode_vertex_buffer::<[f32; 4]>(&encoded[..10], vertices.len()).is_err());
+        assert!(decode_vertex_buffer::<[u8; 3]>(&encoded, vertices.len()).is_err());
+        assert!(encode_vertex_buffer(&[[0u8; 3]; 4]).is_err());
+
+        let indices: Vec<u32> = (0..30).collect();
+        let encoded = encode_index_buffer(&indices, 30).unwrap();
+        assert!(decode_index_buffer::<u32>(&encoded, indices.len()).is_ok());
+        for index_count in [3_000, usize::MAX / 3 * 3] {
+            assert!(matches!(
+                decode_index_buffer::<u32>(&encoded, index_count),
+                Err(Error::BufferTooSmall)
+            ));
+        }
+        assert!(decode_index_buffer::<u32>(&encoded, 29).is_err());
+    }
+}
@@ -1,5 +1,7 @@
+use alloc::{borrow::Cow, string::String};
+
 /// A type alias for handling errors throughout meshopt
-pub type Result<T> = std::result::Result<T, Error>;
+pub type Result<T> = core::result::Result<T, Error>;
 
 /// An error that can occur
 #[derive(Debug, thiserror::Error)]
@@ -9,15 +11,30 @@ pub enum Error {
     #[error("native error: {0}")]
     Native(i32),
 
+    /// The encoded data uses an unsupported version or has an invalid header
+    /// (native decoder code -1).
+    #[error("unsupported encoding version or invalid header")]
+    UnsupportedVersion,
+
+    /// The encoded data is shorter than the decoded size requires (native decoder code -2).
+    #[error("encoded data is truncated")]
+    BufferTooSmall,
+
+    /// The encoded data is inconsistent with the requested element count or size
+    /// (native decoder code -3).
+    #[error("encoded data is corrupt")]
+    CorruptData,
+
     /// An error that occurred while accessing or allocating memory
     #[error("memory error: {0}")]
-    Memory(std::borrow::Cow<'static, str>),
+    Memory(Cow<'static, str>),
 
     /// An error that occurred while parsing a data source
     #[error("parse error: {0}")]
     Parse(String),
 
     /// An error that occurred while working with a file path.
+    #[cfg(feature = "std")]
     #[error("path error: {0}")]
     Path(std::path::PathBuf),
 
@@ -30,6 +47,7 @@ pub enum Error {
     Config(String),
 
     /// An unexpected I/O error occurred.
+    #[cfg(feature = "std")]
     #[error(transparent)]
     Io(#[from] std::io::Error),
     // An error occurred while parsing a number in a free-form query.
@@ -39,12 +57,22 @@ pub enum Error {
 impl Error {
     #[inline]
     pub(crate) fn memory(msg: &'static str) -> Self {
-        Self::Memory(std::borrow::Cow::Borrowed(msg))
+        Self::Memory(Cow::Borrowed(msg))
     }
 
     #[inline]
     pub(crate) fn memory_dynamic(msg: String) -> Self {
-        Self::Memory(std::borrow::Cow::Owned(msg))
+        Self::Memory(Cow::Owned(msg))
+    }
+
+    /// Maps a non-zero return code of the native decoders to an error.
+    pub(crate) fn from_native(code: i32) -> Self {
+        match code {
+            -1 => Self::UnsupportedVersion,
+            -2 => Self::BufferTooSmall,
+            -3 => Self::CorruptData,
+            _ => Self::Native(code),
+        }
     }
 }
 
@@ -53,6 +81,42 @@ pub(crate) fn error_or<T>(code: i32, ok: T) -> Result<T> {
     if code == 0 {
         Ok(ok)
     } else {
-        Err(Error::Native(code))
+        Err(Error::from_native(code))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{decode_vertex_buffer, encode_vertex_buffer};
+
+    #[test]
+    fn test_native_codes() {
+        assert!(matches!(Error::from_native(-1), Error::UnsupportedVersion));
+        assert!(matches!(Error::from_native(-2), Error::BufferTooSmall));
+        assert!(matches!(Error::from_native(-3), Error::CorruptData));
+        assert!(matches!(Error::from_native(-4), Error::Native(-4)));
+        assert!(error_or(0, ()).is_ok());
+    }
+
+    #[test]
+    fn test_decode_errors() {
+        let vertices: Vec<[f32; 3]> = (0..64).map(|i| [i as f32, 0.0, 1.0]).collect();
+        let encoded = encode_vertex_buffer(&vertices).unwrap();
+
+        let mut bad_header = encoded.clone();
+        bad_header[0] = 0;
+        assert!(matches!(
+            decode_vertex_buffer::<[f32; 3]>(&bad_header, vertices.len()),
+            Err(Error::UnsupportedVersion)
+        ));
+        assert!(matches!(
+            decode_vertex_buffer::<[f32; 3]>(&encoded[..1], vertices.len()),
+            Err(Error::BufferTooSmall)
+        ));
+        assert!(matches!(
+            decode_vertex_buffer::<[f32; 3]>(&encoded, vertices.len() / 2),
+            Err(Error::CorruptData)
+        ));
     }
 }
@@ -1,3 +1,5 @@
+// Some lints in the standard set below have been renamed or removed in newer toolchains
+#![allow(renamed_and_removed_lints)]
 // BEGIN - Embark standard lints v5 for Rust 1.55+
 // do not change or add/remove here, but one can add exceptions after this section
 // for more info see: <https://github.com/EmbarkStudios/rust-ecosystem/issues/59>
@@ -81,6 +83,9 @@
 // crate-specific exceptions:
 // This crate is doing a lot of FFI and byte munging
 #![allow(unsafe_code)]
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
 
 pub mod analyze;
 pub mod clusterize;
@@ -95,32 +100,49 @@ pub mod simplify;
 pub mod stripify;
 pub mod utilities;
 
+#[cfg(test)]
+mod test_utils;
+
 pub use crate::{
     analyze::*, clusterize::*, encoding::*, error::*, optimize::*, packing::*, remap::*, shadow::*,
     simplify::*, stripify::*, utilities::*,
 };
-use std::marker::PhantomData;
+use alloc::{format, vec::Vec};
+use core::marker::PhantomData;
 
 /// Vertex attribute stream, similar to `glVertexPointer`
 ///
 /// Each element takes size bytes, with stride controlling
 /// the spacing between successive elements.
+///
+/// The fields can only be read through accessors, so a checked stream can't be changed
+/// to read past the end of its data:
+///
+/// ```compile_fail
+/// let data = [0u8; 16];
+/// let mut stream = meshopt::VertexStream::from_bytes(&data, 1, 1).unwrap();
+/// stream.stride = 64;
+/// ```
 #[derive(Debug, Copy, Clone)]
 pub struct VertexStream<'a> {
-    /// Pointer to buffer which contains vertex data.
-    pub data: *const u8,
-    /// Space between vertices inside the buffer (in bytes).
-    pub stride: usize,
-    /// The size in bytes of the vertex attribute this Stream is representing.
-    pub size: usize,
+    data: *const u8,
+    stride: usize,
+    size: usize,
+
+    /// Number of vertices in the buffer, if the stream was created from a slice.
+    vertex_count: Option<usize>,
 
     _marker: PhantomData<&'a ()>,
 }
 
 impl<'a> VertexStream<'a> {
     /// Create a `VertexStream` for a buffer consisting only of elements of type `T`.
-    pub fn new<T>(ptr: *const T) -> VertexStream<'a> {
-        Self::new_with_stride::<T, T>(ptr, std::mem::size_of::<T>())
+    ///
+    /// # Safety
+    ///
+    /// Same as [`VertexStream::new_with_stride`].
+    pub unsafe fn new<T>(ptr: *const T) -> VertexStream<'a> {
+        unsafe { Self::new_with_stride::<T, T>(ptr, core::mem::size_of::<T>()) }
     }
 
     /// Create a `VertexStream` for a buffer that contains elements of type `VertexType`.
@@ -130,16 +152,272 @@ impl<'a> VertexStream<'a> {
     ///
     /// (The `VertexType` does not need to be a concrete type,
     /// it is only used here to avoid casts on the caller side).
-    pub fn new_with_stride<T, VertexType>(
+    ///
+    /// Prefer [`VertexStream::from_slice_with_offset`] or [`VertexStream::from_bytes`], which
+    /// know the number of vertices in the buffer.
+    ///
+    /// # Safety
+    ///
+    /// The stream doesn't know how many vertices the buffer holds, so `ptr` must be valid for
+    /// reads of `size_of::<T>()` bytes at every multiple of `stride` for as many vertices as
+    /// the stream is used with, for the lifetime `'a`.
+    pub unsafe fn new_with_stride<T, VertexType>(
         ptr: *const VertexType,
         stride: usize,
     ) -> VertexStream<'a> {
         VertexStream {
             data: ptr.cast(),
             stride,
-            size: std::mem::size_of::<T>(),
+            size: core::mem::size_of::<T>(),
+            vertex_count: None,
+
+            _marker: PhantomData,
+        }
+    }
+
+    /// Create a `VertexStream` over all elements of `data`, borrowing it for the
+    /// lifetime of the stream.
+    ///
+    /// For plain old data types, `from_bytes` together with e.g. `bytemuck::cast_slice`
+    /// is a safe alternative.
+    ///
+    /// # Safety
+    ///
+    /// `T` must not contain padding or any other uninitialized bytes, since meshoptimizer
+    /// reads the elements as raw bytes.
+    pub unsafe fn from_slice<T>(data: &'a [T]) -> VertexStream<'a> {
+        unsafe { Self::from_slice_with_offset::<T, T>(data, 0) }
+    }
+
+    /// Create a `VertexStream` for an attribute of type `T` that starts `offset` bytes into
+    /// each element of the interleaved `vertices`, borrowing them for the lifetime of the stream.
+    ///
+    /// # Safety
+    ///
+    /// The `size_of::<T>()` bytes at `offset` in each element must not contain padding or any
+    /// other uninitialized bytes, since meshoptimizer reads the attributes as raw bytes.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the attribute does not fit into `VertexType` at `offset`.
+    pub unsafe fn from_slice_with_offset<T, VertexType>(
+        vertices: &'a [VertexType],
+        offset: usize,
+    ) -> VertexStream<'a> {
+        let stride = core::mem::size_of::<VertexType>();
+        assert!(
+            offset + core::mem::size_of::<T>() <= stride,
+            "attribute must fit into the vertex type"
+        );
+        VertexStream {
+            data: vertices.as_ptr().cast::<u8>().wrapping_add(offset),
+            stride,
+            size: core::mem::size_of::<T>(),
+            vertex_count: Some(vertices.len()),
 
             _marker: PhantomData,
         }
     }
+
+    /// Create a `VertexStream` over raw vertex bytes, with attributes of `size` bytes spaced
+    /// `stride` bytes apart, borrowing `data` for the lifetime of the stream.
+    ///
+    /// The vertex count is the number of attributes that fit into `data`; the last vertex
+    /// only needs `size` bytes, not a full `stride`.
+    ///
+    /// Returns an error if `size` is not in `1..=256` or is larger than `stride`, or if `size`
+    /// is at least 4 and `data` or `stride` is not 4 byte aligned.
+    pub fn from_bytes(data: &'a [u8], size: usize, stride: usize) -> Result<VertexStream<'a>> {
+        if size == 0 || size > 256 {
+            return Err(Error::memory_dynamic(format!(
+                "stream size ({}) must be in 1..=256",
+                size
+            )));
+        }
+        if size > stride {
+            return Err(Error::memory_dynamic(format!(
+                "stream size ({}) must not be larger than stride ({})",
+                size, stride
+            )));
+        }
+        check_stream_alignment(data.as_ptr(), size, stride)?;
+        let vertex_count = if data.len() < size {
+            0
+        } else {
+            (data.len() - size) / stride + 1
+        };
+        Ok(VertexStream {
+            data: data.as_ptr(),
+            stride,
+            size,
+            vertex_count: Some(vertex_count),
+
+            _marker: PhantomData,
+        })
+    }
+
+    /// Pointer to buffer which contains vertex data.
+    pub fn data(&self) -> *const u8 {
+        self.data
+    }
+
+    /// Space between vertices inside the buffer (in bytes).
+    pub fn stride(&self) -> usize {
+        self.stride
+    }
+
+    /// The size in bytes of the vertex attribute this Stream is representing.
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    /// Number of vertices in the stream, if it was created from a slice.
+    pub fn vertex_count(&self) -> Option<usize> {
+        self.vertex_count
+    }
+}
+
+/// Mutable vertex attribute stream, for functions that reorder several streams in place
+/// such as `optimize_vertex_fetch_multi`.
+///
+/// The stream covers whole elements of `stride` bytes, which are moved as a unit.
+#[derive(Debug)]
+pub struct VertexStreamMut<'a> {
+    data: &'a mut [u8],
+    stride: usize,
+}
+
+impl<'a> VertexStreamMut<'a> {
+    /// Create a `VertexStreamMut` over all elements of `data`, borrowing it mutably for the
+    /// lifetime of the stream.
+    ///
+    /// For plain old data types, `from_bytes` together with e.g. `bytemuck::cast_slice_mut`
+    /// is a safe alternative.
+    ///
+    /// # Safety
+    ///
+    /// `T` must not contain padding or any other uninitialized bytes, since the stream reads
+    /// the elements as raw bytes. Elements are only ever moved as a whole, so they stay
+    /// valid values of `T`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `T` is zero sized.
+    pub unsafe fn from_slice<T: Copy>(data: &'a mut [T]) -> VertexStreamMut<'a> {
+        let stride = core::mem::size_of::<T>();
+        assert!(stride > 0, "element type must not be zero sized");
+        let len = core::mem::size_of_val(data);
+        let data = unsafe { core::slice::from_raw_parts_mut(data.as_mut_ptr().cast::<u8>(), len) };
+        VertexStreamMut { data, stride }
+    }
+
+    /// Create a `VertexStreamMut` over raw vertex bytes with `stride` bytes per vertex.
+    ///
+    /// Returns an error if `stride` is zero or the length of `data` is not a multiple of it.
+    pub fn from_bytes(data: &'a mut [u8], stride: usize) -> Result<VertexStreamMut<'a>> {
+        if stride == 0 || !data.len().is_multiple_of(stride) {
+            return Err(Error::memory_dynamic(format!(
+                "data length ({}) must be a multiple of a non-zero stride ({})",
+                data.len(),
+                stride
+            )));
+        }
+        Ok(VertexStreamMut { data, stride })
+    }
+
+    /// Space between vertices inside the buffer (in bytes).
+    pub fn stride(&self) -> usize {
+        self.stride
+    }
+
+    /// Number of vertices in the stream.
+    pub fn vertex_count(&self) -> usize {
+        self.data.len() / self.stride
+    }
+
+    /// Moves each vertex to the location given by `remap`, skipping vertices that map to
+    /// `u32::MAX`; `scratch` keeps a copy of the original data.
+    pub(crate) fn remap(&mut self, remap: &[u32], scratch: &mut Vec<u8>) {
+        scratch.clear();
+        scratch.extend_from_slice(self.data);
+        for (vertex, &target) in scratch.chunks_exact(self.stride).zip(remap) {
+            if target != u32::MAX {
+                let start = target as usize * self.stride;
+                self.data[start..start + self.stride].copy_from_slice(vertex);
+            }
+        }
+    }
+}
+
+// meshoptimizer hashes stream data 4 bytes at a time with `unsigned int` reads
+fn check_stream_alignment(data: *const u8, size: usize, stride: usize) -> Result<()> {
+    if size >= 4 && !(data.addr().is_multiple_of(4) && stride.is_multiple_of(4)) {
+        return Err(Error::memory_dynamic(format!(
+            "stream data and stride ({}) must be 4 byte aligned for a stream size ({}) of 4 or more",
+            stride, size
+        )));
+    }
+    Ok(())
+}
+
+/// Checks that `stream` can be read for `vertex_count` vertices: the size must be in
+/// `1..=256` and not larger than the stride, and a stream created from a slice must have
+/// exactly `vertex_count` vertices.
+pub(crate) fn check_stream(stream: &VertexStream<'_>, vertex_count: usize) -> Result<()> {
+    if !(1..=256).contains(&stream.size) || stream.size > stream.stride {
+        return Err(Error::memory_dynamic(format!(
+            "stream size ({}) must be in 1..=256 and not larger than the stride ({})",
+            stream.size, stream.stride
+        )));
+    }
+    match stream.vertex_count {
+        Some(count) if count != vertex_count => Err(Error::memory_dynamic(format!(
+            "stream vertex count ({}) must match the vertex count ({})",
+            count, vertex_count
+        ))),
+        _ => Ok(()),
+    }
+}
+
+/// Returns an error if an index is not less than `vertex_count`.
+pub(crate) fn check_indices_in_range(indices: &[u32], vertex_count: usize) -> Result<()> {
+    match indices
+        .iter()
+        .find(|&&index| index as usize >= vertex_count)
+    {
+        Some(index) => Err(Error::memory_dynamic(format!(
+            "index ({}) must be less than the vertex count ({})",
+            index, vertex_count
+        ))),
+        None => Ok(()),
+    }
+}
+
+/// Converts `streams` for the multi-stream C functions, checking the requirements they
+/// assert on.
+///
+/// Returns an error if there are no streams or more than 16, or if `check_stream` rejects
+/// a stream.
+pub(crate) fn ffi_streams(
+    vertex_count: usize,
+    streams: &[VertexStream<'_>],
+) -> Result<Vec<ffi::meshopt_Stream>> {
+    if !(1..=16).contains(&streams.len()) {
+        return Err(Error::memory_dynamic(format!(
+            "stream count ({}) must be in 1..=16",
+            streams.len()
+        )));
+    }
+    streams
+        .iter()
+        .map(|stream| {
+            check_stream(stream, vertex_count)?;
+            check_stream_alignment(stream.data, stream.size, stream.stride)?;
+            Ok(ffi::meshopt_Stream {
+                data: stream.data.cast(),
+                size: stream.size,
+                stride: stream.stride,
+            })
+        })
+        .collect()
 }
@@ -1,5 +1,11 @@
-use crate::{ffi, DecodePosition, VertexDataAdapter};
-use std::mem;
+use crate::{
+    analyze_overdraw_decoder, analyze_vertex_cache, ffi, generate_vertex_remap, remap_index_buffer,
+    remap_index_buffer_in_place, remap_index_buffer_into, remap_vertex_buffer,
+    remap_vertex_buffer_into, DecodePosition, Error, OverdrawStatistics, Result,
+    VertexCacheStatistics, VertexDataAdapter, VertexStream, VertexStreamMut,
+};
+use alloc::{format, vec, vec::Vec};
+use core::mem;
 
 /// Reorders indices to reduce the number of GPU vertex shader invocations.
 ///
@@ -33,6 +39,44 @@ pub fn optimize_vertex_cache_in_place(indices: &mut [u32], vertex_count: usize)
     }
 }
 
+/// Vertex transform cache optimizer for strip-like caches.
+///
+/// Produces inferior results to `optimize_vertex_cache` from the GPU vertex cache
+/// perspective, but the resulting index order is more optimal if the goal is to
+/// reduce the triangle strip length or improve compression efficiency.
+/// Use this instead of `optimize_vertex_cache` when the result is going to be
+/// converted with `stripify`.
+pub fn optimize_vertex_cache_strip(indices: &[u32], vertex_count: usize) -> Vec<u32> {
+    let mut optimized: Vec<u32> = vec![0; indices.len()];
+    unsafe {
+        ffi::meshopt_optimizeVertexCacheStrip(
+            optimized.as_mut_ptr(),
+            indices.as_ptr(),
+            indices.len(),
+            vertex_count,
+        );
+    }
+    optimized
+}
+
+/// Vertex transform cache optimizer for strip-like caches (in place).
+///
+/// Produces inferior results to `optimize_vertex_cache_in_place` from the GPU vertex
+/// cache perspective, but the resulting index order is more optimal if the goal is to
+/// reduce the triangle strip length or improve compression efficiency.
+/// Use this instead of `optimize_vertex_cache_in_place` when the result is going
+/// to be converted with `stripify`.
+pub fn optimize_vertex_cache_strip_in_place(indices: &mut [u32], vertex_count: usize) {
+    unsafe {
+        ffi::meshopt_optimizeVertexCacheStrip(
+            indices.as_mut_ptr(),
+            indices.as_ptr(),
+            indices.len(),
+            vertex_count,
+        );
+    }
+}
+
 /// Vertex transform cache optimizer for FIFO caches.
 ///
 /// Reorders indices to reduce the number of GPU vertex shader invocations.
@@ -42,11 +86,16 @@ pub fn optimize_vertex_cache_in_place(indices: &mut [u32], vertex_count: usize)
 ///
 /// If index buffer contains multiple ranges for multiple draw calls,
 /// this function needs to be called on each range individually.
+///
+/// # Panics
+///
+/// Panics if `cache_size` is less than 3.
 pub fn optimize_vertex_cache_fifo(
     indices: &[u32],
     vertex_count: usize,
     cache_size: u32,
 ) -> Vec<u32> {
+    assert!(cache_size >= 3, "cache_size must be at least 3");
     let mut optimized: Vec<u32> = vec![0; indices.len()];
     unsafe {
         ffi::meshopt_optimizeVertexCacheFifo(
@@ -65,15 +114,20 @@ pub fn optimize_vertex_cache_fifo(
 /// Reorders indices to reduce the number of GPU vertex shader invocations.
 ///
 /// Generally takes ~3x less time to optimize meshes but produces inferior
-/// results compared to `optimize_vertex_cache_fifo_in_place`.
+/// results compared to `optimize_vertex_cache_in_place`.
 ///
 /// If index buffer contains multiple ranges for multiple draw calls,
 /// this function needs to be called on each range individually.
+///
+/// # Panics
+///
+/// Panics if `cache_size` is less than 3.
 pub fn optimize_vertex_cache_fifo_in_place(
     indices: &mut [u32],
     vertex_count: usize,
     cache_size: u32,
 ) {
+    assert!(cache_size >= 3, "cache_size must be at least 3");
     unsafe {
         ffi::meshopt_optimizeVertexCacheFifo(
             indices.as_mut_ptr(),
@@ -108,6 +162,49 @@ pub fn optimize_vertex_fetch<T: Clone + Default>(indices: &mut [u32], vertices:
     result
 }
 
+/// Like `optimize_vertex_fetch`, but for untyped vertex data whose layout is only known at
+/// runtime: `vertices` holds `vertex_count` vertices of `vertex_size` bytes each.
+///
+/// Returns the reordered vertex data, truncated to the vertices referenced by `indices`.
+/// Returns an error if `vertices.len()` is not `vertex_count * vertex_size`, or if
+/// `vertex_size` is not in `1..=256`.
+///
+/// `indices` is used both as an input and as an output index buffer.
+pub fn optimize_vertex_fetch_raw(
+    indices: &mut [u32],
+    vertices: &[u8],
+    vertex_count: usize,
+    vertex_size: usize,
+) -> Result<Vec<u8>> {
+    if !(1..=256).contains(&vertex_size) {
+        return Err(Error::memory_dynamic(format!(
+            "vertex_size ({}) must be in 1..=256",
+            vertex_size
+        )));
+    }
+    if vertex_count.checked_mul(vertex_size) != Some(vertices.len()) {
+        return Err(Error::memory_dynamic(format!(
+            "vertex data length ({}) must be vertex_count ({}) * vertex_size ({})",
+            vertices.len(),
+            vertex_count,
+            vertex_size
+        )));
+    }
+    let mut result: Vec<u8> = vec![0; vertices.len()];
+    let next_vertex = unsafe {
+        ffi::meshopt_optimizeVertexFetch(
+            result.as_mut_ptr().cast(),
+            indices.as_mut_ptr(),
+            indices.len(),
+            vertices.as_ptr().cast(),
+            vertex_count,
+            vertex_size,
+        )
+    };
+    result.truncate(next_vertex * vertex_size);
+    Ok(result)
+}
+
 /// Vertex fetch cache optimizer (modifies in place)
 /// Reorders vertices and changes indices to reduce the amount of GPU
 /// memory fetches during vertex processing.
@@ -132,19 +229,285 @@ pub fn optimize_vertex_fetch_in_place<T>(indices: &mut [u32], vertices: &mut [T]
 /// Generates vertex remap to reduce the amount of GPU memory fetches during
 /// vertex processing.
 ///
-/// The resulting remap table should be used to reorder vertex/index buffers
-/// using `optimize_remap_vertex_buffer`/`optimize_remap_index_buffer`.
-pub fn optimize_vertex_fetch_remap(indices: &[u32], vertex_count: usize) -> Vec<u32> {
-    let mut result: Vec<u32> = vec![0; vertex_count];
-    let next_vertex = unsafe {
+/// Returns the remap table together with the number of unique vertices. The
+/// table has one entry per input vertex (unused vertices map to `u32::MAX`) and
+/// should be used to reorder each vertex stream and the index buffer
+/// using `remap_vertex_buffer`/`remap_index_buffer`.
+pub fn optimize_vertex_fetch_remap(indices: &[u32], vertex_count: usize) -> (Vec<u32>, usize) {
+    let mut remap: Vec<u32> = vec![0; vertex_count];
+    let unique_count = unsafe {
         ffi::meshopt_optimizeVertexFetchRemap(
-            result.as_mut_ptr(),
+            remap.as_mut_ptr(),
             indices.as_ptr(),
             indices.len(),
             vertex_count,
         )
     };
-    result.resize(next_vertex, 0u32);
+    (remap, unique_count)
+}
+
+/// Like `optimize_vertex_fetch_in_place`, but reorders several vertex streams with the
+/// same remap table, e.g. for non-interleaved attributes.
+///
+/// Rewrites `indices`, moves the vertices of every stream, and returns the number of
+/// unique vertices; the vertices after that in each stream are unspecified.
+///
+/// # Panics
+///
+/// Panics if a stream doesn't have `vertex_count` vertices, or if any index is out of
+/// range of `vertex_count`.
+pub fn optimize_vertex_fetch_multi(
+    indices: &mut [u32],
+    streams: &mut [VertexStreamMut<'_>],
+    vertex_count: usize,
+) -> usize {
+    for stream in streams.iter() {
+        assert_eq!(
+            stream.vertex_count(),
+            vertex_count,
+            "every stream must have vertex_count vertices"
+        );
+    }
+    assert!(
+        indices.iter().all(|&index| (index as usize) < vertex_count),
+        "indices must be less than the vertex count"
+    );
+
+    let (remap, unique_count) = optimize_vertex_fetch_remap(indices, vertex_count);
+    let mut scratch = Vec::new();
+    for stream in streams.iter_mut() {
+        stream.remap(&remap, &mut scratch);
+    }
+    remap_index_buffer_in_place(indices, &remap).expect("invalid remap input");
+    unique_count
+}
+
+/// Generates a remap table that reorders vertices along a space-filling curve, for better
+/// compression of point clouds and unindexed attribute streams with `encode_vertex_buffer`.
+///
+/// The remap table has one entry per vertex and can be used with
+/// `remap_vertex_buffer`/`remap_index_buffer`.
+///
+/// # Panics
+///
+/// Panics if the vertex stride is not a multiple of 4 in `12..=256`.
+pub fn spatial_sort_remap(positions: &VertexDataAdapter<'_>) -> Vec<u32> {
+    check_position_stride(positions.vertex_stride);
+    let mut remap: Vec<u32> = vec![0; positions.vertex_count];
+    if positions.vertex_count == 0 {
+        return remap;
+    }
+    unsafe {
+        ffi::meshopt_spatialSortRemap(
+            remap.as_mut_ptr(),
+            positions.pos_ptr(),
+            positions.vertex_count,
+            positions.vertex_stride,
+        );
+    }
+    remap
+}
+
+/// Reorders `vertices` along a space-filling curve through `positions` and rewrites `indices`
+/// to match, combining `spatial_sort_remap`, `remap_vertex_buffer` and `remap_index_buffer`.
+///
+/// The remap table maps old vertex indices to new ones: vertex `i` of `vertices` ends up at
+/// `remap[i]` of the returned vertices. Every triangle still references the same vertices,
+/// only their order in the vertex buffer changes.
+///
+/// # Panics
+///
+/// Panics if `vertices` doesn't have one entry per position, if any index is out of range,
+/// or if the vertex stride is not a multiple of 4 in `12..=256`.
+pub fn spatial_sort_mesh<T: Clone>(
+    vertices: &[T],
+    indices: &[u32],
+    positions: &VertexDataAdapter<'_>,
+) -> (Vec<T>, Vec<u32>) {
+    assert_eq!(
+        vertices.len(),
+        positions.vertex_count,
+        "vertices must have one entry per position"
+    );
+    let remap = spatial_sort_remap(positions);
+    let mut sorted = vertices.to_vec();
+    remap_vertex_buffer_into(&mut sorted, vertices, &remap).expect("invalid remap input");
+    (sorted, remap_index_buffer(Some(indices), 0, &remap))
+}
+
+/// Like `spatial_sort_mesh`, but reorders several vertex streams with the same remap table,
+/// e.g. for non-interleaved attributes; `positions` may alias one of the streams.
+///
+/// Returns each reordered stream tightly packed, with `size` bytes per vertex, together with
+/// the rewritten indices.
+///
+/// # Panics
+///
+/// Panics if a stream size is not in `1..=256` or larger than its stride, if a stream created
+/// from a slice doesn't have one vertex per position, if any index is out of range, or if the
+/// vertex stride is not a multiple of 4 in `12..=256`.
+pub fn spatial_sort_mesh_multi(
+    indices: &[u32],
+    positions: &VertexDataAdapter<'_>,
+    streams: &[VertexStream<'_>],
+) -> (Vec<Vec<u8>>, Vec<u32>) {
+    let vertex_count = positions.vertex_count;
+    for stream in streams {
+        crate::check_stream(stream, vertex_count).expect("invalid vertex stream");
+    }
+    let remap = spatial_sort_remap(positions);
+    let sorted = streams
+        .iter()
+        .map(|stream| {
+            let mut data: Vec<u8> = vec![0; vertex_count * stream.size()];
+            for (i, &target) in remap.iter().enumerate() {
+                let element = unsafe {
+                    core::slice::from_raw_parts(
+                        stream.data().add(i * stream.stride()),
+                        stream.size(),
+                    )
+                };
+                let target = target as usize * stream.size();
+                data[target..target + stream.size()].copy_from_slice(element);
+            }
+            data
+        })
+        .collect();
+    (sorted, remap_index_buffer(Some(indices), 0, &remap))
+}
+
+/// Reorders points into spatially coherent clusters, e.g. for culling point clouds in
+/// batches, and returns the point indices in cluster order.
+///
+/// Every consecutive chunk of `cluster_size` indices forms one cluster; only the last chunk
+/// can be smaller than `cluster_size`.
+///
+/// # Panics
+///
+/// Panics if `cluster_size` is zero, or if the vertex stride is not a multiple of 4 in
+/// `12..=256`.
+pub fn spatial_cluster_points(positions: &VertexDataAdapter<'_>, cluster_size: usize) -> Vec<u32> {
+    assert!(cluster_size > 0, "cluster size must be non-zero");
+    check_position_stride(positions.vertex_stride);
+    let mut result: Vec<u32> = vec![0; positions.vertex_count];
+    if positions.vertex_count == 0 {
+        return result;
+    }
+    unsafe {
+        ffi::meshopt_spatialClusterPoints(
+            result.as_mut_ptr(),
+            positions.pos_ptr(),
+            positions.vertex_count,
+            positions.vertex_stride,
+            cluster_size,
+        );
+    }
+    result
+}
+
+/// Reorders triangles along a space-filling curve through their centroids, e.g. for ray
+/// tracing acceleration structure builds or for more coherent overdraw of transparent
+/// geometry. Every triangle is kept exactly once, with its winding intact.
+///
+/// This replaces the order produced by `optimize_vertex_cache` (and the other way around),
+/// so pick the one that matters for the use case.
+///
+/// # Panics
+///
+/// Panics if the index count is not a multiple of 3, if any index is out of range of the
+/// vertex count, or if the vertex stride is not a multiple of 4 in `12..=256`.
+pub fn spatial_sort_triangles(indices: &[u32], vertices: &VertexDataAdapter<'_>) -> Vec<u32> {
+    let mut result = indices.to_vec();
+    spatial_sort_triangles_in_place(&mut result, vertices);
+    result
+}
+
+/// Like `spatial_sort_triangles`, but reorders `indices` in place.
+///
+/// # Panics
+///
+/// Panics if the index count is not a multiple of 3, if any index is out of range of the
+/// vertex count, or if the vertex stride is not a multiple of 4 in `12..=256`.
+pub fn spatial_sort_triangles_in_place(indices: &mut [u32], vertices: &VertexDataAdapter<'_>) {
+    assert!(
+        indices.len().is_multiple_of(3),
+        "index count must be a multiple of 3"
+    );
+    assert!(
+        indices
+            .iter()
+            .all(|&index| (index as usize) < vertices.vertex_count),
+        "indices must be less than the vertex count"
+    );
+    check_position_stride(vertices.vertex_stride);
+    unsafe {
+        ffi::meshopt_spatialSortTriangles(
+            indices.as_mut_ptr(),
+            indices.as_ptr(),
+            indices.len(),
+            vertices.pos_ptr(),
+            vertices.vertex_count,
+            vertices.vertex_stride,
+        );
+    }
+}
+
+fn check_overdraw_threshold(threshold: f32) {
+    assert!(threshold >= 1.0, "threshold must be at least 1.0");
+}
+
+pub(crate) fn check_position_stride(position_stride: usize) {
+    assert!(
+        (12..=256).contains(&position_stride) && position_stride.is_multiple_of(4),
+        "position_stride must be a multiple of 4 in 12..=256"
+    );
+}
+
+// number of vertices in a float array with a float3 position every `position_stride` bytes;
+// like `VertexStream::from_bytes`, the last vertex only needs its 12 position bytes
+pub(crate) fn position_count(positions: &[f32], position_stride: usize) -> usize {
+    match mem::size_of_val(positions).checked_sub(12) {
+        Some(rest) => rest / position_stride + 1,
+        None => 0,
+    }
+}
+
+/// Reorders indices to reduce the number of GPU vertex shader invocations
+/// and the pixel overdraw.
+///
+/// `indices` must contain index data that is the result of `optimize_vertex_cache`
+/// (*not* the original mesh indices!); use `optimize_vertex_cache_and_overdraw`
+/// to run both passes in one call.
+///
+/// `threshold` indicates how much the overdraw optimizer can degrade vertex cache
+/// efficiency (1.05 = up to 5%) to reduce overdraw more efficiently; 1.0 keeps
+/// the vertex cache efficiency of the input.
+///
+/// Only the vertex positions in `vertices` are read.
+///
+/// # Panics
+///
+/// Panics if any index is not less than the vertex count, or if `threshold` is less
+/// than 1.0 or NaN.
+pub fn optimize_overdraw(
+    indices: &[u32],
+    vertices: &VertexDataAdapter<'_>,
+    threshold: f32,
+) -> Vec<u32> {
+    check_overdraw_threshold(threshold);
+    crate::check_indices_in_range(indices, vertices.vertex_count).expect("invalid index");
+    let mut result: Vec<u32> = vec![0; indices.len()];
+    unsafe {
+        ffi::meshopt_optimizeOverdraw(
+            result.as_mut_ptr(),
+            indices.as_ptr(),
+            indices.len(),
+            vertices.pos_ptr(),
+            vertices.vertex_count,
+            vertices.vertex_stride,
+            threshold,
+        );
+    }
     result
 }
 
@@ -156,20 +519,24 @@ pub fn optimize_vertex_fetch_remap(indices: &[u32], vertex_count: usize) -> Vec<
 ///
 /// `threshold` indicates how much the overdraw optimizer can degrade vertex cache
 /// efficiency (1.05 = up to 5%) to reduce overdraw more efficiently.
+///
+/// # Panics
+///
+/// Panics if any index is not less than the vertex count, or if `threshold` is less
+/// than 1.0 or NaN.
 pub fn optimize_overdraw_in_place(
     indices: &mut [u32],
     vertices: &VertexDataAdapter<'_>,
     threshold: f32,
 ) {
-    let vertex_data = vertices.reader.get_ref();
-    let vertex_data = vertex_data.as_ptr().cast::<u8>();
-    let positions = unsafe { vertex_data.add(vertices.position_offset) };
+    check_overdraw_threshold(threshold);
+    crate::check_indices_in_range(indices, vertices.vertex_count).expect("invalid index");
     unsafe {
         ffi::meshopt_optimizeOverdraw(
             indices.as_mut_ptr(),
             indices.as_ptr(),
             indices.len(),
-            positions.cast(),
+            vertices.pos_ptr(),
             vertices.vertex_count,
             vertices.vertex_stride,
             threshold,
@@ -185,11 +552,18 @@ pub fn optimize_overdraw_in_place(
 ///
 /// `threshold` indicates how much the overdraw optimizer can degrade vertex cache
 /// efficiency (1.05 = up to 5%) to reduce overdraw more efficiently.
+///
+/// # Panics
+///
+/// Panics if any index is not less than `vertices.len()`, or if `threshold` is less than
+/// 1.0 or NaN.
 pub fn optimize_overdraw_in_place_decoder<T: DecodePosition>(
     indices: &mut [u32],
     vertices: &[T],
     threshold: f32,
 ) {
+    check_overdraw_threshold(threshold);
+    crate::check_indices_in_range(indices, vertices.len()).expect("invalid index");
     let positions = vertices
         .iter()
         .map(|vertex| vertex.decode_position())
@@ -206,3 +580,1092 @@ pub fn optimize_overdraw_in_place_decoder<T: DecodePosition>(
         );
     }
 }
+
+/// Like `optimize_overdraw`, but reads the vertex positions from a plain float array.
+///
+/// `positions` should have a float3 position in the first 12 bytes of each vertex, with
+/// `position_stride` bytes between vertices; the last vertex doesn't need to be padded up to
+/// the stride. Like `optimize_overdraw`, this assumes
+/// `indices` were optimized with `optimize_vertex_cache`; use
+/// `optimize_vertex_cache_and_overdraw_positions` otherwise.
+///
+/// # Panics
+///
+/// Panics if `position_stride` is not a multiple of 4 in `12..=256`, if any index is not
+/// less than the number of positions, or if `threshold` is less than 1.0 or NaN.
+pub fn optimize_overdraw_positions(
+    indices: &[u32],
+    positions: &[f32],
+    position_stride: usize,
+    threshold: f32,
+) -> Vec<u32> {
+    check_position_stride(position_stride);
+    check_overdraw_threshold(threshold);
+    let vertex_count = position_count(positions, position_stride);
+    crate::check_indices_in_range(indices, vertex_count).expect("invalid index");
+    let mut result: Vec<u32> = vec![0; indices.len()];
+    unsafe {
+        ffi::meshopt_optimizeOverdraw(
+            result.as_mut_ptr(),
+            indices.as_ptr(),
+            indices.len(),
+            positions.as_ptr(),
+            vertex_count,
+            position_stride,
+            threshold,
+        );
+    }
+    result
+}
+
+/// Clustering statistics of the overdraw optimizer, returned by `optimize_overdraw_with_stats`.
+#[derive(Debug, Copy, Clone, PartialEq, Default)]
+pub struct OverdrawOptimizeStatistics {
+    /// Number of hard clusters, started by triangles with three vertex cache misses.
+    pub hard_cluster_count: usize,
+    /// Number of clusters the optimizer reorders; hard clusters are split further wherever
+    /// their running ACMR drops to `threshold` times the ACMR of the whole hard cluster.
+    pub cluster_count: usize,
+    /// Average number of triangles per cluster.
+    pub average_cluster_size: f32,
+}
+
+// Mirrors updateCache in the C overdraw optimizer: a FIFO cache simulated with timestamps.
+fn update_overdraw_cache(
+    triangle: &[u32],
+    cache_size: u32,
+    cache_timestamps: &mut [u32],
+    timestamp: &mut u32,
+) -> u32 {
+    let mut cache_misses = 0;
+    for &vertex in triangle {
+        let entry = &mut cache_timestamps[vertex as usize];
+        if timestamp.wrapping_sub(*entry) > cache_size {
+            *entry = *timestamp;
+            *timestamp = timestamp.wrapping_add(1);
+            cache_misses += 1;
+        }
+    }
+    cache_misses
+}
+
+// Reimplements the hard and soft boundary steps of meshopt_optimizeOverdraw, returning the
+// hard cluster count and the first triangle of every soft cluster.
+fn overdraw_clusters(indices: &[u32], vertex_count: usize, threshold: f32) -> (usize, Vec<u32>) {
+    if indices.is_empty() || vertex_count == 0 {
+        return (0, Vec::new());
+    }
+    let cache_size = 16;
+    let face_count = indices.len() / 3;
+    let mut cache_timestamps: Vec<u32> = vec![0; vertex_count];
+
+    let mut hard_clusters = Vec::new();
+    let mut timestamp = cache_size + 1;
+    for (i, triangle) in indices.chunks_exact(3).enumerate() {
+        let m = update_overdraw_cache(triangle, cache_size, &mut cache_timestamps, &mut timestamp);
+        if i == 0 || m == 3 {
+            hard_clusters.push(i as u32);
+        }
+    }
+
+    cache_timestamps.fill(0);
+    let mut timestamp = 0u32;
+    let mut soft_clusters: Vec<u32> = Vec::with_capacity(hard_clusters.len());
+    for (it, &start) in hard_clusters.iter().enumerate() {
+        let end = hard_clusters
+            .get(it + 1)
+            .map_or(face_count, |&end| end as usize);
+        let triangles = &indices[start as usize * 3..end * 3];
+
+        timestamp = timestamp.wrapping_add(cache_size + 1);
+        let cluster_misses: u32 = triangles
+            .chunks_exact(3)
+            .map(|triangle| {
+                update_overdraw_cache(triangle, cache_size, &mut cache_timestamps, &mut timestamp)
+            })
+            .sum();
+        let cluster_threshold = threshold * (cluster_misses as f32 / (end - start as usize) as f32);
+
+        soft_clusters.push(start);
+        timestamp = timestamp.wrapping_add(cache_size + 1);
+        let mut running_misses = 0u32;
+        let mut running_faces = 0u32;
+        for (i, triangle) in triangles.chunks_exact(3).enumerate() {
+            running_misses +=
+                update_overdraw_cache(triangle, cache_size, &mut cache_timestamps, &mut timestamp);
+            running_faces += 1;
+
+            if running_misses as f32 / running_faces as f32 <= cluster_threshold {
+                soft_clusters.push(start + i as u32 + 1);
+                timestamp = timestamp.wrapping_add(cache_size + 1);
+                running_misses = 0;
+                running_faces = 0;
+            }
+        }
+
+        // the last cluster of every hard cluster is merged with the one before it
+        if soft_clusters.last() != Some(&start) {
+            soft_clusters.pop();
+        }
+    }
+    (hard_clusters.len(), soft_clusters)
+}
+
+/// Like `optimize_overdraw`, but also reports how the optimizer clustered the triangles,
+/// e.g. for tuning `threshold`.
+///
+/// The clustering is deterministic given the vertex cache optimized input order, so it is
+/// recomputed in Rust alongside the native optimizer. A higher `threshold` splits clusters
+/// more eagerly, which produces more and smaller clusters that can be sorted more freely.
+///
+/// # Panics
+///
+/// Panics if the index count is not a multiple of 3, if any index is out of range, or if
+/// `threshold` is less than 1.0 or NaN.
+pub fn optimize_overdraw_with_stats(
+    indices: &[u32],
+    vertices: &VertexDataAdapter<'_>,
+    threshold: f32,
+) -> (Vec<u32>, OverdrawOptimizeStatistics) {
+    assert!(
+        indices.len().is_multiple_of(3),
+        "index count ({}) must be a multiple of 3",
+        indices.len()
+    );
+    assert!(
+        indices
+            .iter()
+            .all(|&index| (index as usize) < vertices.vertex_count),
+        "indices must be less than the vertex count"
+    );
+    let result = optimize_overdraw(indices, vertices, threshold);
+    let (hard_cluster_count, clusters) =
+        overdraw_clusters(indices, vertices.vertex_count, threshold);
+    let stats = OverdrawOptimizeStatistics {
+        hard_cluster_count,
+        cluster_count: clusters.len(),
+        average_cluster_size: if clusters.is_empty() {
+            0.0
+        } else {
+            (indices.len() / 3) as f32 / clusters.len() as f32
+        },
+    };
+    (result, stats)
+}
+
+/// Runs `optimize_vertex_cache` followed by `optimize_overdraw`, which is the order the
+/// overdraw optimizer expects its input in.
+///
+/// # Panics
+///
+/// Panics if `threshold` is less than 1.0 or NaN.
+pub fn optimize_vertex_cache_and_overdraw(
+    indices: &[u32],
+    vertices: &VertexDataAdapter<'_>,
+    threshold: f32,
+) -> Vec<u32> {
+    check_overdraw_threshold(threshold);
+    let mut result = optimize_vertex_cache(indices, vertices.vertex_count);
+    optimize_overdraw_in_place(&mut result, vertices, threshold);
+    result
+}
+
+/// Like `optimize_vertex_cache_and_overdraw`, but reads the vertex positions from a plain
+/// float array as in `optimize_overdraw_positions`.
+///
+/// # Panics
+///
+/// Panics if `position_stride` is not a multiple of 4 in `12..=256`, or if `threshold`
+/// is less than 1.0 or NaN.
+pub fn optimize_vertex_cache_and_overdraw_positions(
+    indices: &[u32],
+    positions: &[f32],
+    position_stride: usize,
+    threshold: f32,
+) -> Vec<u32> {
+    check_position_stride(position_stride);
+    check_overdraw_threshold(threshold);
+    let vertex_count = position_count(positions, position_stride);
+    let indices = optimize_vertex_cache(indices, vertex_count);
+    optimize_overdraw_positions(&indices, positions, position_stride, threshold)
+}
+
+/// Settings for `optimize_mesh`.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct OptimizeSettings {
+    /// How much the overdraw optimizer can degrade vertex cache efficiency to reduce
+    /// overdraw (1.05 = up to 5%); must be at least 1.0.
+    pub overdraw_threshold: f32,
+    /// Whether to reorder the vertex buffer for vertex fetch efficiency as the last step.
+    pub optimize_vertex_fetch: bool,
+}
+
+impl Default for OptimizeSettings {
+    fn default() -> Self {
+        Self {
+            overdraw_threshold: 1.05,
+            optimize_vertex_fetch: true,
+        }
+    }
+}
+
+/// Result of `optimize_mesh`.
+///
+/// The statistics are computed on the input mesh and on the optimized mesh, with
+/// `analyze_vertex_cache` using a 16 entry FIFO cache and `analyze_overdraw_decoder`.
+#[derive(Debug, Clone)]
+pub struct OptimizedMesh<T> {
+    /// Deduplicated and reordered vertex buffer.
+    pub vertices: Vec<T>,
+    /// Index buffer referencing `vertices`.
+    pub indices: Vec<u32>,
+    pub vertex_cache_before: VertexCacheStatistics,
+    pub vertex_cache_after: VertexCacheStatistics,
+    pub overdraw_before: OverdrawStatistics,
+    pub overdraw_after: OverdrawStatistics,
+}
+
+/// Runs the full optimization pipeline on an indexed triangle mesh: removes duplicate
+/// vertices with `generate_vertex_remap`, then runs `optimize_vertex_cache`,
+/// `optimize_overdraw` and, if enabled in `settings`, `optimize_vertex_fetch`, in the
+/// order these optimizations expect.
+///
+/// `vertices` can be an interleaved vertex struct or a positions-only array; vertices are
+/// considered duplicates only if all their bytes are equal, so `T` should not contain
+/// uninitialized padding.
+///
+/// # Panics
+///
+/// Panics if `settings.overdraw_threshold` is less than 1.0 or NaN.
+pub fn optimize_mesh<T: DecodePosition + Clone + Default>(
+    vertices: &[T],
+    indices: &[u32],
+    settings: OptimizeSettings,
+) -> OptimizedMesh<T> {
+    check_overdraw_threshold(settings.overdraw_threshold);
+    let vertex_cache_before = analyze_vertex_cache(indices, vertices.len(), 16, 0, 0);
+    let overdraw_before = analyze_overdraw_decoder(indices, vertices);
+
+    let (vertex_count, remap) = generate_vertex_remap(vertices, Some(indices));
+    let mut indices = remap_index_buffer(Some(indices), vertex_count, &remap);
+    let mut vertices = remap_vertex_buffer(vertices, vertex_count, &remap);
+
+    optimize_vertex_cache_in_place(&mut indices, vertices.len());
+    optimize_overdraw_in_place_decoder(&mut indices, &vertices, settings.overdraw_threshold);
+    if settings.optimize_vertex_fetch {
+        let vertex_count = optimize_vertex_fetch_in_place(&mut indices, &mut vertices);
+        vertices.truncate(vertex_count);
+    }
+
+    OptimizedMesh {
+        vertex_cache_after: analyze_vertex_cache(&indices, vertices.len(), 16, 0, 0),
+        overdraw_after: analyze_overdraw_decoder(&indices, &vertices),
+        vertices,
+        indices,
+        vertex_cache_before,
+        overdraw_before,
+    }
+}
+
+/// Reusable buffers for running the optimizers over many meshes, e.g. in an asset
+/// processing loop, without allocating new vectors for every call.
+///
+/// The `_into` methods write their results into caller provided vectors, reusing their
+/// capacity, and keep intermediate tables such as vertex remaps inside the `Optimizer`.
+/// The results match the corresponding allocating functions. Note that meshoptimizer
+/// still allocates its own temporary memory internally.
+#[derive(Debug, Default, Clone)]
+pub struct Optimizer {
+    remap: Vec<u32>,
+}
+
+impl Optimizer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Like `optimize_vertex_cache`, but writes the result into `out`.
+    pub fn optimize_vertex_cache_into(
+        &mut self,
+        indices: &[u32],
+        vertex_count: usize,
+        out: &mut Vec<u32>,
+    ) {
+        out.clear();
+        out.extend_from_slice(indices);
+        optimize_vertex_cache_in_place(out, vertex_count);
+    }
+
+    /// Like `optimize_overdraw`, but writes the result into `out`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if any index is not less than the vertex count, or if `threshold` is less
+    /// than 1.0 or NaN.
+    pub fn optimize_overdraw_into(
+        &mut self,
+        indices: &[u32],
+        vertices: &VertexDataAdapter<'_>,
+        threshold: f32,
+        out: &mut Vec<u32>,
+    ) {
+        out.clear();
+        out.extend_from_slice(indices);
+        optimize_overdraw_in_place(out, vertices, threshold);
+    }
+
+    /// Like `optimize_vertex_fetch`, but writes the reordered vertices into `out`.
+    ///
+    /// The vertices are cloned through the remap table, so `T` doesn't have to be `Copy`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if any index is out of range of `vertices`.
+    pub fn optimize_vertex_fetch_into<T: Clone + Default>(
+        &mut self,
+        indices: &mut [u32],
+        vertices: &[T],
+        out: &mut Vec<T>,
+    ) {
+        assert!(
+            indices
+                .iter()
+                .all(|&index| (index as usize) < vertices.len()),
+            "indices must be less than the vertex count"
+        );
+        self.remap.clear();
+        self.remap.resize(vertices.len(), 0);
+        let unique_count = unsafe {
+            ffi::meshopt_optimizeVertexFetchRemap(
+                self.remap.as_mut_ptr(),
+                indices.as_ptr(),
+                indices.len(),
+                vertices.len(),
+            )
+        };
+        out.clear();
+        out.resize(unique_count, T::default());
+        remap_vertex_buffer_into(out, vertices, &self.remap).expect("invalid remap output");
+        remap_index_buffer_in_place(indices, &self.remap).expect("invalid remap output");
+    }
+
+    /// Removes binary equivalent vertices like `generate_vertex_remap` followed by
+    /// `remap_index_buffer` and `remap_vertex_buffer`, writing the new index and vertex
+    /// buffers into `out_indices` and `out_vertices`. Returns the number of unique vertices.
+    pub fn deduplicate_into<T: Clone + Default>(
+        &mut self,
+        indices: &[u32],
+        vertices: &[T],
+        out_indices: &mut Vec<u32>,
+        out_vertices: &mut Vec<T>,
+    ) -> usize {
+        self.remap.clear();
+        self.remap.resize(vertices.len(), 0);
+        let unique_count = unsafe {
+            ffi::meshopt_generateVertexRemap(
+                self.remap.as_mut_ptr(),
+                indices.as_ptr(),
+                indices.len(),
+                vertices.as_ptr().cast(),
+                vertices.len(),
+                mem::size_of::<T>(),
+            )
+        };
+
+        out_indices.clear();
+        out_indices.resize(indices.len(), 0);
+        remap_index_buffer_into(out_indices, Some(indices), &self.remap)
+            .expect("remap table covers all indices");
+        out_vertices.clear();
+        out_vertices.resize(unique_count, T::default());
+        remap_vertex_buffer_into(out_vertices, vertices, &self.remap)
+            .expect("remap table matches the vertices");
+        unique_count
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        stripify,
+        test_utils::{grid_indices, grid_positions, sorted_triangles},
+        typed_to_bytes, StripRestart, Vertex,
+    };
+
+    fn scrambled_grid(size: u32) -> Vec<u32> {
+        let indices = grid_indices(size);
+        let triangle_count = indices.len() / 3;
+        (0..triangle_count)
+            .flat_map(|i| {
+                let t = (i * 7919) % triangle_count;
+                indices[t * 3..t * 3 + 3].to_vec()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_optimize_vertex_cache_fifo() {
+        let size = 32;
+        let vertex_count = ((size + 1) * (size + 1)) as usize;
+        let indices = scrambled_grid(size);
+        let cache_size = 8;
+
+        let before = analyze_vertex_cache(&indices, vertex_count, cache_size, 0, 0);
+        let optimized = optimize_vertex_cache_fifo(&indices, vertex_count, cache_size);
+        let after = analyze_vertex_cache(&optimized, vertex_count, cache_size, 0, 0);
+        assert!(after.acmr < before.acmr);
+
+        let mut in_place = indices.clone();
+        optimize_vertex_cache_fifo_in_place(&mut in_place, vertex_count, cache_size);
+        assert_eq!(in_place, optimized);
+    }
+
+    #[test]
+    fn test_optimize_vertex_cache_in_place_variants() {
+        let size = 32;
+        let vertex_count = ((size + 1) * (size + 1)) as usize;
+        let indices = scrambled_grid(size);
+        let before = analyze_vertex_cache(&indices, vertex_count, 16, 0, 0);
+
+        let mut in_place = indices.clone();
+        optimize_vertex_cache_in_place(&mut in_place, vertex_count);
+        assert_eq!(in_place, optimize_vertex_cache(&indices, vertex_count));
+
+        let mut fifo = indices.clone();
+        optimize_vertex_cache_fifo_in_place(&mut fifo, vertex_count, 16);
+        assert_eq!(fifo, optimize_vertex_cache_fifo(&indices, vertex_count, 16));
+
+        let mut strip = indices.clone();
+        optimize_vertex_cache_strip_in_place(&mut strip, vertex_count);
+        assert_eq!(strip, optimize_vertex_cache_strip(&indices, vertex_count));
+
+        for optimized in [&in_place, &fifo, &strip] {
+            // triangles are reordered, but each one is kept intact
+            assert_eq!(sorted_triangles(optimized), sorted_triangles(&indices));
+            let after = analyze_vertex_cache(optimized, vertex_count, 16, 0, 0);
+            assert!(after.acmr <= before.acmr);
+        }
+    }
+
+    #[test]
+    fn test_optimize_vertex_cache_strip_stripifies_shorter() {
+        let size = 32;
+        let vertex_count = ((size + 1) * (size + 1)) as usize;
+        let indices = scrambled_grid(size);
+
+        let strip_optimized = optimize_vertex_cache_strip(&indices, vertex_count);
+        let cache_optimized = optimize_vertex_cache(&indices, vertex_count);
+        for restart in [StripRestart::None, StripRestart::Index(u32::MAX)] {
+            let from_strip = stripify(&strip_optimized, vertex_count, restart).unwrap();
+            let from_cache = stripify(&cache_optimized, vertex_count, restart).unwrap();
+            assert!(from_strip.len() < from_cache.len());
+        }
+    }
+
+    #[test]
+    fn test_optimize_vertex_fetch_raw() {
+        let size = 8;
+        let indices = scrambled_grid(size);
+        let vertex_count = ((size + 1) * (size + 1)) as usize + 1;
+        // 9 floats per vertex, with one unused vertex at the end
+        let vertices: Vec<[f32; 9]> = (0..vertex_count)
+            .map(|i| core::array::from_fn(|c| (i * 9 + c) as f32))
+            .collect();
+
+        let mut typed_indices = indices.clone();
+        let typed = optimize_vertex_fetch(&mut typed_indices, &vertices);
+
+        let mut raw_indices = indices.clone();
+        let raw = optimize_vertex_fetch_raw(
+            &mut raw_indices,
+            typed_to_bytes(&vertices),
+            vertex_count,
+            36,
+        )
+        .unwrap();
+        assert_eq!(raw_indices, typed_indices);
+        assert_eq!(raw, typed_to_bytes(&typed));
+        assert_eq!(raw.len(), (vertex_count - 1) * 36);
+
+        let bytes = typed_to_bytes(&vertices);
+        let mut indices = indices;
+        assert!(optimize_vertex_fetch_raw(&mut indices, bytes, vertex_count, 32).is_err());
+        assert!(optimize_vertex_fetch_raw(&mut indices, bytes, vertex_count, 0).is_err());
+        assert!(optimize_vertex_fetch_raw(&mut indices, &[0; 300], 1, 300).is_err());
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_optimize_vertex_cache_fifo_small_cache() {
+        optimize_vertex_cache_fifo(&scrambled_grid(4), 25, 2);
+    }
+
+    #[test]
+    fn test_optimize_vertex_fetch_multi() {
+        let size = 8;
+        let indices = scrambled_grid(size);
+        let vertex_count = ((size + 1) * (size + 1)) as usize;
+        let positions: Vec<[f32; 3]> = (0..vertex_count)
+            .map(|i| [i as f32, 0.0, -(i as f32)])
+            .collect();
+        let mut colors: Vec<u8> = (0..vertex_count as u32)
+            .flat_map(|i| i.to_le_bytes())
+            .collect();
+
+        let mut new_indices = indices.clone();
+        let mut new_positions = positions.clone();
+        let unique_count = optimize_vertex_fetch_multi(
+            &mut new_indices,
+            &mut [
+                // [f32; 3] has no padding
+                unsafe { VertexStreamMut::from_slice(&mut new_positions) },
+                VertexStreamMut::from_bytes(&mut colors, 4).unwrap(),
+            ],
+            vertex_count,
+        );
+        assert_eq!(unique_count, vertex_count);
+
+        // both streams were reordered the same way as optimize_vertex_fetch would
+        let mut fetch_indices = indices.clone();
+        let fetch_positions = optimize_vertex_fetch(&mut fetch_indices, &positions);
+        assert_eq!(new_indices, fetch_indices);
+        assert_eq!(new_positions, fetch_positions);
+        for (position, color) in new_positions.iter().zip(colors.chunks_exact(4)) {
+            let original = u32::from_le_bytes(color.try_into().unwrap());
+            assert_eq!(position[0], original as f32);
+        }
+        for (&new, &old) in new_indices.iter().zip(&indices) {
+            assert_eq!(new_positions[new as usize], positions[old as usize]);
+        }
+    }
+
+    #[test]
+    fn test_spatial_sort_remap() {
+        use rand::{Rng, SeedableRng};
+
+        let mut rng = rand::rngs::StdRng::seed_from_u64(42);
+        let points: Vec<[u16; 4]> = (0..4096)
+            .map(|_| [rng.gen(), rng.gen(), rng.gen(), 0])
+            .collect();
+        let positions: Vec<[f32; 3]> = points
+            .iter()
+            .map(|p| [p[0] as f32, p[1] as f32, p[2] as f32])
+            .collect();
+        let adapter = VertexDataAdapter::new(typed_to_bytes(&positions), 12, 0).unwrap();
+
+        let remap = spatial_sort_remap(&adapter);
+        let mut sorted_remap = remap.clone();
+        sorted_remap.sort_unstable();
+        assert!(sorted_remap.iter().copied().eq(0..points.len() as u32));
+
+        let sorted = remap_vertex_buffer(&points, points.len(), &remap);
+        let unsorted_size = crate::encode_vertex_buffer(&points).unwrap().len();
+        let sorted_size = crate::encode_vertex_buffer(&sorted).unwrap().len();
+        assert!(sorted_size < unsorted_size);
+
+        let empty = VertexDataAdapter::new(&[], 12, 0).unwrap();
+        assert!(spatial_sort_remap(&empty).is_empty());
+    }
+
+    #[test]
+    fn test_spatial_sort_mesh() {
+        use rand::{seq::SliceRandom, SeedableRng};
+
+        let mut rng = rand::rngs::StdRng::seed_from_u64(3);
+        let vertices: Vec<Vertex> = (0..17 * 17)
+            .map(|i| Vertex {
+                p: [(i % 17) as f32, (i / 17) as f32, 0.0],
+                n: [0.0, 0.0, 1.0],
+                t: [(i % 17) as f32 / 16.0, (i / 17) as f32 / 16.0],
+            })
+            .collect();
+        let mut order: Vec<u32> = (0..vertices.len() as u32).collect();
+        order.shuffle(&mut rng);
+        let shuffled = remap_vertex_buffer(&vertices, vertices.len(), &order);
+        let mut indices = Vec::new();
+        for y in 0..16 {
+            for x in 0..16 {
+                let i = y * 17 + x;
+                for corner in [i, i + 1, i + 17, i + 1, i + 18, i + 17] {
+                    indices.push(order[corner as usize]);
+                }
+            }
+        }
+
+        let adapter =
+            VertexDataAdapter::new(typed_to_bytes(&shuffled), mem::size_of::<Vertex>(), 0).unwrap();
+        let (sorted, sorted_indices) = spatial_sort_mesh(&shuffled, &indices, &adapter);
+        assert_eq!(sorted.len(), shuffled.len());
+        assert_eq!(sorted_indices.len(), indices.len());
+        for (&old, &new) in indices.iter().zip(&sorted_indices) {
+            assert_eq!(shuffled[old as usize], sorted[new as usize]);
+        }
+
+        let positions: Vec<[f32; 3]> = shuffled.iter().map(|v| v.p).collect();
+        let uvs: Vec<[f32; 2]> = shuffled.iter().map(|v| v.t).collect();
+        // the streams are plain float arrays without padding
+        let input = unsafe {
+            [
+                VertexStream::from_slice(&positions),
+                VertexStream::from_slice(&uvs),
+            ]
+        };
+        let (streams, multi_indices) = spatial_sort_mesh_multi(&indices, &adapter, &input);
+        assert_eq!(multi_indices, sorted_indices);
+        let sorted_positions: Vec<[f32; 3]> = sorted.iter().map(|v| v.p).collect();
+        let sorted_uvs: Vec<[f32; 2]> = sorted.iter().map(|v| v.t).collect();
+        assert_eq!(streams[0], typed_to_bytes(&sorted_positions));
+        assert_eq!(streams[1], typed_to_bytes(&sorted_uvs));
+    }
+
+    #[test]
+    fn test_spatial_cluster_points() {
+        use rand::{Rng, SeedableRng};
+
+        let mut rng = rand::rngs::StdRng::seed_from_u64(7);
+        let positions: Vec<[f32; 3]> = (0..1000)
+            .map(|_| [rng.gen(), rng.gen(), rng.gen()])
+            .collect();
+        let adapter = VertexDataAdapter::new(typed_to_bytes(&positions), 12, 0).unwrap();
+
+        let clustered = spatial_cluster_points(&adapter, 64);
+        let mut sorted = clustered.clone();
+        sorted.sort_unstable();
+        assert!(sorted.iter().copied().eq(0..positions.len() as u32));
+
+        // sum of the bounding box diagonals (squared) of each cluster
+        let extent = |order: &[u32]| -> f32 {
+            order
+                .chunks(64)
+                .map(|cluster| {
+                    let mut min = [f32::MAX; 3];
+                    let mut max = [f32::MIN; 3];
+                    for &point in cluster {
+                        for axis in 0..3 {
+                            min[axis] = min[axis].min(positions[point as usize][axis]);
+                            max[axis] = max[axis].max(positions[point as usize][axis]);
+                        }
+                    }
+                    (0..3)
+                        .map(|axis| (max[axis] - min[axis]).powi(2))
+                        .sum::<f32>()
+                })
+                .sum()
+        };
+        let random: Vec<u32> = (0..positions.len() as u32).collect();
+        assert!(extent(&clustered) * 2.0 < extent(&random));
+
+        let empty = VertexDataAdapter::new(&[], 12, 0).unwrap();
+        assert!(spatial_cluster_points(&empty, 64).is_empty());
+    }
+
+    #[test]
+    fn test_spatial_sort_triangles() {
+        let size = 16;
+        let indices = scrambled_grid(size);
+        let positions = grid_positions(size);
+        let adapter = VertexDataAdapter::new(typed_to_bytes(&positions), 12, 0).unwrap();
+
+        let sorted = spatial_sort_triangles(&indices, &adapter);
+        assert_ne!(sorted, indices);
+        assert_eq!(sorted_triangles(&sorted), sorted_triangles(&indices));
+
+        let mut in_place = indices.clone();
+        spatial_sort_triangles_in_place(&mut in_place, &adapter);
+        assert_eq!(in_place, sorted);
+
+        assert!(spatial_sort_triangles(&[], &adapter).is_empty());
+    }
+
+    #[test]
+    fn test_optimize_vertex_fetch_remap_matches_optimize_vertex_fetch() {
+        let size = 16;
+        let indices = scrambled_grid(size);
+        // Interleaved position + uv, with a couple of trailing vertices that no
+        // triangle references.
+        let vertex_count = ((size + 1) * (size + 1)) as usize + 2;
+        let vertices: Vec<[f32; 5]> = (0..vertex_count)
+            .map(|i| {
+                let f = i as f32;
+                [f, f * 0.5, -f, f * 0.25, 1.0 - f]
+            })
+            .collect();
+
+        let mut fetch_indices = indices.clone();
+        let fetch_vertices = optimize_vertex_fetch(&mut fetch_indices, &vertices);
+
+        let (remap, unique_count) = optimize_vertex_fetch_remap(&indices, vertex_count);
+        assert_eq!(remap.len(), vertex_count);
+        assert_eq!(unique_count, vertex_count - 2);
+        assert_eq!(remap[vertex_count - 1], u32::MAX);
+
+        let remap_indices = remap_index_buffer(Some(&indices), vertex_count, &remap);
+        let remap_vertices = remap_vertex_buffer(&vertices, unique_count, &remap);
+
+        assert_eq!(remap_indices, fetch_indices);
+        assert_eq!(
+            typed_to_bytes(&remap_vertices),
+            typed_to_bytes(&fetch_vertices)
+        );
+    }
+
+    fn layered_positions(size: u32) -> Vec<[f32; 3]> {
+        (0..(size + 1) * (size + 1))
+            .map(|i| {
+                let x = (i % (size + 1)) as f32;
+                let y = (i / (size + 1)) as f32;
+                [x, y, (x * 0.7).sin() * (y * 0.3).cos() * 4.0]
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_optimize_overdraw_with_stats() {
+        let size = 32;
+        let indices =
+            optimize_vertex_cache(&scrambled_grid(size), ((size + 1) * (size + 1)) as usize);
+        let positions = layered_positions(size);
+        let adapter = VertexDataAdapter::new(typed_to_bytes(&positions), 12, 0).unwrap();
+
+        for threshold in [1.0, 1.05, 1.5, 3.0] {
+            let (optimized, stats) = optimize_overdraw_with_stats(&indices, &adapter, threshold);
+            assert_eq!(optimized, optimize_overdraw(&indices, &adapter, threshold));
+            assert!(stats.hard_cluster_count >= 1);
+            assert!(stats.cluster_count >= stats.hard_cluster_count);
+            let triangles = indices.len() / 3;
+            assert!(
+                (stats.average_cluster_size * stats.cluster_count as f32 - triangles as f32).abs()
+                    < 0.5
+            );
+
+            // the native optimizer only reorders whole clusters, so its output must be made
+            // of exactly the clusters recomputed in Rust
+            let (_, boundaries) = overdraw_clusters(&indices, positions.len(), threshold);
+            let mut clusters: Vec<&[u32]> = boundaries
+                .iter()
+                .enumerate()
+                .map(|(i, &start)| {
+                    let end = boundaries.get(i + 1).map_or(triangles, |&end| end as usize);
+                    &indices[start as usize * 3..end * 3]
+                })
+                .collect();
+            let mut rest = optimized.as_slice();
+            while !rest.is_empty() {
+                let found = clusters
+                    .iter()
+                    .position(|cluster| rest.starts_with(cluster))
+                    .expect("output must consist of whole clusters");
+                rest = &rest[clusters.swap_remove(found).len()..];
+            }
+            assert!(clusters.is_empty());
+        }
+
+        let empty = optimize_overdraw_with_stats(&[], &adapter, 1.05);
+        assert!(empty.0.is_empty());
+        assert_eq!(empty.1, OverdrawOptimizeStatistics::default());
+    }
+
+    #[test]
+    fn test_optimize_overdraw_with_stats_threshold_direction() {
+        let size = 32;
+        let indices =
+            optimize_vertex_cache(&scrambled_grid(size), ((size + 1) * (size + 1)) as usize);
+        let positions = layered_positions(size);
+        let adapter = VertexDataAdapter::new(typed_to_bytes(&positions), 12, 0).unwrap();
+
+        // like meshopt_optimizeOverdraw, a higher threshold gives more and smaller clusters
+        let stats: Vec<OverdrawOptimizeStatistics> = (0..=8)
+            .map(|step| {
+                optimize_overdraw_with_stats(&indices, &adapter, 1.0 + step as f32 * 0.25).1
+            })
+            .collect();
+        for pair in stats.windows(2) {
+            assert!(pair[1].cluster_count >= pair[0].cluster_count);
+            assert!(pair[1].average_cluster_size <= pair[0].average_cluster_size);
+        }
+        assert!(stats[8].cluster_count > stats[0].cluster_count);
+    }
+
+    #[test]
+    fn test_optimize_overdraw_variants_agree() {
+        let size = 16;
+        let indices =
+            optimize_vertex_cache(&scrambled_grid(size), ((size + 1) * (size + 1)) as usize);
+        let positions = layered_positions(size);
+        // Interleave the positions with a padding attribute so the adapter stride differs
+        // from the position size.
+        let interleaved: Vec<[f32; 5]> = positions
+            .iter()
+            .map(|p| [p[0], p[1], p[2], 0.5, 0.25])
+            .collect();
+        let adapter =
+            VertexDataAdapter::new(typed_to_bytes(&interleaved), mem::size_of::<[f32; 5]>(), 0)
+                .unwrap();
+
+        let optimized = optimize_overdraw(&indices, &adapter, 1.05);
+        assert_ne!(optimized, indices);
+
+        let mut in_place = indices.clone();
+        optimize_overdraw_in_place(&mut in_place, &adapter, 1.05);
+        assert_eq!(in_place, optimized);
+
+        let from_positions = optimize_overdraw_positions(
+            &indices,
+            interleaved.as_flattened(),
+            mem::size_of::<[f32; 5]>(),
+            1.05,
+        );
+        assert_eq!(from_positions, optimized);
+
+        let mut decoded = indices.clone();
+        optimize_overdraw_in_place_decoder(&mut decoded, &positions, 1.05);
+        assert_eq!(decoded, optimized);
+    }
+
+    #[test]
+    fn test_optimizer_vertex_fetch_into_non_copy() {
+        // owned vertices must be cloned, not copied bytewise
+        let vertices: Vec<String> = ["a", "b", "c", "d"].iter().map(|v| v.to_string()).collect();
+        let mut indices = [3, 1, 0, 1, 3, 0];
+        let mut out = vec![String::from("stale")];
+        Optimizer::new().optimize_vertex_fetch_into(&mut indices, &vertices, &mut out);
+        assert_eq!(out, ["d", "b", "a"]);
+        assert_eq!(indices, [0, 1, 2, 1, 0, 2]);
+    }
+
+    #[test]
+    fn test_optimizer_matches_allocating_functions() {
+        let mut optimizer = Optimizer::new();
+        let mut out_indices = Vec::new();
+        let mut out_vertices = Vec::new();
+        for size in [4, 16, 8] {
+            let vertex_count = ((size + 1) * (size + 1)) as usize;
+            let indices = scrambled_grid(size);
+            let positions = layered_positions(size);
+            let adapter =
+                VertexDataAdapter::new(typed_to_bytes(&positions), mem::size_of::<[f32; 3]>(), 0)
+                    .unwrap();
+
+            optimizer.optimize_vertex_cache_into(&indices, vertex_count, &mut out_indices);
+            let cache_optimized = optimize_vertex_cache(&indices, vertex_count);
+            assert_eq!(out_indices, cache_optimized);
+
+            optimizer.optimize_overdraw_into(&cache_optimized, &adapter, 1.05, &mut out_indices);
+            assert_eq!(
+                out_indices,
+                optimize_overdraw(&cache_optimized, &adapter, 1.05)
+            );
+
+            let mut fetch_indices = cache_optimized.clone();
+            optimizer.optimize_vertex_fetch_into(&mut fetch_indices, &positions, &mut out_vertices);
+            let mut expected_indices = cache_optimized.clone();
+            let expected = optimize_vertex_fetch(&mut expected_indices, &positions);
+            assert_eq!(fetch_indices, expected_indices);
+            assert_eq!(out_vertices, expected);
+
+            // every vertex appears twice, once per half of the index buffer
+            let duplicated: Vec<[f32; 3]> = positions.iter().chain(&positions).copied().collect();
+            let split: Vec<u32> = indices
+                .iter()
+                .enumerate()
+                .map(|(i, &index)| index + (i % 2 * vertex_count) as u32)
+                .collect();
+            let unique = optimizer.deduplicate_into(
+                &split,
+                &duplicated,
+                &mut out_indices,
+                &mut out_vertices,
+            );
+            let (expected_unique, remap) = generate_vertex_remap(&duplicated, Some(&split));
+            assert_eq!(unique, expected_unique);
+            assert_eq!(
+                out_indices,
+                remap_index_buffer(Some(&split), duplicated.len(), &remap)
+            );
+            assert_eq!(
+                out_vertices,
+                remap_vertex_buffer(&duplicated, expected_unique, &remap)
+            );
+        }
+    }
+
+    #[test]
+    fn test_optimize_vertex_cache_and_overdraw() {
+        let size = 16;
+        let vertex_count = ((size + 1) * (size + 1)) as usize;
+        let indices = scrambled_grid(size);
+        let positions = layered_positions(size);
+        let adapter =
+            VertexDataAdapter::new(typed_to_bytes(&positions), mem::size_of::<[f32; 3]>(), 0)
+                .unwrap();
+
+        let combined = optimize_vertex_cache_and_overdraw(&indices, &adapter, 1.05);
+        let manual = optimize_overdraw(
+            &optimize_vertex_cache(&indices, vertex_count),
+            &adapter,
+            1.05,
+        );
+        assert_eq!(combined, manual);
+
+        let before = analyze_vertex_cache(&indices, vertex_count, 16, 0, 0);
+        let after = analyze_vertex_cache(&combined, vertex_count, 16, 0, 0);
+        assert!(after.acmr < before.acmr);
+    }
+
+    #[test]
+    fn test_optimize_vertex_cache_and_overdraw_positions() {
+        let size = 16;
+        let vertex_count = ((size + 1) * (size + 1)) as usize;
+        let indices = scrambled_grid(size);
+        let positions = layered_positions(size);
+        let flat = positions.as_flattened();
+
+        let combined = optimize_vertex_cache_and_overdraw_positions(&indices, flat, 12, 1.05);
+        let adapter = VertexDataAdapter::new(typed_to_bytes(&positions), 12, 0).unwrap();
+        assert_eq!(
+            combined,
+            optimize_vertex_cache_and_overdraw(&indices, &adapter, 1.05)
+        );
+
+        // without the vertex cache pass the overdraw optimizer only sees scrambled clusters
+        let overdraw_only = optimize_overdraw_positions(&indices, flat, 12, 1.05);
+        let combined_cache = analyze_vertex_cache(&combined, vertex_count, 16, 0, 0);
+        let overdraw_only_cache = analyze_vertex_cache(&overdraw_only, vertex_count, 16, 0, 0);
+        assert!(combined_cache.acmr < overdraw_only_cache.acmr);
+    }
+
+    #[test]
+    fn test_positions_vertex_count() {
+        assert_eq!(position_count(&[], 12), 0);
+        assert_eq!(position_count(&[0.0; 2], 12), 0);
+        assert_eq!(position_count(&[0.0; 3], 16), 1);
+        assert_eq!(position_count(&[0.0; 7], 16), 2);
+        assert_eq!(position_count(&[0.0; 8], 16), 2);
+
+        // an interleaved array that ends right after the last position
+        let size = 8;
+        let indices = scrambled_grid(size);
+        let interleaved: Vec<[f32; 4]> = layered_positions(size)
+            .iter()
+            .map(|p| [p[0], p[1], p[2], 1.0])
+            .collect();
+        let full = interleaved.as_flattened();
+        let trimmed = &full[..full.len() - 1];
+        assert_eq!(
+            optimize_overdraw_positions(&indices, trimmed, 16, 1.05),
+            optimize_overdraw_positions(&indices, full, 16, 1.05)
+        );
+        assert_eq!(
+            optimize_vertex_cache_and_overdraw_positions(&indices, trimmed, 16, 1.05),
+            optimize_vertex_cache_and_overdraw_positions(&indices, full, 16, 1.05)
+        );
+        assert_eq!(
+            crate::analyze_overdraw_positions(&indices, trimmed, 16).overdraw,
+            crate::analyze_overdraw_positions(&indices, full, 16).overdraw
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_optimize_overdraw_low_threshold() {
+        let positions = layered_positions(4);
+        optimize_overdraw_positions(&scrambled_grid(4), positions.as_flattened(), 12, 0.5);
+    }
+
+    #[test]
+    #[should_panic(expected = "threshold must be at least 1.0")]
+    fn test_optimize_overdraw_in_place_nan_threshold() {
+        let positions = layered_positions(4);
+        let adapter = VertexDataAdapter::new(typed_to_bytes(&positions), 12, 0).unwrap();
+        optimize_overdraw_in_place(&mut scrambled_grid(4), &adapter, f32::NAN);
+    }
+
+    #[test]
+    #[should_panic(expected = "invalid index")]
+    fn test_optimize_overdraw_out_of_range_index() {
+        let positions = layered_positions(4);
+        let adapter = VertexDataAdapter::new(typed_to_bytes(&positions), 12, 0).unwrap();
+        let mut indices = scrambled_grid(4);
+        indices[1] = positions.len() as u32;
+        optimize_overdraw(&indices, &adapter, 1.05);
+    }
+
+    #[test]
+    #[should_panic(expected = "invalid index")]
+    fn test_optimize_overdraw_positions_out_of_range_index() {
+        let positions = layered_positions(4);
+        let mut indices = scrambled_grid(4);
+        indices[1] = positions.len() as u32;
+        optimize_overdraw_positions(&indices, positions.as_flattened(), 12, 1.05);
+    }
+
+    #[test]
+    fn test_optimize_mesh() {
+        let size = 16;
+        let indices = scrambled_grid(size);
+        let positions = layered_positions(size);
+        // unindexed copy, so the remap has duplicates to remove
+        let unindexed: Vec<Vertex> = indices
+            .iter()
+            .map(|&i| {
+                let p = positions[i as usize];
+                Vertex {
+                    p,
+                    n: [0.0, 0.0, 1.0],
+                    t: [p[0] / size as f32, p[1] / size as f32],
+                }
+            })
+            .collect();
+        let unindexed_indices: Vec<u32> = (0..unindexed.len() as u32).collect();
+
+        let optimized = optimize_mesh(&unindexed, &unindexed_indices, OptimizeSettings::default());
+        assert_eq!(optimized.vertices.len(), positions.len());
+        assert_eq!(optimized.indices.len(), indices.len());
+        assert!(optimized.vertex_cache_after.acmr < optimized.vertex_cache_before.acmr);
+        assert_eq!(
+            optimized.overdraw_after.pixels_covered,
+            optimized.overdraw_before.pixels_covered
+        );
+
+        // the optimized mesh describes the same triangles
+        let triangle_positions = |vertices: &[Vertex], indices: &[u32]| {
+            let mut triangles: Vec<[u32; 9]> = indices
+                .chunks_exact(3)
+                .map(|t| {
+                    let mut triangle = [0; 9];
+                    for (k, &i) in t.iter().enumerate() {
+                        for (c, v) in vertices[i as usize].p.iter().enumerate() {
+                            triangle[k * 3 + c] = v.to_bits();
+                        }
+                    }
+                    triangle
+                })
+                .collect();
+            triangles.sort_unstable();
+            triangles
+        };
+        assert_eq!(
+            triangle_positions(&optimized.vertices, &optimized.indices),
+            triangle_positions(&unindexed, &unindexed_indices)
+        );
+
+        // vertex fetch optimization orders vertices by first use
+        let mut seen = 0;
+        for &i in &optimized.indices {
+            assert!(i <= seen);
+            seen = seen.max(i + 1);
+        }
+
+        let positions_only = optimize_mesh(
+            &positions,
+            &indices,
+            OptimizeSettings {
+                overdraw_threshold: 1.0,
+                optimize_vertex_fetch: false,
+            },
+        );
+        assert_eq!(positions_only.vertices.len(), positions.len());
+        assert!(positions_only.vertex_cache_after.acmr < positions_only.vertex_cache_before.acmr);
+    }
+}
@@ -1,4 +1,5 @@
-use crate::{quantize_half, quantize_snorm};
+use crate::{dequantize_half, quantize_float, quantize_half, quantize_snorm, quantize_unorm};
+use alloc::{vec, vec::Vec};
 use float_cmp::ApproxEqUlps;
 
 pub trait DecodePosition {
@@ -18,13 +19,13 @@ pub trait FromVertex {
 #[derive(Default, Debug, Copy, Clone, Eq, PartialEq)]
 #[repr(C)]
 pub struct PackedVertex {
-    /// Unsigned 16-bit value, use pos_offset/pos_scale to unpack
+    /// Unsigned 16-bit value, use `pos_offset`/`pos_scale` to unpack
     pub p: [u16; 4],
 
     /// Normalized signed 8-bit value
     pub n: [i8; 4],
 
-    /// Unsigned 16-bit value, use uv_offset/uv_scale to unpack
+    /// Unsigned 16-bit value, use `uv_offset`/`uv_scale` to unpack
     pub t: [u16; 2],
 }
 
@@ -125,3 +126,284 @@ pub fn pack_vertices<T: FromVertex + Default + Clone>(input: &[Vertex]) -> Vec<T
     }
     vertices
 }
+
+/// How `quantize_attributes` stores each component of an attribute.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub enum AttributeKind {
+    /// Unsigned normalized fixed point (see `quantize_unorm`); input is expected in `[0..1]`,
+    /// e.g. after normalizing positions with `calc_pos_offset_and_scale`.
+    #[default]
+    Unorm,
+    /// Signed normalized fixed point (see `quantize_snorm`); input is expected in `[-1..1]`.
+    Snorm,
+    /// Half-precision floating point (see `quantize_half`); `bits` is ignored.
+    Half,
+    /// Full-precision floating point with `bits` significant mantissa bits
+    /// (see `quantize_float`).
+    Float,
+}
+
+/// Describes one attribute of an interleaved float vertex for `quantize_attributes`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct AttributeSpec {
+    /// How the attribute is quantized.
+    pub kind: AttributeKind,
+    /// Bits per component: `1..=16` for `Unorm`, `2..=16` for `Snorm`, `1..=23` for `Float`.
+    pub bits: u32,
+    /// Offset of the attribute within each source vertex, in floats.
+    pub offset: usize,
+    /// Number of components, at least 1.
+    pub count: usize,
+}
+
+impl AttributeSpec {
+    /// Size in bytes of one quantized component: 1 byte for up to 8 bits of `Unorm`/`Snorm`,
+    /// 2 bytes for wider fixed point and `Half`, and 4 bytes for `Float`.
+    pub fn component_size(&self) -> usize {
+        match self.kind {
+            AttributeKind::Unorm | AttributeKind::Snorm if self.bits <= 8 => 1,
+            AttributeKind::Unorm | AttributeKind::Snorm | AttributeKind::Half => 2,
+            AttributeKind::Float => 4,
+        }
+    }
+
+    /// Size in bytes of the quantized attribute, padded to a multiple of 4 bytes.
+    pub fn size(&self) -> usize {
+        (self.component_size() * self.count).next_multiple_of(4)
+    }
+
+    fn check(&self) {
+        assert!(self.count > 0, "attribute component count must be non-zero");
+        let bits = match self.kind {
+            AttributeKind::Unorm => 1..=16,
+            AttributeKind::Snorm => 2..=16,
+            AttributeKind::Half => return,
+            AttributeKind::Float => 1..=23,
+        };
+        assert!(
+            bits.contains(&self.bits),
+            "{:?} attribute bits ({}) must be in {:?}",
+            self.kind,
+            self.bits,
+            bits
+        );
+    }
+}
+
+/// Returns the size in bytes of one vertex quantized by `quantize_attributes` with `layout`.
+pub fn quantized_vertex_size(layout: &[AttributeSpec]) -> usize {
+    layout.iter().map(AttributeSpec::size).sum()
+}
+
+/// Returns the size in floats of one source vertex described by `layout`.
+fn source_vertex_size(layout: &[AttributeSpec]) -> usize {
+    layout
+        .iter()
+        .map(|attribute| attribute.offset + attribute.count)
+        .max()
+        .unwrap_or(0)
+}
+
+/// Quantizes interleaved float `vertices` into a packed byte buffer with one attribute
+/// after another in `layout` order, ready for `encode_vertex_streams` (e.g. through
+/// `VertexStream::from_bytes`) or for uploading to the GPU.
+///
+/// The source vertex size in floats is the largest `offset + count` in `layout`. Each
+/// quantized attribute is stored little-endian with its components tightly packed and is
+/// padded with zeros to a multiple of 4 bytes, so every vertex takes
+/// `quantized_vertex_size(layout)` bytes. Use `dequantize_attributes` to reverse it.
+///
+/// # Panics
+///
+/// Panics if `layout` is empty, if an attribute has no components or bits out of range
+/// for its kind, or if the length of `vertices` is not a multiple of the source vertex size.
+pub fn quantize_attributes(vertices: &[f32], layout: &[AttributeSpec]) -> Vec<u8> {
+    assert!(!layout.is_empty(), "attribute layout must not be empty");
+    layout.iter().for_each(AttributeSpec::check);
+    let source_size = source_vertex_size(layout);
+    assert!(
+        vertices.len().is_multiple_of(source_size),
+        "vertices length ({}) must be a multiple of the vertex size ({})",
+        vertices.len(),
+        source_size
+    );
+
+    let mut result =
+        Vec::with_capacity(vertices.len() / source_size * quantized_vertex_size(layout));
+    for vertex in vertices.chunks_exact(source_size) {
+        for attribute in layout {
+            let start = result.len();
+            for &v in &vertex[attribute.offset..attribute.offset + attribute.count] {
+                match (attribute.kind, attribute.component_size()) {
+                    (AttributeKind::Unorm, 1) => {
+                        result.push(quantize_unorm(v, attribute.bits as i32) as u8);
+                    }
+                    (AttributeKind::Unorm, _) => result.extend_from_slice(
+                        &(quantize_unorm(v, attribute.bits as i32) as u16).to_le_bytes(),
+                    ),
+                    (AttributeKind::Snorm, 1) => {
+                        result.push(quantize_snorm(v, attribute.bits) as i8 as u8);
+                    }
+                    (AttributeKind::Snorm, _) => result.extend_from_slice(
+                        &(quantize_snorm(v, attribute.bits) as i16).to_le_bytes(),
+                    ),
+                    (AttributeKind::Half, _) => {
+                        result.extend_from_slice(&quantize_half(v).to_le_bytes());
+                    }
+                    (AttributeKind::Float, _) => result
+                        .extend_from_slice(&quantize_float(v, attribute.bits as i32).to_le_bytes()),
+                }
+            }
+            result.resize(start + attribute.size(), 0);
+        }
+    }
+    result
+}
+
+/// Reverses `quantize_attributes`, converting packed `data` back into interleaved float
+/// vertices with the source layout described by `layout`.
+///
+/// Floats of the source vertex that are not covered by any attribute are set to zero.
+///
+/// # Panics
+///
+/// Panics under the same conditions as `quantize_attributes`, or if the length of `data`
+/// is not a multiple of `quantized_vertex_size(layout)`.
+pub fn dequantize_attributes(data: &[u8], layout: &[AttributeSpec]) -> Vec<f32> {
+    assert!(!layout.is_empty(), "attribute layout must not be empty");
+    layout.iter().for_each(AttributeSpec::check);
+    let vertex_size = quantized_vertex_size(layout);
+    assert!(
+        data.len().is_multiple_of(vertex_size),
+        "data length ({}) must be a multiple of the quantized vertex size ({})",
+        data.len(),
+        vertex_size
+    );
+
+    let source_size = source_vertex_size(layout);
+    let mut result = vec![0f32; data.len() / vertex_size * source_size];
+    for (vertex, packed) in result
+        .chunks_exact_mut(source_size)
+        .zip(data.chunks_exact(vertex_size))
+    {
+        let mut packed = packed;
+        for attribute in layout {
+            let (bytes, rest) = packed.split_at(attribute.size());
+            packed = rest;
+            let component_size = attribute.component_size();
+            let components = bytes.chunks_exact(component_size);
+            let target = &mut vertex[attribute.offset..attribute.offset + attribute.count];
+            for (v, c) in target.iter_mut().zip(components) {
+                *v = match (attribute.kind, component_size) {
+                    (AttributeKind::Unorm, 1) => {
+                        f32::from(c[0]) / ((1u32 << attribute.bits) - 1) as f32
+                    }
+                    (AttributeKind::Unorm, _) => {
+                        f32::from(u16::from_le_bytes([c[0], c[1]]))
+                            / ((1u32 << attribute.bits) - 1) as f32
+                    }
+                    (AttributeKind::Snorm, 1) => (f32::from(c[0] as i8)
+                        / ((1u32 << (attribute.bits - 1)) - 1) as f32)
+                        .max(-1f32),
+                    (AttributeKind::Snorm, _) => (f32::from(i16::from_le_bytes([c[0], c[1]]))
+                        / ((1u32 << (attribute.bits - 1)) - 1) as f32)
+                        .max(-1f32),
+                    (AttributeKind::Half, _) => dequantize_half(u16::from_le_bytes([c[0], c[1]])),
+                    (AttributeKind::Float, _) => f32::from_le_bytes([c[0], c[1], c[2], c[3]]),
+                };
+            }
+        }
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{decode_vertex_streams, encode_vertex_streams, VertexStream};
+
+    #[test]
+    fn test_quantize_attributes_round_trip() {
+        // position (normalized), normal, uv, an unused float and a float weight
+        let layout = [
+            AttributeSpec {
+                kind: AttributeKind::Unorm,
+                bits: 14,
+                offset: 0,
+                count: 3,
+            },
+            AttributeSpec {
+                kind: AttributeKind::Snorm,
+                bits: 8,
+                offset: 3,
+                count: 3,
+            },
+            AttributeSpec {
+                kind: AttributeKind::Half,
+                bits: 16,
+                offset: 6,
+                count: 2,
+            },
+            AttributeSpec {
+                kind: AttributeKind::Float,
+                bits: 10,
+                offset: 9,
+                count: 1,
+            },
+        ];
+        assert_eq!(quantized_vertex_size(&layout), 8 + 4 + 4 + 4);
+
+        let mut vertices = Vec::new();
+        for i in 0..100 {
+            let t = i as f32 / 99.0;
+            let angle = t * core::f32::consts::TAU;
+            vertices.extend_from_slice(&[t, 1.0 - t, t * t]);
+            vertices.extend_from_slice(&[angle.cos(), angle.sin(), 0.0]);
+            vertices.extend_from_slice(&[t * 4.0, -t * 2.0]);
+            vertices.push(42.0);
+            vertices.push(100.0 * t + 1.0);
+        }
+
+        let packed = quantize_attributes(&vertices, &layout);
+        assert_eq!(packed.len(), 100 * quantized_vertex_size(&layout));
+
+        // the packed buffer goes straight into the vertex codec
+        let stream_size = quantized_vertex_size(&layout);
+        let stream = VertexStream::from_bytes(&packed, stream_size, stream_size).unwrap();
+        let encoded = encode_vertex_streams(100, &[stream]).unwrap();
+        let decoded = decode_vertex_streams(
+            &encoded.iter().map(Vec::as_slice).collect::<Vec<_>>(),
+            100,
+            &[stream_size],
+        )
+        .unwrap();
+        assert_eq!(decoded[0], packed);
+
+        let restored = dequantize_attributes(&decoded[0], &layout);
+        assert_eq!(restored.len(), vertices.len());
+        for (source, restored) in vertices.chunks_exact(10).zip(restored.chunks_exact(10)) {
+            for i in 0..3 {
+                assert!((source[i] - restored[i]).abs() <= 1.0 / (1 << 15) as f32);
+                assert!((source[3 + i] - restored[3 + i]).abs() <= 1.0 / (1 << 7) as f32);
+            }
+            for i in 6..8 {
+                assert!((source[i] - restored[i]).abs() <= source[i].abs() * 5e-4);
+            }
+            // the float that no attribute covers is not stored
+            assert_eq!(restored[8], 0.0);
+            assert!((source[9] - restored[9]).abs() <= source[9] / (1 << 10) as f32);
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_quantize_attributes_bits_out_of_range() {
+        let layout = [AttributeSpec {
+            kind: AttributeKind::Unorm,
+            bits: 17,
+            offset: 0,
+            count: 1,
+        }];
+        quantize_attributes(&[0.5], &layout);
+    }
+}
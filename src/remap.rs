@@ -1,5 +1,13 @@
-use crate::{ffi, VertexStream};
-use std::mem;
+use crate::{
+    encoding::calc_pos_offset_and_scale, ffi, utilities::triangle_cross, Error, Result,
+    VertexDataAdapter, VertexStream,
+};
+use alloc::{
+    collections::{BTreeMap, BTreeSet},
+    format, vec,
+    vec::Vec,
+};
+use core::mem;
 
 /// Generates a vertex remap table from the vertex buffer and an optional index buffer and returns number of unique vertices.
 ///
@@ -21,7 +29,7 @@ pub fn generate_vertex_remap<T>(vertices: &[T], indices: Option<&[u32]>) -> (usi
             ),
             None => ffi::meshopt_generateVertexRemap(
                 remap.as_mut_ptr(),
-                std::ptr::null(),
+                core::ptr::null(),
                 vertices.len(),
                 vertices.as_ptr().cast(),
                 vertices.len(),
@@ -32,6 +40,209 @@ pub fn generate_vertex_remap<T>(vertices: &[T], indices: Option<&[u32]>) -> (usi
     (vertex_count, remap)
 }
 
+/// Like `generate_vertex_remap`, but welds vertices that have the same `key` instead of
+/// vertices that are binary equivalent, e.g. to weld within a tolerance by quantizing the
+/// position, or to ignore some attributes.
+///
+/// Each group of welded vertices maps to the location of its first vertex in index buffer
+/// order (or vertex order if `indices` is `None`); vertices that aren't referenced by
+/// `indices` map to `u32::MAX`. Runs in Rust rather than in meshoptimizer.
+///
+/// # Panics
+///
+/// Panics if `indices` references a vertex beyond the end of `vertices`.
+pub fn generate_vertex_remap_by<T, K: Ord>(
+    vertices: &[T],
+    indices: Option<&[u32]>,
+    key: impl Fn(&T) -> K,
+) -> (usize, Vec<u32>) {
+    let mut remap: Vec<u32> = vec![u32::MAX; vertices.len()];
+    let mut remap_by_key: BTreeMap<K, u32> = BTreeMap::new();
+    let mut next_vertex = 0u32;
+    let mut visit = |vertex: usize| {
+        if remap[vertex] != u32::MAX {
+            return;
+        }
+        remap[vertex] = *remap_by_key
+            .entry(key(&vertices[vertex]))
+            .or_insert_with(|| {
+                next_vertex += 1;
+                next_vertex - 1
+            });
+    };
+    match indices {
+        Some(indices) => indices.iter().for_each(|&index| visit(index as usize)),
+        None => (0..vertices.len()).for_each(&mut visit),
+    }
+    (next_vertex as usize, remap)
+}
+
+/// Welds vertices whose positions are within `tolerance` of each other and returns the
+/// number of unique vertices and the remap table, like `generate_vertex_remap`.
+///
+/// Positions are snapped onto a grid with cells of size `tolerance`, starting at the minimum
+/// corner of the mesh (see `calc_pos_offset_and_scale`), and vertices in the same cell are
+/// welded. This is best-effort: two vertices closer than `tolerance` can still end up in
+/// neighboring cells if they straddle a cell boundary. Vertices with non-finite positions
+/// are only welded to binary equivalent ones.
+///
+/// A `tolerance` of `0.0` welds binary equivalent positions only, which matches
+/// `generate_vertex_remap` on a buffer that only contains the positions.
+///
+/// # Panics
+///
+/// Panics if `tolerance` is negative or not finite, or if the position of a vertex is not
+/// within the vertex data.
+pub fn weld_vertices(positions: &VertexDataAdapter<'_>, tolerance: f32) -> (usize, Vec<u32>) {
+    assert!(
+        tolerance.is_finite() && tolerance >= 0.0,
+        "tolerance ({}) must be finite and non-negative",
+        tolerance
+    );
+
+    let positions: Vec<[f32; 3]> = (0..positions.vertex_count)
+        .map(|vertex| {
+            positions
+                .xyz_f32_at(vertex)
+                .expect("position must be within the vertex data")
+        })
+        .collect();
+
+    if tolerance == 0.0 {
+        return generate_vertex_remap_by(&positions, None, |position| position.map(f32::to_bits));
+    }
+
+    #[derive(PartialEq, Eq, PartialOrd, Ord)]
+    enum WeldKey {
+        Cell([i64; 3]),
+        Bits([u32; 3]),
+    }
+
+    let flat: Vec<f32> = positions.iter().flatten().copied().collect();
+    let (pos_offset, _) = calc_pos_offset_and_scale(&flat);
+    generate_vertex_remap_by(&positions, None, |position| {
+        if position.iter().all(|value| value.is_finite()) {
+            // finite components are never below the offset, so truncation rounds down
+            WeldKey::Cell(core::array::from_fn(|axis| {
+                ((position[axis] - pos_offset[axis]) / tolerance) as i64
+            }))
+        } else {
+            WeldKey::Bits(position.map(f32::to_bits))
+        }
+    })
+}
+
+/// Removes degenerate triangles and returns the remaining indices, in their original order,
+/// and the number of removed triangles.
+///
+/// Triangles that reference the same vertex more than once are always removed. When
+/// `positions` is provided, triangles with an area below `area_epsilon` times the squared
+/// extent of the mesh (see `calc_pos_offset_and_scale`) are removed as well; an
+/// `area_epsilon` of `0.0` only removes triangles with exactly zero area, and keeps thin
+/// slivers. Useful after simplification, welding or quantization.
+///
+/// # Panics
+///
+/// Panics if the index count is not a multiple of 3, if `area_epsilon` is negative or not
+/// finite, or if `positions` is provided and an index is out of range of it.
+pub fn remove_degenerate_triangles(
+    indices: &[u32],
+    positions: Option<&VertexDataAdapter<'_>>,
+    area_epsilon: f32,
+) -> (Vec<u32>, usize) {
+    assert!(
+        indices.len().is_multiple_of(3),
+        "index count must be a multiple of 3"
+    );
+    assert!(
+        area_epsilon.is_finite() && area_epsilon >= 0.0,
+        "area_epsilon ({}) must be finite and non-negative",
+        area_epsilon
+    );
+
+    let positions: Option<(Vec<[f32; 3]>, f32)> = positions.map(|positions| {
+        let positions: Vec<[f32; 3]> = (0..positions.vertex_count)
+            .map(|vertex| {
+                positions
+                    .xyz_f32_at(vertex)
+                    .expect("position must be within the vertex data")
+            })
+            .collect();
+        let flat: Vec<f32> = positions.iter().flatten().copied().collect();
+        let (_, pos_scale) = calc_pos_offset_and_scale(&flat);
+        // compare the squared length of the cross product, which is twice the area
+        let threshold = 2.0 * area_epsilon * pos_scale * pos_scale;
+        (positions, threshold * threshold)
+    });
+
+    let mut result = Vec::with_capacity(indices.len());
+    for triangle in indices.chunks_exact(3) {
+        let [a, b, c] = [triangle[0], triangle[1], triangle[2]];
+        if a == b || b == c || c == a {
+            continue;
+        }
+        if let Some((positions, threshold)) = &positions {
+            let position = |index: u32| {
+                *positions
+                    .get(index as usize)
+                    .expect("indices must be less than the vertex count")
+            };
+            let normal = triangle_cross(position(a), position(b), position(c));
+            let length_squared = normal.iter().map(|n| n * n).sum::<f32>();
+            if normal == [0.0; 3] || length_squared < *threshold {
+                continue;
+            }
+        }
+        result.extend_from_slice(triangle);
+    }
+    let removed = (indices.len() - result.len()) / 3;
+    (result, removed)
+}
+
+/// Which triangles `remove_duplicate_triangles` considers duplicates of each other.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub enum DuplicateMode {
+    /// Triangles with the same vertices in the same cyclic order, e.g. `abc` and `bca`.
+    #[default]
+    SameWinding,
+    /// Triangles with the same vertices in any order, which also matches mirrored copies
+    /// such as `abc` and `acb`.
+    AnyWinding,
+}
+
+/// Removes triangles that repeat an earlier triangle and returns the remaining indices, in
+/// their original order, and the number of removed triangles.
+///
+/// The first occurrence of each triangle is kept. Runs in `O(n log n)` for `n` triangles.
+///
+/// # Panics
+///
+/// Panics if the index count is not a multiple of 3.
+pub fn remove_duplicate_triangles(indices: &[u32], mode: DuplicateMode) -> (Vec<u32>, usize) {
+    assert!(
+        indices.len().is_multiple_of(3),
+        "index count must be a multiple of 3"
+    );
+
+    let mut seen = BTreeSet::new();
+    let mut result = Vec::with_capacity(indices.len());
+    for triangle in indices.chunks_exact(3) {
+        let mut key = [triangle[0], triangle[1], triangle[2]];
+        match mode {
+            DuplicateMode::SameWinding => {
+                let first = (0..3).min_by_key(|&i| key[i]).unwrap_or(0);
+                key.rotate_left(first);
+            }
+            DuplicateMode::AnyWinding => key.sort_unstable(),
+        }
+        if seen.insert(key) {
+            result.extend_from_slice(triangle);
+        }
+    }
+    let removed = (indices.len() - result.len()) / 3;
+    (result, removed)
+}
+
 /// Generates a vertex remap table from multiple vertex streams and an optional index buffer and returns number of unique vertices.
 ///
 /// As a result, all vertices that are binary equivalent map to the same (new) location, with no gaps in the resulting sequence.
@@ -40,19 +251,20 @@ pub fn generate_vertex_remap<T>(vertices: &[T], indices: Option<&[u32]>) -> (usi
 /// To remap vertex buffers, you will need to call `remap_vertex_buffer` for each vertex stream.
 ///
 /// The `indices` can be `None` if the input is unindexed.
+///
+/// Returns an error if there are no streams or more than 16, if a stream size is not in
+/// `1..=256` or larger than its stride, if a stream of 4 or more bytes per vertex is not 4 byte
+/// aligned, if a stream created from a slice doesn't have `vertex_count` vertices, or if an
+/// index is not less than `vertex_count`.
 pub fn generate_vertex_remap_multi(
     vertex_count: usize,
     streams: &[VertexStream<'_>],
     indices: Option<&[u32]>,
-) -> (usize, Vec<u32>) {
-    let streams: Vec<ffi::meshopt_Stream> = streams
-        .iter()
-        .map(|stream| ffi::meshopt_Stream {
-            data: stream.data.cast(),
-            size: stream.size,
-            stride: stream.stride,
-        })
-        .collect();
+) -> Result<(usize, Vec<u32>)> {
+    let streams = crate::ffi_streams(vertex_count, streams)?;
+    if let Some(indices) = indices {
+        crate::check_indices_in_range(indices, vertex_count)?;
+    }
     let mut remap: Vec<u32> = vec![0; vertex_count];
     let vertex_count = unsafe {
         match indices {
@@ -66,7 +278,7 @@ pub fn generate_vertex_remap_multi(
             ),
             None => ffi::meshopt_generateVertexRemapMulti(
                 remap.as_mut_ptr(),
-                std::ptr::null(),
+                core::ptr::null(),
                 vertex_count,
                 vertex_count,
                 streams.as_ptr(),
@@ -74,54 +286,671 @@ pub fn generate_vertex_remap_multi(
             ),
         }
     };
-    (vertex_count, remap)
+    Ok((vertex_count, remap))
 }
 
 /// Generate index buffer from the source index buffer and remap table generated by `generate_vertex_remap`.
 ///
 /// `indices` can be `None` if the input is unindexed.
+///
+/// # Panics
+///
+/// Panics if an index (or `vertex_count` for unindexed input) is out of range of `remap`.
 pub fn remap_index_buffer(indices: Option<&[u32]>, vertex_count: usize, remap: &[u32]) -> Vec<u32> {
-    let mut result: Vec<u32> = Vec::new();
-    if let Some(indices) = indices {
-        result.resize(indices.len(), 0u32);
-        unsafe {
-            ffi::meshopt_remapIndexBuffer(
-                result.as_mut_ptr(),
-                indices.as_ptr(),
-                indices.len(),
-                remap.as_ptr(),
-            );
+    let mut result: Vec<u32> = vec![0; indices.map_or(vertex_count, <[u32]>::len)];
+    remap_index_buffer_into(&mut result, indices, remap).expect("invalid remap input");
+    result
+}
+
+fn check_remap_indices(indices: &[u32], remap: &[u32]) -> Result<()> {
+    match indices.iter().find(|&&index| index as usize >= remap.len()) {
+        Some(index) => Err(Error::memory_dynamic(format!(
+            "index ({}) must be less than the remap table length ({})",
+            index,
+            remap.len()
+        ))),
+        None => Ok(()),
+    }
+}
+
+/// Like `remap_index_buffer`, but writes the remapped indices into `destination`.
+///
+/// `destination` must have the length of `indices`; if `indices` is `None`, the first
+/// `destination.len()` vertices are remapped as an unindexed input.
+///
+/// Returns an error if `destination` has the wrong length or an index is out of range of `remap`.
+pub fn remap_index_buffer_into(
+    destination: &mut [u32],
+    indices: Option<&[u32]>,
+    remap: &[u32],
+) -> Result<()> {
+    match indices {
+        Some(indices) => {
+            if destination.len() != indices.len() {
+                return Err(Error::memory_dynamic(format!(
+                    "destination length ({}) must match the index count ({})",
+                    destination.len(),
+                    indices.len()
+                )));
+            }
+            check_remap_indices(indices, remap)?;
         }
-    } else {
-        result.resize(vertex_count, 0u32);
-        unsafe {
-            ffi::meshopt_remapIndexBuffer(
-                result.as_mut_ptr(),
-                std::ptr::null(),
-                vertex_count,
-                remap.as_ptr(),
-            );
+        None => {
+            if destination.len() > remap.len() {
+                return Err(Error::memory_dynamic(format!(
+                    "destination length ({}) must not exceed the remap table length ({})",
+                    destination.len(),
+                    remap.len()
+                )));
+            }
         }
     }
+    unsafe {
+        ffi::meshopt_remapIndexBuffer(
+            destination.as_mut_ptr(),
+            indices.map_or(core::ptr::null(), <[u32]>::as_ptr),
+            destination.len(),
+            remap.as_ptr(),
+        );
+    }
+    Ok(())
+}
 
-    result
+/// Like `remap_index_buffer`, but remaps `indices` in place.
+///
+/// Returns an error if an index is out of range of `remap`.
+pub fn remap_index_buffer_in_place(indices: &mut [u32], remap: &[u32]) -> Result<()> {
+    check_remap_indices(indices, remap)?;
+    unsafe {
+        ffi::meshopt_remapIndexBuffer(
+            indices.as_mut_ptr(),
+            indices.as_ptr(),
+            indices.len(),
+            remap.as_ptr(),
+        );
+    }
+    Ok(())
 }
 
 /// Generates vertex buffer from the source vertex buffer and remap table generated by `generate_vertex_remap`.
+///
+/// # Panics
+///
+/// Panics if `remap` doesn't have an entry for every vertex, or if it maps a vertex at or
+/// beyond `vertex_count`.
 pub fn remap_vertex_buffer<T: Clone + Default>(
     vertices: &[T],
     vertex_count: usize,
     remap: &[u32],
 ) -> Vec<T> {
     let mut result: Vec<T> = vec![T::default(); vertex_count];
+    remap_vertex_buffer_into(&mut result, vertices, remap).expect("invalid remap input");
+    result
+}
+
+/// Like `remap_vertex_buffer`, but writes the remapped vertices into `destination`, which
+/// has to hold at least the unique vertex count returned together with `remap`.
+///
+/// Vertices that `remap` maps to `u32::MAX` (unused vertices) are skipped.
+///
+/// Returns an error if `remap` doesn't have an entry for every vertex, or if it maps a vertex
+/// beyond the end of `destination`.
+pub fn remap_vertex_buffer_into<T: Clone>(
+    destination: &mut [T],
+    vertices: &[T],
+    remap: &[u32],
+) -> Result<()> {
+    if remap.len() != vertices.len() {
+        return Err(Error::memory_dynamic(format!(
+            "remap table length ({}) must match the vertex count ({})",
+            remap.len(),
+            vertices.len()
+        )));
+    }
+    if let Some(&target) = remap
+        .iter()
+        .find(|&&target| target != u32::MAX && target as usize >= destination.len())
+    {
+        return Err(Error::memory_dynamic(format!(
+            "remapped vertex ({}) must be less than the destination length ({})",
+            target,
+            destination.len()
+        )));
+    }
+    // this is what meshopt_remapVertexBuffer does, but cloning keeps it sound for any `T`
+    for (vertex, &target) in vertices.iter().zip(remap) {
+        if target != u32::MAX {
+            destination[target as usize] = vertex.clone();
+        }
+    }
+    Ok(())
+}
+
+/// Converts an unindexed triangle soup into an index buffer and a vertex buffer without
+/// binary equivalent duplicates, as a starting point for the optimization functions.
+///
+/// This is `generate_vertex_remap` with `indices` set to `None`, followed by
+/// `remap_index_buffer` and `remap_vertex_buffer`.
+pub fn index_mesh<T: Clone + Default>(vertices: &[T]) -> (Vec<u32>, Vec<T>) {
+    let (vertex_count, remap) = generate_vertex_remap(vertices, None);
+    let indices = remap_index_buffer(None, vertices.len(), &remap);
+    let vertices = remap_vertex_buffer(vertices, vertex_count, &remap);
+    (indices, vertices)
+}
+
+/// Generates an index buffer for visibility buffer rendering, where each triangle's provoking
+/// vertex index is equal to its primitive id, and returns it together with the reorder table
+/// and the new vertex count.
+///
+/// The reorder table stores the original vertex id for each vertex referenced by the new index
+/// buffer, and should be used to load (or remap) the vertex data. The provoking vertex is the
+/// first vertex of each triangle; for OpenGL, rotate each triangle (abc -> bca) before rendering.
+///
+/// The new vertex count is the length of the reorder table, at most `vertex_count` plus the
+/// triangle count.
+///
+/// For maximum efficiency the index buffer should be optimized for vertex cache first.
+///
+/// Returns an error if the index count is not a multiple of 3, if any index is out of range
+/// of `vertex_count`, or if the new vertex count could exceed the 32-bit index range.
+pub fn generate_provoking_index_buffer(
+    indices: &[u32],
+    vertex_count: usize,
+) -> Result<(Vec<u32>, Vec<u32>, usize)> {
+    if !indices.len().is_multiple_of(3) {
+        return Err(Error::memory_dynamic(format!(
+            "index count ({}) must be a multiple of 3",
+            indices.len()
+        )));
+    }
+    crate::check_indices_in_range(indices, vertex_count)?;
+    // every triangle may need a new provoking vertex, which must still fit into a u32 index
+    let max_vertex_count = vertex_count as u64 + (indices.len() / 3) as u64;
+    if max_vertex_count > u32::MAX as u64 {
+        return Err(Error::memory_dynamic(format!(
+            "vertex count ({}) plus triangle count ({}) must fit into 32-bit indices",
+            vertex_count,
+            indices.len() / 3
+        )));
+    }
+
+    let mut result: Vec<u32> = vec![0; indices.len()];
+    let mut reorder: Vec<u32> = vec![0; max_vertex_count as usize];
+    let reorder_count = unsafe {
+        ffi::meshopt_generateProvokingIndexBuffer(
+            result.as_mut_ptr(),
+            reorder.as_mut_ptr(),
+            indices.as_ptr(),
+            indices.len(),
+            vertex_count,
+        )
+    };
+    reorder.truncate(reorder_count);
+    Ok((result, reorder, reorder_count))
+}
+
+// checks the requirements of the position based index buffer generators
+fn check_patch_input(indices: &[u32], vertices: &VertexDataAdapter<'_>) {
+    assert!(
+        indices.len().is_multiple_of(3),
+        "index count must be a multiple of 3"
+    );
+    assert!(
+        indices
+            .iter()
+            .all(|&index| (index as usize) < vertices.vertex_count),
+        "indices must be less than the vertex count"
+    );
+    assert!(
+        (12..=256).contains(&vertices.vertex_stride) && vertices.vertex_stride.is_multiple_of(4),
+        "vertex stride must be a multiple of 4 in 12..=256"
+    );
+}
+
+/// Generates an index buffer that can be used as a geometry shader input with triangle
+/// adjacency topology (e.g. `VK_PRIMITIVE_TOPOLOGY_TRIANGLE_LIST_WITH_ADJACENCY`), for
+/// silhouette detection and other geometry shader driven rendering.
+///
+/// Each triangle is converted into a 6-vertex patch: entries 0, 2 and 4 are the original
+/// triangle vertices and entries 1, 3 and 5 are the vertices opposite to edges 02, 24 and 40
+/// in the adjacent triangles, so the result has `indices.len() * 2` entries. Vertices are
+/// matched by position. An open edge without an adjacent triangle refers to its own first
+/// vertex instead, i.e. `patch[1] == patch[0]` for a border on edge 02.
+///
+/// # Panics
+///
+/// Panics if the index count is not a multiple of 3, if any index is out of range of the
+/// vertex count, or if the vertex stride is not a multiple of 4 in `12..=256`; see
+/// [`validate_mesh`](crate::validate_mesh).
+pub fn generate_adjacency_index_buffer(
+    indices: &[u32],
+    vertices: &VertexDataAdapter<'_>,
+) -> Vec<u32> {
+    check_patch_input(indices, vertices);
+    let mut result: Vec<u32> = vec![0; indices.len() * 2];
     unsafe {
-        ffi::meshopt_remapVertexBuffer(
-            result.as_mut_ptr().cast(),
-            vertices.as_ptr().cast(),
-            vertices.len(),
-            mem::size_of::<T>(),
-            remap.as_ptr(),
+        ffi::meshopt_generateAdjacencyIndexBuffer(
+            result.as_mut_ptr(),
+            indices.as_ptr(),
+            indices.len(),
+            vertices.pos_ptr(),
+            vertices.vertex_count,
+            vertices.vertex_stride,
+        );
+    }
+    result
+}
+
+/// Generates an index buffer that can be used for PN-AEN tessellation with crack-free
+/// displacement, see the "Tessellation on Any Budget" talk from GDC 2011.
+///
+/// Each triangle is converted into a 12-vertex patch, so the result has `indices.len() * 4`
+/// entries:
+/// - 0, 1, 2: original triangle vertices
+/// - 3, 4: opposing edge for edge 0, 1
+/// - 5, 6: opposing edge for edge 1, 2
+/// - 7, 8: opposing edge for edge 2, 0
+/// - 9, 10, 11: dominant vertices for corners 0, 1, 2
+///
+/// Vertices are matched by position, so vertices that are duplicated along attribute seams
+/// get the same dominant vertex on both sides. An open edge refers to itself as its opposing
+/// edge.
+///
+/// # Panics
+///
+/// Panics if the index count is not a multiple of 3, if any index is out of range of the
+/// vertex count, or if the vertex stride is not a multiple of 4 in `12..=256`; see
+/// [`validate_mesh`](crate::validate_mesh).
+pub fn generate_tessellation_index_buffer(
+    indices: &[u32],
+    vertices: &VertexDataAdapter<'_>,
+) -> Vec<u32> {
+    check_patch_input(indices, vertices);
+    let mut result: Vec<u32> = vec![0; indices.len() * 4];
+    unsafe {
+        ffi::meshopt_generateTessellationIndexBuffer(
+            result.as_mut_ptr(),
+            indices.as_ptr(),
+            indices.len(),
+            vertices.pos_ptr(),
+            vertices.vertex_count,
+            vertices.vertex_stride,
         );
     }
     result
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::rotate_triangle;
+
+    #[test]
+    fn test_generate_provoking_index_buffer() {
+        // a small fan where every triangle shares vertex 0
+        let indices = [0, 1, 2, 0, 2, 3, 0, 3, 4, 0, 4, 1, 1, 2, 3];
+        let (provoking, reorder, new_vertex_count) =
+            generate_provoking_index_buffer(&indices, 5).unwrap();
+        assert_eq!(provoking.len(), indices.len());
+        assert_eq!(reorder.len(), new_vertex_count);
+        assert!(new_vertex_count <= 5 + indices.len() / 3);
+        assert!(provoking
+            .iter()
+            .all(|&index| (index as usize) < reorder.len()));
+        assert!(reorder.iter().all(|&vertex| vertex < 5));
+
+        // each triangle's first index is its primitive id and therefore unique
+        for (triangle, chunk) in provoking.chunks(3).enumerate() {
+            assert_eq!(chunk[0] as usize, triangle);
+        }
+
+        // the reorder table maps the triangles back to the original ones, up to rotation
+        for (original, chunk) in indices.chunks(3).zip(provoking.chunks(3)) {
+            let remapped = [
+                reorder[chunk[0] as usize],
+                reorder[chunk[1] as usize],
+                reorder[chunk[2] as usize],
+            ];
+            assert_eq!(rotate_triangle(&remapped), rotate_triangle(original));
+        }
+
+        assert!(generate_provoking_index_buffer(&indices[..4], 5).is_err());
+        assert!(generate_provoking_index_buffer(&indices, 4).is_err());
+        assert!(generate_provoking_index_buffer(&indices, u32::MAX as usize).is_err());
+    }
+
+    #[test]
+    fn test_generate_adjacency_index_buffer() {
+        // a quad split along the 0-2 diagonal
+        let positions: [[f32; 3]; 4] = [
+            [0.0, 0.0, 0.0],
+            [1.0, 0.0, 0.0],
+            [1.0, 1.0, 0.0],
+            [0.0, 1.0, 0.0],
+        ];
+        let indices = [0, 1, 2, 0, 2, 3];
+        let adapter = VertexDataAdapter::new(crate::typed_to_bytes(&positions), 12, 0).unwrap();
+
+        let adjacency = generate_adjacency_index_buffer(&indices, &adapter);
+        assert_eq!(adjacency.len(), indices.len() * 2);
+        // the edge 2-0 of the first triangle is shared with the second one, opposite to 3;
+        // its other edges are open and refer to their own first vertex
+        assert_eq!(adjacency[..6], [0, 0, 1, 1, 2, 3]);
+        // the edge 0-2 of the second triangle is opposite to 1 in the first one
+        assert_eq!(adjacency[6..], [0, 1, 2, 2, 3, 3]);
+    }
+
+    #[test]
+    fn test_generate_tessellation_index_buffer() {
+        // a quad split along the 0-2 diagonal, with the diagonal duplicated as 4-5 for a uv seam
+        let positions: [[f32; 3]; 6] = [
+            [0.0, 0.0, 0.0],
+            [1.0, 0.0, 0.0],
+            [1.0, 1.0, 0.0],
+            [0.0, 1.0, 0.0],
+            [0.0, 0.0, 0.0],
+            [1.0, 1.0, 0.0],
+        ];
+        let indices = [0, 1, 2, 4, 5, 3];
+        let adapter = VertexDataAdapter::new(crate::typed_to_bytes(&positions), 12, 0).unwrap();
+
+        let patches = generate_tessellation_index_buffer(&indices, &adapter);
+        assert_eq!(patches.len(), indices.len() * 4);
+        let (first, second) = patches.split_at(12);
+        assert_eq!(first[..3], indices[..3]);
+        assert_eq!(second[..3], indices[3..]);
+        // the seam edge 2-0 of the first triangle is opposed by the duplicated edge 5-4
+        assert_eq!(first[7..9], [5, 4]);
+        // both sides of the seam agree on the dominant vertices of the shared corners
+        assert_eq!(first[9], 0);
+        assert_eq!(first[11], 2);
+        assert_eq!(second[9..11], [first[9], first[11]]);
+    }
+
+    #[test]
+    fn test_generate_vertex_remap_multi() {
+        // vertices 0 and 1 share a position but not a uv, vertices 0 and 2 are full duplicates
+        let positions: [[f32; 3]; 4] = [[0.0; 3], [0.0; 3], [0.0; 3], [1.0, 0.0, 0.0]];
+        let uvs: [[f32; 2]; 4] = [[0.0, 0.0], [1.0, 0.0], [0.0, 0.0], [1.0, 1.0]];
+        let indices = [0, 1, 3, 2, 1, 3];
+        let streams = unsafe {
+            [
+                VertexStream::from_slice(&positions),
+                VertexStream::from_slice(&uvs),
+            ]
+        };
+
+        let (unique, remap) = generate_vertex_remap_multi(4, &streams, Some(&indices)).unwrap();
+        assert_eq!(unique, 3);
+        assert_ne!(remap[0], remap[1]);
+        assert_eq!(remap[0], remap[2]);
+
+        let (unique, remap) =
+            generate_vertex_remap_multi(4, &streams[..1], Some(&indices)).unwrap();
+        assert_eq!(unique, 2);
+        assert_eq!(remap[0], remap[1]);
+    }
+
+    #[test]
+    fn test_generate_vertex_remap_multi_mismatched_streams() {
+        let positions = [[0.0f32; 3]; 4];
+        let uvs = [[0.0f32; 2]; 3];
+        let streams = unsafe {
+            [
+                VertexStream::from_slice(&positions),
+                VertexStream::from_slice(&uvs),
+            ]
+        };
+        assert!(generate_vertex_remap_multi(4, &streams, None).is_err());
+        assert!(generate_vertex_remap_multi(4, &[], None).is_err());
+        assert!(generate_vertex_remap_multi(4, &streams[..1], Some(&[0, 1, 4])).is_err());
+    }
+
+    #[test]
+    fn test_generate_vertex_remap_multi_from_bytes() {
+        // interleaved position and uv, matching the streams of the test above
+        let vertices: [[f32; 5]; 4] = [
+            [0.0, 0.0, 0.0, 0.0, 0.0],
+            [0.0, 0.0, 0.0, 1.0, 0.0],
+            [0.0, 0.0, 0.0, 0.0, 0.0],
+            [1.0, 0.0, 0.0, 1.0, 1.0],
+        ];
+        let bytes = crate::typed_to_bytes(&vertices);
+        let streams = [
+            VertexStream::from_bytes(bytes, 12, 20).unwrap(),
+            VertexStream::from_bytes(&bytes[12..], 8, 20).unwrap(),
+        ];
+        assert_eq!(streams[0].vertex_count(), Some(4));
+        assert_eq!(streams[1].vertex_count(), Some(4));
+        let indices = [0, 1, 3, 2, 1, 3];
+        let (unique, remap) = generate_vertex_remap_multi(4, &streams, Some(&indices)).unwrap();
+        assert_eq!(unique, 3);
+        assert_ne!(remap[0], remap[1]);
+        assert_eq!(remap[0], remap[2]);
+
+        assert_eq!(
+            VertexStream::from_bytes(&bytes[..19], 12, 20)
+                .unwrap()
+                .vertex_count(),
+            Some(1)
+        );
+        assert_eq!(
+            VertexStream::from_bytes(&bytes[..8], 12, 20)
+                .unwrap()
+                .vertex_count(),
+            Some(0)
+        );
+        assert!(VertexStream::from_bytes(bytes, 24, 20).is_err());
+        assert!(VertexStream::from_bytes(bytes, 0, 20).is_err());
+        assert!(VertexStream::from_bytes(bytes, 260, 300).is_err());
+
+        // the stride of a stream can't be changed; a wider stride means a new stream, whose
+        // vertex count no longer matches
+        let wide = VertexStream::from_bytes(bytes, 12, 40).unwrap();
+        assert_eq!((wide.size(), wide.stride()), (12, 40));
+        assert_eq!(wide.vertex_count(), Some(2));
+        assert!(generate_vertex_remap_multi(4, &[wide], None).is_err());
+
+        // meshoptimizer reads streams of 4 bytes or more as `unsigned int`s
+        assert!(VertexStream::from_bytes(&bytes[2..], 12, 20).is_err());
+        assert!(VertexStream::from_bytes(bytes, 12, 18).is_err());
+        assert!(VertexStream::from_bytes(&bytes[1..], 3, 5).is_ok());
+        let words = [[0u32; 2]; 4];
+        let unaligned = [unsafe { VertexStream::from_slice_with_offset::<[u8; 4], _>(&words, 1) }];
+        assert!(generate_vertex_remap_multi(4, &unaligned, None).is_err());
+    }
+
+    #[test]
+    fn test_generate_vertex_remap_by() {
+        // a quad split into two triangles with unshared, slightly jittered corners
+        let positions: [[f32; 3]; 6] = [
+            [0.0, 0.0, 0.0],
+            [1.0, 0.0, 0.0],
+            [1.0, 1.0, 0.0],
+            [0.0001, -0.0001, 0.0],
+            [1.0, 1.0001, 0.0],
+            [0.0, 1.0, 0.0],
+        ];
+        let indices = [0, 1, 2, 3, 4, 5];
+        let grid_key = |p: &[f32; 3]| p.map(|c| (c * 100.0).round() as i32);
+
+        let (unique, remap) = generate_vertex_remap_by(&positions, Some(&indices), grid_key);
+        assert_eq!(unique, 4);
+        assert_eq!(remap, [0, 1, 2, 0, 2, 3]);
+        let welded = remap_index_buffer(Some(&indices), unique, &remap);
+        assert_eq!(welded, [0, 1, 2, 0, 2, 3]);
+        let vertices = remap_vertex_buffer(&positions, unique, &remap);
+        for (position, &new_vertex) in positions.iter().zip(&remap) {
+            assert_eq!(grid_key(&vertices[new_vertex as usize]), grid_key(position));
+        }
+
+        // exact keys match the binary remap, including unreferenced vertices
+        let (unique, remap) =
+            generate_vertex_remap_by(&positions, Some(&indices[..3]), |p| p.map(f32::to_bits));
+        assert_eq!(
+            (unique, remap),
+            generate_vertex_remap(&positions, Some(&indices[..3]))
+        );
+        assert_eq!(
+            generate_vertex_remap_by(&positions, None, grid_key),
+            (4, vec![0, 1, 2, 0, 2, 3])
+        );
+    }
+
+    #[test]
+    fn test_remap_into() {
+        let vertices: [[f32; 2]; 5] = [[0.0, 0.0], [1.0, 0.0], [0.0, 0.0], [1.0, 1.0], [9.0, 9.0]];
+        let indices = [0, 1, 3, 2, 1, 3];
+        let (unique, remap) = generate_vertex_remap(&vertices, Some(&indices));
+        assert_eq!(unique, 3);
+
+        let mut index_destination = [0u32; 6];
+        remap_index_buffer_into(&mut index_destination, Some(&indices), &remap).unwrap();
+        assert_eq!(index_destination, [0, 1, 2, 0, 1, 2]);
+        assert_eq!(
+            remap_index_buffer(Some(&indices), unique, &remap),
+            index_destination
+        );
+
+        let mut in_place = indices;
+        remap_index_buffer_in_place(&mut in_place, &remap).unwrap();
+        assert_eq!(in_place, index_destination);
+
+        let mut vertex_destination = [[0.0f32; 2]; 3];
+        remap_vertex_buffer_into(&mut vertex_destination, &vertices, &remap).unwrap();
+        assert_eq!(vertex_destination, [[0.0, 0.0], [1.0, 0.0], [1.0, 1.0]]);
+        assert_eq!(
+            remap_vertex_buffer(&vertices, unique, &remap),
+            vertex_destination
+        );
+
+        // wrong destination sizes and out of range references
+        assert!(remap_index_buffer_into(&mut [0; 5], Some(&indices), &remap).is_err());
+        assert!(remap_index_buffer_into(&mut [0; 6], None, &remap).is_err());
+        assert!(remap_index_buffer_in_place(&mut [0, 1, 5], &remap).is_err());
+        assert!(remap_vertex_buffer_into(&mut [[0.0; 2]; 2], &vertices, &remap).is_err());
+        assert!(remap_vertex_buffer_into(&mut vertex_destination, &vertices[..4], &remap).is_err());
+
+        // non-Copy vertices are cloned rather than copied bitwise
+        let names: Vec<String> = ["a", "b", "a", "c", "unused"].map(String::from).to_vec();
+        let mut named = vec![String::new(); unique];
+        remap_vertex_buffer_into(&mut named, &names, &remap).unwrap();
+        assert_eq!(named, ["a", "b", "c"]);
+    }
+
+    #[test]
+    fn test_remove_degenerate_triangles() {
+        let positions: [[f32; 3]; 6] = [
+            [0.0, 0.0, 0.0],
+            [10.0, 0.0, 0.0],
+            [0.0, 10.0, 0.0],
+            [20.0, 0.0, 0.0],
+            [0.0, 1e-3, 0.0],
+            [10.0, 10.0, 0.0],
+        ];
+        let adapter = VertexDataAdapter::new(crate::typed_to_bytes(&positions), 12, 0).unwrap();
+        let indices = [
+            0, 1, 2, // valid
+            1, 1, 2, // repeated index
+            0, 1, 3, // collinear
+            0, 3, 4, // sliver
+            1, 5, 2, // valid
+        ];
+
+        let (result, removed) = remove_degenerate_triangles(&indices, None, 0.0);
+        assert_eq!(removed, 1);
+        assert_eq!(result, [0, 1, 2, 0, 1, 3, 0, 3, 4, 1, 5, 2]);
+
+        let (result, removed) = remove_degenerate_triangles(&indices, Some(&adapter), 0.0);
+        assert_eq!(removed, 2);
+        assert_eq!(result, [0, 1, 2, 0, 3, 4, 1, 5, 2]);
+
+        // the sliver has an area of 0.01, the mesh extent is 20
+        let (result, removed) = remove_degenerate_triangles(&indices, Some(&adapter), 1e-4);
+        assert_eq!(removed, 3);
+        assert_eq!(result, [0, 1, 2, 1, 5, 2]);
+        let (_, removed) = remove_degenerate_triangles(&indices, Some(&adapter), 1e-6);
+        assert_eq!(removed, 2);
+    }
+
+    #[test]
+    fn test_index_mesh() {
+        let triangle = [[0.0f32, 0.0, 0.0], [1.0, 0.0, 0.0], [0.0, 1.0, 0.0]];
+        let soup: Vec<[f32; 3]> = triangle.iter().chain(&triangle).copied().collect();
+
+        let (indices, vertices) = index_mesh(&soup);
+        assert_eq!(indices, [0, 1, 2, 0, 1, 2]);
+        assert_eq!(vertices, triangle);
+
+        let (indices, vertices) = index_mesh::<[f32; 3]>(&[]);
+        assert!(indices.is_empty() && vertices.is_empty());
+    }
+
+    #[test]
+    fn test_remove_duplicate_triangles() {
+        let indices = [
+            0, 1, 2, // original
+            1, 2, 0, // rotated
+            2, 1, 0, // mirrored
+            3, 4, 5, // other
+            0, 2, 1, // rotated mirrored
+            5, 3, 4, // rotated other
+        ];
+
+        let (result, removed) = remove_duplicate_triangles(&indices, DuplicateMode::SameWinding);
+        assert_eq!(removed, 3);
+        assert_eq!(result, [0, 1, 2, 2, 1, 0, 3, 4, 5]);
+
+        let (result, removed) = remove_duplicate_triangles(&indices, DuplicateMode::AnyWinding);
+        assert_eq!(removed, 4);
+        assert_eq!(result, [0, 1, 2, 3, 4, 5]);
+    }
+
+    #[test]
+    fn test_weld_vertices() {
+        #[derive(Clone, Copy)]
+        #[repr(C)]
+        struct Vertex {
+            position: [f32; 3],
+            material: u32,
+        }
+
+        // two quads that share an edge, with the shared positions differing by an ulp
+        let nudge = |v: f32| f32::from_bits(v.to_bits() + 1);
+        let positions = [
+            [0.25, 0.25, 0.0],
+            [1.25, 0.25, 0.0],
+            [1.25, 1.25, 0.0],
+            [0.25, 1.25, 0.0],
+            [nudge(1.25), 0.25, 0.0],
+            [2.25, 0.25, 0.0],
+            [2.25, 1.25, 0.0],
+            [nudge(1.25), 1.25, 0.0],
+        ];
+        let vertices: Vec<Vertex> = positions
+            .iter()
+            .enumerate()
+            .map(|(i, &position)| Vertex {
+                position,
+                material: i as u32 / 4,
+            })
+            .collect();
+        let adapter = VertexDataAdapter::new(
+            crate::typed_to_bytes(&vertices),
+            mem::size_of::<Vertex>(),
+            0,
+        )
+        .unwrap();
+
+        let (exact_count, exact_remap) = generate_vertex_remap(&positions, None);
+        assert_eq!(exact_count, 8);
+        assert_eq!(weld_vertices(&adapter, 0.0), (exact_count, exact_remap));
+
+        let (count, remap) = weld_vertices(&adapter, 0.1);
+        assert_eq!(count, 6);
+        assert_eq!(remap[4], remap[1]);
+        assert_eq!(remap[7], remap[2]);
+        assert_eq!(remap[..4], [0, 1, 2, 3]);
+        assert_eq!(remap[5..7], [4, 5]);
+    }
+}
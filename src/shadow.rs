@@ -1,4 +1,5 @@
-use crate::{ffi, DecodePosition, VertexDataAdapter, VertexStream};
+use crate::{ffi, DecodePosition, Result, VertexDataAdapter, VertexStream};
+use alloc::{vec, vec::Vec};
 
 /// Generate index buffer that can be used for more efficient rendering when only a subset of the vertex
 /// attributes is necessary. All vertices that are binary equivalent (wrt first `vertex_size` bytes) map to
@@ -6,7 +7,41 @@ use crate::{ffi, DecodePosition, VertexDataAdapter, VertexStream};
 ///
 /// This makes it possible to use the index buffer for Z pre-pass or shadowmap rendering, while using
 /// the original index buffer for regular rendering.
+///
+/// Vertices are compared by their 12 position bytes; see `generate_shadow_indices_with_size`.
+///
+/// # Panics
+///
+/// Panics if the position doesn't fit into the vertex stride at the adapter's position offset.
 pub fn generate_shadow_indices(indices: &[u32], vertices: &VertexDataAdapter<'_>) -> Vec<u32> {
+    generate_shadow_indices_with_size(indices, vertices, core::mem::size_of::<f32>() * 3)
+}
+
+/// Like `generate_shadow_indices`, but compares the `compare_size` bytes starting at the adapter's
+/// position offset within each vertex, e.g. to include an attribute that follows the position, or
+/// to compare a position that isn't stored as three floats.
+///
+/// # Panics
+///
+/// Panics if `compare_size` is not in `1..=256`, or if `position_offset + compare_size` is larger
+/// than the vertex stride.
+pub fn generate_shadow_indices_with_size(
+    indices: &[u32],
+    vertices: &VertexDataAdapter<'_>,
+    compare_size: usize,
+) -> Vec<u32> {
+    assert!(
+        (1..=256).contains(&compare_size),
+        "compare_size ({}) must be in 1..=256",
+        compare_size
+    );
+    assert!(
+        vertices.position_offset + compare_size <= vertices.vertex_stride,
+        "position_offset ({}) + compare_size ({}) must not exceed vertex_stride ({})",
+        vertices.position_offset,
+        compare_size,
+        vertices.vertex_stride
+    );
     let vertex_data = vertices.reader.get_ref();
     let vertex_data = vertex_data.as_ptr().cast::<u8>();
     let positions = unsafe { vertex_data.add(vertices.position_offset) };
@@ -18,7 +53,7 @@ pub fn generate_shadow_indices(indices: &[u32], vertices: &VertexDataAdapter<'_>
             indices.len(),
             positions.cast(),
             vertices.vertex_count,
-            std::mem::size_of::<f32>() * 3,
+            compare_size,
             vertices.vertex_stride,
         );
     }
@@ -31,6 +66,10 @@ pub fn generate_shadow_indices(indices: &[u32], vertices: &VertexDataAdapter<'_>
 ///
 /// This makes it possible to use the index buffer for Z pre-pass or shadowmap rendering, while using
 /// the original index buffer for regular rendering.
+///
+/// # Panics
+///
+/// Panics if an index is not less than the number of `vertices`.
 pub fn generate_shadow_indices_decoder<T: DecodePosition>(
     indices: &[u32],
     vertices: &[T],
@@ -39,6 +78,9 @@ pub fn generate_shadow_indices_decoder<T: DecodePosition>(
         .iter()
         .map(|vertex| vertex.decode_position())
         .collect::<Vec<[f32; 3]>>();
+    // one position per vertex; the C function reads every indexed position from this buffer
+    let vertex_count = vertices.len();
+    crate::check_indices_in_range(indices, vertex_count).expect("invalid index");
     let positions = vertices.as_ptr().cast();
     let mut shadow_indices: Vec<u32> = vec![0; indices.len()];
     unsafe {
@@ -47,9 +89,9 @@ pub fn generate_shadow_indices_decoder<T: DecodePosition>(
             indices.as_ptr(),
             indices.len(),
             positions,
-            vertices.len() * 3,
-            std::mem::size_of::<f32>() * 3,
-            std::mem::size_of::<f32>() * 3,
+            vertex_count,
+            core::mem::size_of::<f32>() * 3,
+            core::mem::size_of::<f32>() * 3,
         );
     }
     shadow_indices
@@ -61,19 +103,18 @@ pub fn generate_shadow_indices_decoder<T: DecodePosition>(
 ///
 /// This makes it possible to use the index buffer for Z pre-pass or shadowmap rendering, while using
 /// the original index buffer for regular rendering.
+///
+/// Returns an error if there are no streams or more than 16, if a stream size is not in
+/// `1..=256` or larger than its stride, if a stream of 4 or more bytes per vertex is not 4 byte
+/// aligned, if a stream created from a slice doesn't have `vertex_count` vertices, or if an
+/// index is not less than `vertex_count`.
 pub fn generate_shadow_indices_multi(
     indices: &[u32],
     vertex_count: usize,
     streams: &[VertexStream<'_>],
-) -> Vec<u32> {
-    let streams: Vec<ffi::meshopt_Stream> = streams
-        .iter()
-        .map(|stream| ffi::meshopt_Stream {
-            data: stream.data.cast(),
-            size: stream.size,
-            stride: stream.stride,
-        })
-        .collect();
+) -> Result<Vec<u32>> {
+    let streams = crate::ffi_streams(vertex_count, streams)?;
+    crate::check_indices_in_range(indices, vertex_count)?;
     let mut shadow_indices: Vec<u32> = vec![0; indices.len()];
     unsafe {
         ffi::meshopt_generateShadowIndexBufferMulti(
@@ -85,5 +126,107 @@ pub fn generate_shadow_indices_multi(
             streams.len(),
         );
     }
-    shadow_indices
+    Ok(shadow_indices)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_generate_shadow_indices_multi() {
+        // vertices 0 and 1 share a position but are skinned to different bones,
+        // vertices 0 and 2 match in both
+        let positions: [[f32; 3]; 4] = [[0.0; 3], [0.0; 3], [0.0; 3], [1.0, 0.0, 0.0]];
+        let bones: [[u8; 4]; 4] = [[0, 1, 0, 0], [2, 3, 0, 0], [0, 1, 0, 0], [0, 0, 0, 0]];
+        let indices = [0, 1, 3, 2, 1, 3];
+        let streams = unsafe {
+            [
+                VertexStream::from_slice(&positions),
+                VertexStream::from_slice(&bones),
+            ]
+        };
+
+        let shadow = generate_shadow_indices_multi(&indices, 4, &streams).unwrap();
+        assert_eq!(shadow, [0, 1, 3, 0, 1, 3]);
+
+        // by position alone, all three coincident vertices merge
+        let position_only = generate_shadow_indices_multi(&indices, 4, &streams[..1]).unwrap();
+        assert_eq!(position_only, [0, 0, 3, 0, 0, 3]);
+        assert_eq!(
+            generate_shadow_indices_decoder(&indices, &positions),
+            position_only
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_generate_shadow_indices_decoder_index_past_vertices() {
+        // index 3 is within three times the vertex count that used to be passed to C, which
+        // made meshoptimizer read past the decoded positions
+        let positions: [[f32; 3]; 3] = [[0.0; 3], [1.0, 0.0, 0.0], [0.0, 1.0, 0.0]];
+        generate_shadow_indices_decoder(&[0, 1, 3], &positions);
+    }
+
+    #[test]
+    fn test_generate_shadow_indices_with_offset() {
+        // 32-byte vertices with the position at offset 8, after a per-vertex color and id
+        #[derive(Clone, Copy)]
+        #[repr(C)]
+        struct Vertex {
+            color: [u8; 4],
+            id: u32,
+            position: [f32; 3],
+            uv: [f32; 3],
+        }
+        let vertex = |id: u32, position: [f32; 3], uv: f32| Vertex {
+            color: [id as u8; 4],
+            id,
+            position,
+            uv: [uv; 3],
+        };
+        let vertices = [
+            vertex(0, [0.0; 3], 0.0),
+            vertex(1, [1.0, 0.0, 0.0], 0.0),
+            vertex(2, [0.0; 3], 1.0),
+            vertex(3, [1.0, 0.0, 0.0], 0.0),
+        ];
+        assert_eq!(core::mem::size_of::<Vertex>(), 32);
+        let adapter = VertexDataAdapter::new(crate::typed_to_bytes(&vertices), 32, 8).unwrap();
+        let indices = [0, 1, 2, 2, 3, 0];
+
+        // by position, 2 merges into 0 and 3 into 1 even though the other attributes differ
+        assert_eq!(
+            generate_shadow_indices(&indices, &adapter),
+            [0, 1, 0, 0, 1, 0]
+        );
+        // including the uv, 2 stays separate but 3 still merges into 1
+        assert_eq!(
+            generate_shadow_indices_with_size(&indices, &adapter, 24),
+            [0, 1, 2, 2, 1, 0]
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_generate_shadow_indices_with_size_past_stride() {
+        let vertices = [[0.0f32; 4]; 3];
+        let adapter = VertexDataAdapter::new(crate::typed_to_bytes(&vertices), 16, 4).unwrap();
+        generate_shadow_indices_with_size(&[0, 1, 2], &adapter, 16);
+    }
+
+    #[test]
+    fn test_generate_shadow_indices_multi_mismatched_streams() {
+        let positions = [[0.0f32; 3]; 4];
+        let bones = [[0u8; 4]; 2];
+        let streams = unsafe {
+            [
+                VertexStream::from_slice(&positions),
+                VertexStream::from_slice(&bones),
+            ]
+        };
+        assert!(generate_shadow_indices_multi(&[0, 1, 2], 4, &streams).is_err());
+        assert!(generate_shadow_indices_multi(&[0, 1, 2], 4, &[]).is_err());
+        assert!(generate_shadow_indices_multi(&[0, 1, 4], 4, &streams[..1]).is_err());
+    }
 }
@@ -1,6 +1,9 @@
+#[cfg(feature = "std")]
+use crate::Result;
 use crate::{ffi, DecodePosition, VertexDataAdapter};
+use alloc::{collections::BTreeMap, vec, vec::Vec};
 use bitflags::bitflags;
-use std::mem;
+use core::mem;
 
 bitflags! {
     #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
@@ -49,13 +52,233 @@ pub fn simplify(
             target_count,
             target_error,
             options.bits(),
-            result_error.map_or_else(std::ptr::null_mut, |v| v as *mut _),
+            result_error.map_or_else(core::ptr::null_mut, |v| v as *mut _),
         )
     };
     result.resize(index_count, 0u32);
     result
 }
 
+/// Like `simplify`, but takes the target as a number of triangles rather than indices, and
+/// returns the simplified index buffer together with the resulting error.
+///
+/// # Panics
+///
+/// Panics if `target_triangles * 3` exceeds the number of indices.
+pub fn simplify_to_triangles(
+    indices: &[u32],
+    vertices: &VertexDataAdapter<'_>,
+    target_triangles: usize,
+    target_error: f32,
+    options: SimplifyOptions,
+) -> (Vec<u32>, f32) {
+    let target_count = target_triangles
+        .checked_mul(3)
+        .filter(|&count| count <= indices.len())
+        .unwrap_or_else(|| {
+            panic!(
+                "target triangle count ({}) must not exceed the triangle count ({})",
+                target_triangles,
+                indices.len() / 3
+            )
+        });
+    let mut error = 0f32;
+    let indices = simplify(
+        indices,
+        vertices,
+        target_count,
+        target_error,
+        options,
+        Some(&mut error),
+    );
+    (indices, error)
+}
+
+/// The result of `simplify_detailed`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SimplifyResult {
+    /// The simplified index buffer, referencing vertices from the original vertex buffer.
+    pub indices: Vec<u32>,
+    /// The resulting error, relative to mesh extents unless `SimplifyOptions::ErrorAbsolute` is used.
+    pub error: f32,
+    /// Number of indices in `indices`.
+    pub index_count: usize,
+    /// Whether the simplifier reached `target_count`; if false, it stopped early because further
+    /// simplification would have exceeded `target_error` (or the mesh topology didn't allow it).
+    pub reached_target: bool,
+}
+
+/// Like `simplify`, but returns the index buffer together with the resulting error and whether
+/// the target index count was reached, to tell "hit the target" apart from "couldn't simplify further".
+pub fn simplify_detailed(
+    indices: &[u32],
+    vertices: &VertexDataAdapter<'_>,
+    target_count: usize,
+    target_error: f32,
+    options: SimplifyOptions,
+) -> SimplifyResult {
+    let mut error = 0f32;
+    let indices = simplify(
+        indices,
+        vertices,
+        target_count,
+        target_error,
+        options,
+        Some(&mut error),
+    );
+    SimplifyResult {
+        index_count: indices.len(),
+        reached_target: indices.len() <= target_count,
+        indices,
+        error,
+    }
+}
+
+/// Like `simplify`, but also returns an error map with one value per vertex of `vertices`,
+/// for visualizing where the simplified mesh deviates from the original.
+///
+/// Vertices that were referenced by `indices` but not by the simplified index buffer get
+/// their distance to the closest simplified triangle (in the units of the positions, or
+/// infinity if no triangles are left); all other vertices get 0.0. The distances are
+/// computed by brute force over all simplified triangles, so this is meant for debugging
+/// rather than for use at runtime.
+///
+/// Returns an error if a vertex position lies outside of the vertex data.
+#[cfg(feature = "std")]
+pub fn simplify_with_error_map(
+    indices: &[u32],
+    vertices: &VertexDataAdapter<'_>,
+    target_count: usize,
+    target_error: f32,
+    options: SimplifyOptions,
+    result_error: Option<&mut f32>,
+) -> Result<(Vec<u32>, Vec<f32>)> {
+    let simplified = simplify(
+        indices,
+        vertices,
+        target_count,
+        target_error,
+        options,
+        result_error,
+    );
+
+    let mut retained = vec![false; vertices.vertex_count];
+    for &index in &simplified {
+        retained[index as usize] = true;
+    }
+    let triangles = simplified
+        .chunks_exact(3)
+        .map(|t| {
+            Ok([
+                vertices.xyz_f32_at(t[0] as usize)?,
+                vertices.xyz_f32_at(t[1] as usize)?,
+                vertices.xyz_f32_at(t[2] as usize)?,
+            ])
+        })
+        .collect::<Result<Vec<[[f32; 3]; 3]>>>()?;
+
+    let mut error_map = vec![0f32; vertices.vertex_count];
+    for &index in indices {
+        let index = index as usize;
+        if retained[index] || error_map[index] != 0.0 {
+            continue;
+        }
+        let position = vertices.xyz_f32_at(index)?;
+        error_map[index] = triangles
+            .iter()
+            .map(|&[a, b, c]| point_triangle_distance_squared(position, a, b, c))
+            .fold(f32::INFINITY, f32::min)
+            .sqrt();
+    }
+    Ok((simplified, error_map))
+}
+
+#[cfg(feature = "std")]
+fn point_triangle_distance_squared(p: [f32; 3], a: [f32; 3], b: [f32; 3], c: [f32; 3]) -> f32 {
+    fn sub(a: [f32; 3], b: [f32; 3]) -> [f32; 3] {
+        [a[0] - b[0], a[1] - b[1], a[2] - b[2]]
+    }
+    fn dot(a: [f32; 3], b: [f32; 3]) -> f32 {
+        a[0] * b[0] + a[1] * b[1] + a[2] * b[2]
+    }
+    fn cross(a: [f32; 3], b: [f32; 3]) -> [f32; 3] {
+        [
+            a[1] * b[2] - a[2] * b[1],
+            a[2] * b[0] - a[0] * b[2],
+            a[0] * b[1] - a[1] * b[0],
+        ]
+    }
+    fn segment_distance_squared(p: [f32; 3], a: [f32; 3], b: [f32; 3]) -> f32 {
+        let ab = sub(b, a);
+        let length_squared = dot(ab, ab);
+        let t = if length_squared > 0.0 {
+            (dot(sub(p, a), ab) / length_squared).clamp(0.0, 1.0)
+        } else {
+            0.0
+        };
+        let d = sub(p, [a[0] + ab[0] * t, a[1] + ab[1] * t, a[2] + ab[2] * t]);
+        dot(d, d)
+    }
+
+    // if p projects onto the inside of the triangle, the distance is the distance to its plane
+    let normal = cross(sub(b, a), sub(c, a));
+    let normal_squared = dot(normal, normal);
+    if normal_squared > 0.0
+        && dot(cross(sub(b, a), sub(p, a)), normal) >= 0.0
+        && dot(cross(sub(c, b), sub(p, b)), normal) >= 0.0
+        && dot(cross(sub(a, c), sub(p, c)), normal) >= 0.0
+    {
+        let d = dot(sub(p, a), normal);
+        return d * d / normal_squared;
+    }
+
+    segment_distance_squared(p, a, b)
+        .min(segment_distance_squared(p, b, c))
+        .min(segment_distance_squared(p, c, a))
+}
+
+/// A single level of detail, as returned by `generate_lod_chain`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Lod {
+    /// The index buffer of this level, referencing vertices from the original vertex buffer.
+    pub indices: Vec<u32>,
+    /// The simplification error of this level, relative to mesh extents.
+    pub error: f32,
+}
+
+/// Generates a chain of levels of detail, one for each of the given `ratios` of the original
+/// index count (e.g. `[0.5, 0.25, 0.125]`).
+///
+/// Every level is simplified from the original mesh rather than from the previous level, so
+/// errors don't accumulate. The chain stops early at the first level that can't reach its
+/// target index count within `max_error`; that level is still included.
+pub fn generate_lod_chain(
+    indices: &[u32],
+    vertices: &VertexDataAdapter<'_>,
+    ratios: &[f32],
+    max_error: f32,
+) -> Vec<Lod> {
+    let mut lods = Vec::with_capacity(ratios.len());
+    for &ratio in ratios {
+        let target_count = (indices.len() as f32 * ratio) as usize / 3 * 3;
+        let result = simplify_detailed(
+            indices,
+            vertices,
+            target_count,
+            max_error,
+            SimplifyOptions::None,
+        );
+        lods.push(Lod {
+            indices: result.indices,
+            error: result.error,
+        });
+        if !result.reached_target {
+            break;
+        }
+    }
+    lods
+}
+
 /// Reduces the number of triangles in the mesh, attempting to preserve mesh
 /// appearance as much as possible.
 ///
@@ -87,13 +310,76 @@ pub fn simplify_decoder<T: DecodePosition>(
             target_count,
             target_error,
             options.bits(),
-            result_error.map_or_else(std::ptr::null_mut, |v| v as *mut _),
+            result_error.map_or_else(core::ptr::null_mut, |v| v as *mut _),
         )
     };
     result.resize(index_count, 0u32);
     result
 }
 
+/// Computes vertex locks for `simplify_with_locks` that only lock attribute seams, i.e.
+/// vertices that share their position with another vertex referenced by `indices` but have
+/// different attributes (such as the two sides of a UV seam).
+///
+/// Unlike `SimplifyOptions::LockBorder`, this leaves the rest of the mesh border free to
+/// collapse. `attributes` holds the attributes of each vertex with `attribute_stride` bytes
+/// between vertices; positions and attributes are compared bitwise.
+///
+/// # Panics
+///
+/// Panics if `attribute_stride` is not a non-zero multiple of 4, if `attributes` doesn't
+/// cover every vertex of `vertices`, or if any index is out of range of the vertex count.
+pub fn compute_seam_locks(
+    indices: &[u32],
+    vertices: &VertexDataAdapter<'_>,
+    attributes: &[f32],
+    attribute_stride: usize,
+) -> Vec<bool> {
+    assert!(
+        attribute_stride > 0 && attribute_stride.is_multiple_of(4),
+        "attribute stride ({}) must be a non-zero multiple of 4",
+        attribute_stride
+    );
+    let attribute_count = attribute_stride / 4;
+    assert!(
+        attributes.len() >= vertices.vertex_count * attribute_count,
+        "attributes must cover all {} vertices",
+        vertices.vertex_count
+    );
+    let attributes_of = |vertex: usize| {
+        attributes[vertex * attribute_count..(vertex + 1) * attribute_count]
+            .iter()
+            .map(|value| value.to_bits())
+    };
+
+    // the first vertex at each position, and whether the position is on a seam
+    let mut seams: BTreeMap<[u32; 3], (usize, bool)> = BTreeMap::new();
+    let mut positions: Vec<Option<[u32; 3]>> = vec![None; vertices.vertex_count];
+    for &index in indices {
+        let vertex = index as usize;
+        assert!(
+            vertex < vertices.vertex_count,
+            "indices must be less than the vertex count"
+        );
+        if positions[vertex].is_some() {
+            continue;
+        }
+        let position = vertices
+            .xyz_f32_at(vertex)
+            .expect("position must be within the vertex data")
+            .map(f32::to_bits);
+        positions[vertex] = Some(position);
+        let (first, seam) = seams.entry(position).or_insert((vertex, false));
+        if !attributes_of(*first).eq(attributes_of(vertex)) {
+            *seam = true;
+        }
+    }
+    positions
+        .iter()
+        .map(|position| position.is_some_and(|position| seams[&position].1))
+        .collect()
+}
+
 /// Reduces the number of triangles in the mesh, attempting to preserve mesh
 /// appearance as much as possible, while respecting the given vertex locks
 ///
@@ -122,15 +408,15 @@ pub fn simplify_with_locks(
             positions.cast::<f32>(),
             vertices.vertex_count,
             vertices.vertex_stride,
-            std::ptr::null(),
+            core::ptr::null(),
             0,
-            std::ptr::null(),
+            core::ptr::null(),
             0,
             vertex_lock.as_ptr().cast(),
             target_count,
             target_error,
             options.bits(),
-            result_error.map_or_else(std::ptr::null_mut, |v| v as *mut _),
+            result_error.map_or_else(core::ptr::null_mut, |v| v as *mut _),
         )
     };
     result.resize(index_count, 0u32);
@@ -166,15 +452,15 @@ pub fn simplify_with_locks_decoder<T: DecodePosition>(
             positions.as_ptr().cast(),
             positions.len(),
             mem::size_of::<f32>() * 3,
-            std::ptr::null(),
+            core::ptr::null(),
             0,
-            std::ptr::null(),
+            core::ptr::null(),
             0,
             vertex_lock.as_ptr().cast(),
             target_count,
             target_error,
             options.bits(),
-            result_error.map_or_else(std::ptr::null_mut, |v| v as *mut _),
+            result_error.map_or_else(core::ptr::null_mut, |v| v as *mut _),
         )
     };
     result.resize(index_count, 0u32);
@@ -207,9 +493,10 @@ pub fn simplify_sloppy(
             positions.cast(),
             vertices.vertex_count,
             vertices.vertex_stride,
+            core::ptr::null(),
             target_count,
             target_error,
-            result_error.map_or_else(std::ptr::null_mut, |v| v as *mut _),
+            result_error.map_or_else(core::ptr::null_mut, |v| v as *mut _),
         )
     };
     result.resize(index_count, 0u32);
@@ -243,9 +530,76 @@ pub fn simplify_sloppy_decoder<T: DecodePosition>(
             positions.as_ptr().cast(),
             positions.len(),
             mem::size_of::<f32>() * 3,
+            core::ptr::null(),
             target_count,
             target_error,
-            result_error.map_or_else(std::ptr::null_mut, |v| v as *mut _),
+            result_error.map_or_else(core::ptr::null_mut, |v| v as *mut _),
+        )
+    };
+    result.resize(index_count, 0u32);
+    result
+}
+
+/// Reduces the number of triangles in the mesh by removing small isolated parts of the mesh,
+/// without otherwise simplifying it.
+///
+/// `target_error` represents the error relative to mesh extents that can be tolerated,
+/// e.g. 0.01 = 1% deformation; value range [0..1].
+///
+/// The resulting index buffer references vertices from the original vertex buffer.
+///
+/// If the original vertex data isn't required, creating a compact vertex buffer using `optimize_vertex_fetch`
+/// is recommended.
+pub fn simplify_prune(
+    indices: &[u32],
+    vertices: &VertexDataAdapter<'_>,
+    target_error: f32,
+) -> Vec<u32> {
+    let mut result: Vec<u32> = vec![0; indices.len()];
+    let index_count = unsafe {
+        ffi::meshopt_simplifyPrune(
+            result.as_mut_ptr(),
+            indices.as_ptr(),
+            indices.len(),
+            vertices.pos_ptr(),
+            vertices.vertex_count,
+            vertices.vertex_stride,
+            target_error,
+        )
+    };
+    result.resize(index_count, 0u32);
+    result
+}
+
+/// Reduces the number of triangles in the mesh by removing small isolated parts of the mesh,
+/// without otherwise simplifying it.
+///
+/// `target_error` represents the error relative to mesh extents that can be tolerated,
+/// e.g. 0.01 = 1% deformation; value range [0..1].
+///
+/// The resulting index buffer references vertices from the original vertex buffer.
+///
+/// If the original vertex data isn't required, creating a compact vertex buffer using `optimize_vertex_fetch`
+/// is recommended.
+pub fn simplify_prune_decoder<T: DecodePosition>(
+    indices: &[u32],
+    vertices: &[T],
+    target_error: f32,
+) -> Vec<u32> {
+    let positions = vertices
+        .iter()
+        .map(|vertex| vertex.decode_position())
+        .collect::<Vec<[f32; 3]>>();
+    let mut result: Vec<u32> = vec![0; indices.len()];
+    let index_count = unsafe {
+        ffi::meshopt_simplifyPrune(
+            result.as_mut_ptr(),
+            indices.as_ptr(),
+            indices.len(),
+            positions.as_ptr().cast(),
+            positions.len(),
+            mem::size_of::<f32>() * 3,
+            target_error,
         )
     };
     result.resize(index_count, 0u32);
@@ -284,3 +638,307 @@ pub fn simplify_scale_decoder<T: DecodePosition>(vertices: &[T]) -> f32 {
         )
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        test_utils::{grid_indices, grid_positions},
+        typed_to_bytes, Vertex,
+    };
+
+    #[test]
+    fn test_simplify_prune() {
+        let size = 16;
+        let mut positions = grid_positions(size);
+        let mut indices = grid_indices(size);
+        let grid_indices = indices.clone();
+
+        // a tiny triangle floating above the grid
+        let base = positions.len() as u32;
+        positions.extend_from_slice(&[[8.0, 8.0, 4.0], [8.01, 8.0, 4.0], [8.0, 8.01, 4.0]]);
+        indices.extend_from_slice(&[base, base + 1, base + 2]);
+
+        let adapter =
+            VertexDataAdapter::new(typed_to_bytes(&positions), mem::size_of::<[f32; 3]>(), 0)
+                .unwrap();
+        let pruned = simplify_prune(&indices, &adapter, 0.01);
+        assert_eq!(pruned, grid_indices);
+
+        assert_eq!(simplify_prune_decoder(&indices, &positions, 0.01), pruned);
+        assert_eq!(simplify_prune(&indices, &adapter, 0.0), indices);
+    }
+
+    #[test]
+    fn test_simplify_to_triangles() {
+        let (positions, indices) = bumpy_grid(16);
+        let adapter =
+            VertexDataAdapter::new(typed_to_bytes(&positions), mem::size_of::<[f32; 3]>(), 0)
+                .unwrap();
+
+        for target in [0, 10, 100, indices.len() / 3] {
+            let (simplified, error) =
+                simplify_to_triangles(&indices, &adapter, target, 1.0, SimplifyOptions::empty());
+            assert!(simplified.len() <= target * 3);
+            assert!((0.0..=1.0).contains(&error));
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_simplify_to_triangles_too_many() {
+        let (positions, indices) = bumpy_grid(4);
+        let adapter =
+            VertexDataAdapter::new(typed_to_bytes(&positions), mem::size_of::<[f32; 3]>(), 0)
+                .unwrap();
+        simplify_to_triangles(
+            &indices,
+            &adapter,
+            indices.len(),
+            1.0,
+            SimplifyOptions::empty(),
+        );
+    }
+
+    #[test]
+    fn test_compute_seam_locks() {
+        // a 4x4 quad grid with a UV seam along x = 2, where the column of vertices is
+        // duplicated with different texture coordinates for each half
+        let mut positions: Vec<[f32; 3]> = Vec::new();
+        let mut uvs: Vec<[f32; 2]> = Vec::new();
+        for half in 0..2 {
+            for y in 0..=4 {
+                for x in 0..=2 {
+                    positions.push([(x + half * 2) as f32, y as f32, 0.0]);
+                    uvs.push([x as f32 / 2.0 + half as f32 * 2.0, y as f32 / 4.0]);
+                }
+            }
+        }
+        let mut indices = Vec::new();
+        for half in 0..2 {
+            for y in 0..4 {
+                for x in 0..2 {
+                    let i = half * 15 + y * 3 + x;
+                    indices.extend_from_slice(&[i, i + 1, i + 3, i + 1, i + 4, i + 3]);
+                }
+            }
+        }
+        let adapter =
+            VertexDataAdapter::new(typed_to_bytes(&positions), mem::size_of::<[f32; 3]>(), 0)
+                .unwrap();
+        let flat_uvs: Vec<f32> = uvs.iter().flatten().copied().collect();
+
+        let locks = compute_seam_locks(&indices, &adapter, &flat_uvs, 8);
+        for (vertex, &locked) in locks.iter().enumerate() {
+            let x = positions[vertex][0];
+            assert_eq!(
+                locked,
+                x == 2.0,
+                "vertex {} at {:?}",
+                vertex,
+                positions[vertex]
+            );
+        }
+
+        // matching attributes along the shared column are not a seam
+        let continuous: Vec<f32> = positions.iter().map(|p| p[0]).collect();
+        let locks = compute_seam_locks(&indices, &adapter, &continuous, 4);
+        assert!(locks.iter().all(|&locked| !locked));
+
+        let simplified = simplify_with_locks(
+            &indices,
+            &adapter,
+            &compute_seam_locks(&indices, &adapter, &flat_uvs, 8),
+            0,
+            1e-2,
+            SimplifyOptions::empty(),
+            None,
+        );
+        assert!(simplified.len() < indices.len());
+        for vertex in [2, 17] {
+            assert!(simplified.contains(&vertex));
+        }
+    }
+
+    fn bumpy_grid(size: u32) -> (Vec<[f32; 3]>, Vec<u32>) {
+        let mut positions: Vec<[f32; 3]> = Vec::new();
+        for y in 0..=size {
+            for x in 0..=size {
+                let (fx, fy) = (x as f32, y as f32);
+                positions.push([fx, fy, (fx * 0.7).sin() * (fy * 0.5).cos() * 2.0]);
+            }
+        }
+        let indices = grid_indices(size);
+        (positions, indices)
+    }
+
+    #[test]
+    fn test_simplify_detailed() {
+        let (positions, indices) = bumpy_grid(32);
+        let adapter =
+            VertexDataAdapter::new(typed_to_bytes(&positions), mem::size_of::<[f32; 3]>(), 0)
+                .unwrap();
+        let target_count = indices.len() / 4;
+
+        // a generous error budget reaches the target
+        let result =
+            simplify_detailed(&indices, &adapter, target_count, 1.0, SimplifyOptions::None);
+        assert!(result.reached_target);
+        assert_eq!(result.index_count, result.indices.len());
+        assert!(result.index_count <= target_count);
+
+        // a tiny error budget stops before the target
+        let result = simplify_detailed(
+            &indices,
+            &adapter,
+            target_count,
+            1e-4,
+            SimplifyOptions::None,
+        );
+        assert!(!result.reached_target);
+        assert!(result.index_count > target_count);
+        assert!(result.error <= 1e-4);
+    }
+
+    #[test]
+    fn test_generate_lod_chain() {
+        let (positions, indices) = bumpy_grid(32);
+        let adapter =
+            VertexDataAdapter::new(typed_to_bytes(&positions), mem::size_of::<[f32; 3]>(), 0)
+                .unwrap();
+
+        let ratios = [0.5, 0.25, 0.125, 0.0625];
+        let lods = generate_lod_chain(&indices, &adapter, &ratios, 1.0);
+        assert_eq!(lods.len(), ratios.len());
+        for (lod, ratio) in lods.iter().zip(ratios) {
+            assert!(lod.indices.len() as f32 <= indices.len() as f32 * ratio);
+        }
+        for pair in lods.windows(2) {
+            assert!(pair[0].error <= pair[1].error);
+            assert!(pair[0].indices.len() > pair[1].indices.len());
+        }
+
+        // a small error budget stops the chain at the first level that can't be reached
+        let lods = generate_lod_chain(&indices, &adapter, &ratios, 1e-4);
+        assert_eq!(lods.len(), 1);
+        assert!(lods[0].indices.len() as f32 > indices.len() as f32 * ratios[0]);
+    }
+
+    #[test]
+    fn test_simplify_interleaved() {
+        let (positions, indices) = bumpy_grid(16);
+        let interleaved: Vec<Vertex> = positions
+            .iter()
+            .map(|&p| Vertex {
+                p,
+                n: [0.0, 0.0, 1.0],
+                t: [p[0] / 16.0, p[1] / 16.0],
+            })
+            .collect();
+
+        let interleaved_adapter =
+            VertexDataAdapter::new(typed_to_bytes(&interleaved), mem::size_of::<Vertex>(), 0)
+                .unwrap();
+        let positions_adapter =
+            VertexDataAdapter::new(typed_to_bytes(&positions), mem::size_of::<[f32; 3]>(), 0)
+                .unwrap();
+        let target_count = indices.len() / 4;
+
+        let from_interleaved = simplify(
+            &indices,
+            &interleaved_adapter,
+            target_count,
+            0.05,
+            SimplifyOptions::None,
+            None,
+        );
+        let from_positions = simplify(
+            &indices,
+            &positions_adapter,
+            target_count,
+            0.05,
+            SimplifyOptions::None,
+            None,
+        );
+        assert_eq!(from_interleaved, from_positions);
+        assert_eq!(
+            from_interleaved,
+            simplify_decoder(
+                &indices,
+                &positions,
+                target_count,
+                0.05,
+                SimplifyOptions::None,
+                None
+            )
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_simplify_with_error_map() {
+        let (positions, indices) = bumpy_grid(32);
+        // shift the bumps so no border is a straight line, along which vertices could be
+        // removed without any error
+        let positions: Vec<[f32; 3]> = positions
+            .iter()
+            .map(|&[x, y, _]| [x, y, (x * 0.7 + 0.4).sin() * (y * 0.5 + 0.3).cos() * 2.0])
+            .collect();
+        let adapter =
+            VertexDataAdapter::new(typed_to_bytes(&positions), mem::size_of::<[f32; 3]>(), 0)
+                .unwrap();
+        let target_count = indices.len() / 4;
+
+        let (simplified, error_map) = simplify_with_error_map(
+            &indices,
+            &adapter,
+            target_count,
+            1.0,
+            SimplifyOptions::None,
+            None,
+        )
+        .unwrap();
+        assert_eq!(
+            simplified,
+            simplify(
+                &indices,
+                &adapter,
+                target_count,
+                1.0,
+                SimplifyOptions::None,
+                None
+            )
+        );
+        assert_eq!(error_map.len(), positions.len());
+
+        let mut removed = 0;
+        for (vertex, &vertex_error) in error_map.iter().enumerate() {
+            if simplified.contains(&(vertex as u32)) {
+                assert_eq!(vertex_error, 0.0);
+            } else {
+                assert!(vertex_error > 0.0 && vertex_error.is_finite());
+                removed += 1;
+            }
+        }
+        assert!(removed > 0);
+
+        // a point on the triangle has no distance, points off it measure to the plane or edges
+        let (a, b, c) = ([0.0, 0.0, 0.0], [2.0, 0.0, 0.0], [0.0, 2.0, 0.0]);
+        assert_eq!(
+            point_triangle_distance_squared([0.5, 0.5, 0.0], a, b, c),
+            0.0
+        );
+        assert_eq!(
+            point_triangle_distance_squared([0.5, 0.5, 3.0], a, b, c),
+            9.0
+        );
+        assert_eq!(
+            point_triangle_distance_squared([-1.0, 0.0, 0.0], a, b, c),
+            1.0
+        );
+        assert_eq!(
+            point_triangle_distance_squared([2.0, 2.0, 0.0], a, b, c),
+            2.0
+        );
+    }
+}
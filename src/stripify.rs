@@ -1,41 +1,159 @@
 use crate::{ffi, Error, Result};
+use alloc::{format, vec, vec::Vec};
+use core::fmt;
+
+/// How `stripify` separates strips and how `unstripify` detects strip boundaries.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub enum StripRestart {
+    /// Strips are stitched together with degenerate triangles.
+    #[default]
+    None,
+    /// Strips are separated by a restart index, which should be 0xffff or 0xffffffff
+    /// depending on the index size used for rendering.
+    ///
+    /// meshoptimizer uses 0 to mean "no restart index", so `Index(0)` behaves like `None`
+    /// rather than treating vertex 0 as a restart.
+    Index(u32),
+}
+
+impl StripRestart {
+    fn restart_index(self) -> u32 {
+        match self {
+            StripRestart::None => 0,
+            StripRestart::Index(index) => index,
+        }
+    }
+}
+
+/// Returns the worst case number of strip indices `stripify` produces for `index_count`
+/// list indices: 3 indices per triangle plus 2 degenerate indices (or 1 restart index)
+/// between strips.
+///
+/// # Panics
+///
+/// Panics if `index_count` is not a multiple of 3.
+pub fn stripify_bound(index_count: usize) -> usize {
+    assert!(
+        index_count.is_multiple_of(3),
+        "index count must be a multiple of 3"
+    );
+    unsafe { ffi::meshopt_stripifyBound(index_count) }
+}
 
 /// Converts a previously vertex cache optimized triangle list to triangle
-/// strip, stitching strips using restart index.
+/// strip, stitching strips using restart index or degenerate triangles.
 ///
 /// For maximum efficiency the index buffer being converted has to be
-/// optimized for vertex cache first.
+/// optimized for vertex cache first. Use `StripRestart::None` for hardware without
+/// primitive restart support (e.g. OpenGL ES 2).
 ///
-/// The `restart_index` should be 0xffff or 0xffffffff depending on index size,
-/// or 0 to use degenerate triangles.
-pub fn stripify(indices: &[u32], vertex_count: usize, restart_index: u32) -> Result<Vec<u32>> {
-    let mut result: Vec<u32> = vec![0; indices.len() / 3 * 4];
+/// Returns an error if a restart index is a valid vertex index, i.e. less than
+/// `vertex_count`, as the strip couldn't be rendered correctly.
+pub fn stripify(indices: &[u32], vertex_count: usize, restart: StripRestart) -> Result<Vec<u32>> {
+    let mut result: Vec<u32> = vec![0; indices.len() / 3 * 5];
+    let index_count = stripify_into(&mut result, indices, vertex_count, restart)?;
+    result.truncate(index_count);
+    Ok(result)
+}
+
+/// The result of `stripify_detailed`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StripifyResult {
+    /// The strip indices, as returned by `stripify`.
+    pub indices: Vec<u32>,
+    /// Whether `indices` contains the restart index; always false for `StripRestart::None`.
+    pub uses_restart: bool,
+    /// Number of strips, as counted by `analyze_strips`.
+    pub strip_count: usize,
+}
+
+/// Like `stripify`, but also returns whether the strip contains restart indices and how many
+/// strips it consists of, e.g. to fall back to a triangle list for APIs without primitive
+/// restart.
+pub fn stripify_detailed(
+    indices: &[u32],
+    vertex_count: usize,
+    restart: StripRestart,
+) -> Result<StripifyResult> {
+    let indices = stripify(indices, vertex_count, restart)?;
+    let restart_index = restart.restart_index();
+    Ok(StripifyResult {
+        uses_restart: restart_index != 0 && indices.contains(&restart_index),
+        strip_count: analyze_strips(&indices, restart).strip_count,
+        indices,
+    })
+}
+
+/// Like `stripify`, but writes the strip into `destination` and returns the number of
+/// strip indices written, so that no memory is allocated.
+///
+/// Returns an error if `destination` is shorter than `stripify_bound(indices.len())`, if
+/// the index count is not a multiple of 3, if any index is out of range of `vertex_count`,
+/// or if a restart index is a valid vertex index.
+pub fn stripify_into(
+    destination: &mut [u32],
+    indices: &[u32],
+    vertex_count: usize,
+    restart: StripRestart,
+) -> Result<usize> {
+    let restart_index = restart.restart_index();
+    if restart_index != 0 && (restart_index as usize) < vertex_count {
+        return Err(Error::memory_dynamic(format!(
+            "restart index ({}) must not be a valid vertex index (vertex count {})",
+            restart_index, vertex_count
+        )));
+    }
+    if !indices.len().is_multiple_of(3) {
+        return Err(Error::memory("index count must be a multiple of 3"));
+    }
+    if let Some(index) = indices
+        .iter()
+        .find(|&&index| index as usize >= vertex_count)
+    {
+        return Err(Error::memory_dynamic(format!(
+            "index ({}) must be less than the vertex count ({})",
+            index, vertex_count
+        )));
+    }
+    let bound = stripify_bound(indices.len());
+    if destination.len() < bound {
+        return Err(Error::memory_dynamic(format!(
+            "destination length ({}) must be at least the stripify bound ({})",
+            destination.len(),
+            bound
+        )));
+    }
+    if indices.is_empty() {
+        return Ok(0);
+    }
     let index_count = unsafe {
         ffi::meshopt_stripify(
-            result.as_mut_ptr().cast(),
-            indices.as_ptr().cast(),
+            destination.as_mut_ptr(),
+            indices.as_ptr(),
             indices.len(),
             vertex_count,
             restart_index,
         )
     };
-    if index_count <= result.len() {
-        result.resize(index_count, 0u32);
-        Ok(result)
-    } else {
-        Err(Error::memory("index count is larger than result"))
-    }
+    Ok(index_count)
 }
 
-/// Converts a triangle strip to a triangle list
-pub fn unstripify(indices: &[u32], restart_index: u32) -> Result<Vec<u32>> {
-    let mut result: Vec<u32> = vec![0; (indices.len() - 2) * 3];
+/// Converts a triangle strip to a triangle list, preserving the winding of each triangle.
+///
+/// Degenerate triangles (such as the ones `stripify` uses for stitching) are skipped, so
+/// `StripRestart::None` also handles strips that are stitched with a restart index as long
+/// as it isn't a valid vertex index. Strips with fewer than 3 indices result in an empty list.
+pub fn unstripify(indices: &[u32], restart: StripRestart) -> Result<Vec<u32>> {
+    if indices.len() < 3 {
+        return Ok(Vec::new());
+    }
+    let mut result: Vec<u32> = vec![0; unsafe { ffi::meshopt_unstripifyBound(indices.len()) }];
     let index_count = unsafe {
         ffi::meshopt_unstripify(
             result.as_mut_ptr().cast(),
             indices.as_ptr().cast(),
             indices.len(),
-            restart_index,
+            restart.restart_index(),
         )
     };
     if index_count <= result.len() {
@@ -45,3 +163,236 @@ pub fn unstripify(indices: &[u32], restart_index: u32) -> Result<Vec<u32>> {
         Err(Error::memory("index count is larger than result"))
     }
 }
+
+/// Statistics of a triangle strip, as returned by `analyze_strips`.
+#[derive(Debug, Copy, Clone, PartialEq, Default)]
+pub struct StripStatistics {
+    /// Number of strips that contain at least one triangle.
+    pub strip_count: usize,
+    /// Number of non-degenerate triangles in all strips.
+    pub triangle_count: usize,
+    /// Number of triangles in the longest strip.
+    pub max_strip_length: usize,
+    /// Average number of triangles per strip.
+    pub average_strip_length: f32,
+    /// Number of strip indices per index of the equivalent triangle list (lower is better).
+    pub expansion: f32,
+}
+
+impl fmt::Display for StripStatistics {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} strips, {} triangles, {:.2} avg / {} max triangles per strip, {:.3}x list size",
+            self.strip_count,
+            self.triangle_count,
+            self.average_strip_length,
+            self.max_strip_length,
+            self.expansion
+        )
+    }
+}
+
+/// Returns statistics of a triangle strip, such as the output of `stripify`.
+///
+/// Strips end at a restart index (if `restart` is `StripRestart::Index`) and where an index
+/// is repeated, which is how `stripify` stitches strips with degenerate triangles; other
+/// degenerate triangles, such as the ones that swap the strip direction, don't end a strip.
+pub fn analyze_strips(strip_indices: &[u32], restart: StripRestart) -> StripStatistics {
+    let restart_index = restart.restart_index();
+
+    let mut stats = StripStatistics::default();
+    let segments = strip_indices
+        .split(|&index| restart_index != 0 && index == restart_index)
+        .flat_map(|strip| {
+            let mut start = 0;
+            (1..=strip.len()).filter_map(move |i| {
+                if i == strip.len() || strip[i] == strip[i - 1] {
+                    let segment = &strip[start..i];
+                    start = i;
+                    Some(segment)
+                } else {
+                    None
+                }
+            })
+        });
+    for segment in segments {
+        let triangles = segment
+            .windows(3)
+            .filter(|t| t[0] != t[1] && t[1] != t[2] && t[2] != t[0])
+            .count();
+        if triangles > 0 {
+            stats.strip_count += 1;
+            stats.triangle_count += triangles;
+            stats.max_strip_length = stats.max_strip_length.max(triangles);
+        }
+    }
+    if stats.strip_count > 0 {
+        stats.average_strip_length = stats.triangle_count as f32 / stats.strip_count as f32;
+        stats.expansion = strip_indices.len() as f32 / (stats.triangle_count * 3) as f32;
+    }
+    stats
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{grid_indices, sorted_triangles};
+
+    #[test]
+    fn test_stripify_disjoint_triangles() {
+        // triangles that share no vertices produce one strip each, the worst case for the
+        // output length, which needs more than 4 indices per triangle
+        let indices: Vec<u32> = (0..64 * 3).collect();
+        let strip = stripify(&indices, indices.len(), StripRestart::None).unwrap();
+        assert!(strip.len() > indices.len() / 3 * 4);
+        assert_eq!(
+            unstripify(&strip, StripRestart::None).unwrap().len(),
+            indices.len()
+        );
+    }
+
+    #[test]
+    fn test_unstripify_short_strips() {
+        assert!(unstripify(&[], StripRestart::None).unwrap().is_empty());
+        assert!(unstripify(&[0, 1], StripRestart::None).unwrap().is_empty());
+        assert_eq!(
+            unstripify(&[0, 1, 2, 3], StripRestart::None).unwrap(),
+            [0, 1, 2, 2, 1, 3]
+        );
+    }
+
+    #[test]
+    fn test_stripify_into() {
+        let indices = grid_indices(6);
+        let bound = stripify_bound(indices.len());
+        assert_eq!(bound, indices.len() / 3 * 5);
+
+        let mut destination = vec![0; bound];
+        for restart in [StripRestart::None, StripRestart::Index(u32::MAX)] {
+            let count = stripify_into(&mut destination, &indices, 49, restart).unwrap();
+            assert_eq!(
+                &destination[..count],
+                stripify(&indices, 49, restart).unwrap()
+            );
+        }
+
+        let mut short = vec![0; bound - 1];
+        assert!(stripify_into(&mut short, &indices, 49, StripRestart::None).is_err());
+        assert!(stripify_into(&mut destination, &indices, 48, StripRestart::None).is_err());
+        assert!(stripify_into(&mut destination, &indices[..4], 49, StripRestart::None).is_err());
+        assert_eq!(
+            stripify_into(&mut [], &[], 0, StripRestart::None).unwrap(),
+            0
+        );
+    }
+
+    #[test]
+    fn test_analyze_strips() {
+        let restart = analyze_strips(
+            &[0, 1, 2, 3, u32::MAX, 4, 5, 6],
+            StripRestart::Index(u32::MAX),
+        );
+        assert_eq!(restart.strip_count, 2);
+        assert_eq!(restart.triangle_count, 3);
+        assert_eq!(restart.max_strip_length, 2);
+        assert_eq!(restart.average_strip_length, 1.5);
+        assert_eq!(restart.expansion, 8.0 / 9.0);
+
+        let stitched = analyze_strips(&[0, 1, 2, 3, 3, 4, 4, 5, 6], StripRestart::None);
+        assert_eq!(stitched.strip_count, 2);
+        assert_eq!(stitched.triangle_count, 3);
+        assert_eq!(stitched.expansion, 1.0);
+
+        // a swap inside of a strip is degenerate, but doesn't start a new strip
+        let swapped = analyze_strips(&[0, 1, 2, 1, 3], StripRestart::None);
+        assert_eq!(swapped.strip_count, 1);
+        assert_eq!(swapped.triangle_count, 2);
+
+        assert_eq!(
+            analyze_strips(&[], StripRestart::None),
+            StripStatistics::default()
+        );
+        assert_eq!(
+            restart.to_string(),
+            "2 strips, 3 triangles, 1.50 avg / 2 max triangles per strip, 0.889x list size"
+        );
+
+        let indices = grid_indices(6);
+        for mode in [StripRestart::None, StripRestart::Index(u32::MAX)] {
+            let strip = stripify(&indices, 49, mode).unwrap();
+            let stats = analyze_strips(&strip, mode);
+            assert_eq!(stats.triangle_count, indices.len() / 3);
+            assert!(stats.expansion < 1.0);
+        }
+    }
+
+    #[test]
+    fn test_stripify_detailed() {
+        // two triangles that don't share any vertices
+        let disconnected = [0, 1, 2, 3, 4, 5];
+        let result = stripify_detailed(&disconnected, 6, StripRestart::Index(u32::MAX)).unwrap();
+        assert!(result.uses_restart);
+        assert_eq!(result.strip_count, 2);
+        let result = stripify_detailed(&disconnected, 6, StripRestart::None).unwrap();
+        assert!(!result.uses_restart);
+        assert_eq!(result.strip_count, 2);
+
+        let fan: Vec<u32> = (1..7).flat_map(|i| [0, i, i + 1]).collect();
+        let result = stripify_detailed(&fan, 8, StripRestart::None).unwrap();
+        assert!(!result.uses_restart);
+        assert_eq!(
+            result.indices,
+            stripify(&fan, 8, StripRestart::None).unwrap()
+        );
+        assert_eq!(
+            sorted_triangles(&unstripify(&result.indices, StripRestart::None).unwrap()),
+            sorted_triangles(&fan)
+        );
+    }
+
+    #[test]
+    fn test_strip_restart_collision() {
+        let indices = grid_indices(4);
+        assert!(stripify(&indices, 25, StripRestart::Index(24)).is_err());
+        assert!(stripify(&indices, 25, StripRestart::Index(25)).is_ok());
+        assert!(stripify(&indices, 25, StripRestart::Index(0xffff)).is_ok());
+        assert!(stripify(&indices, 25, StripRestart::Index(0)).is_ok());
+    }
+
+    #[test]
+    fn test_strip_restart_modes() {
+        let indices = grid_indices(6);
+        let vertex_count = 49;
+
+        let degenerate = stripify(&indices, vertex_count, StripRestart::None).unwrap();
+        assert!(!degenerate.contains(&u32::MAX));
+        let restart = stripify(&indices, vertex_count, StripRestart::Index(u32::MAX)).unwrap();
+        assert!(restart.contains(&u32::MAX));
+
+        for (strip, mode) in [
+            (&degenerate, StripRestart::None),
+            (&restart, StripRestart::Index(u32::MAX)),
+        ] {
+            let list = unstripify(strip, mode).unwrap();
+            assert_eq!(sorted_triangles(&list), sorted_triangles(&indices));
+        }
+
+        // stitching triangles don't survive the round trip
+        let list = unstripify(&degenerate, StripRestart::None).unwrap();
+        assert_eq!(list.len(), indices.len());
+        assert!(list
+            .chunks_exact(3)
+            .all(|t| t[0] != t[1] && t[1] != t[2] && t[2] != t[0]));
+
+        // 0 is meshoptimizer's "no restart" sentinel, not a restart at vertex 0
+        assert_eq!(
+            stripify(&indices, vertex_count, StripRestart::Index(0)).unwrap(),
+            degenerate
+        );
+        assert_eq!(
+            unstripify(&degenerate, StripRestart::Index(0)).unwrap(),
+            unstripify(&degenerate, StripRestart::None).unwrap()
+        );
+    }
+}
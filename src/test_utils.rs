@@ -0,0 +1,38 @@
+//! Mesh fixtures and triangle comparisons shared by the unit tests and examples.
+
+use alloc::vec::Vec;
+
+/// Index buffer of a `size` x `size` grid of quads, two triangles each, over the
+/// `(size + 1) * (size + 1)` vertices of `grid_positions` in row-major order.
+pub(crate) fn grid_indices(size: u32) -> Vec<u32> {
+    let mut indices = Vec::new();
+    for y in 0..size {
+        for x in 0..size {
+            let i = y * (size + 1) + x;
+            let j = i + size + 1;
+            indices.extend_from_slice(&[i, i + 1, j, i + 1, j + 1, j]);
+        }
+    }
+    indices
+}
+
+/// Vertex positions of the grid indexed by `grid_indices`, one unit apart in the z = 0 plane.
+pub(crate) fn grid_positions(size: u32) -> Vec<[f32; 3]> {
+    (0..=size)
+        .flat_map(|y| (0..=size).map(move |x| [x as f32, y as f32, 0.0]))
+        .collect()
+}
+
+/// Rotates a triangle so that its smallest index comes first, keeping its winding.
+pub(crate) fn rotate_triangle<T: Copy + Ord>(t: &[T]) -> [T; 3] {
+    let first = (0..3).min_by_key(|&i| t[i]).unwrap();
+    [t[first], t[(first + 1) % 3], t[(first + 2) % 3]]
+}
+
+/// The triangles of an index buffer, rotated with `rotate_triangle` and sorted, for comparing
+/// index buffers that may reorder and rotate triangles but must keep their winding.
+pub(crate) fn sorted_triangles(indices: &[u32]) -> Vec<[u32; 3]> {
+    let mut triangles: Vec<[u32; 3]> = indices.chunks_exact(3).map(rotate_triangle).collect();
+    triangles.sort_unstable();
+    triangles
+}
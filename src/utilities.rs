@@ -1,14 +1,21 @@
 use crate::{Error, Result};
-use std::io::{Cursor, Read};
+use alloc::{format, vec::Vec};
+#[cfg(feature = "std")]
+use std::io::Read;
+
+/// The cursor type of `VertexDataAdapter::reader`, which is `std::io::Cursor` with the `std`
+/// feature so that the name is available in every configuration.
+#[cfg(feature = "std")]
+pub use std::io::Cursor;
 
 #[inline(always)]
 pub fn any_as_u8_slice<T: Sized>(p: &T) -> &[u8] {
-    typed_to_bytes(std::slice::from_ref(p))
+    typed_to_bytes(core::slice::from_ref(p))
 }
 
 #[inline(always)]
 pub fn typed_to_bytes<T: Sized>(typed: &[T]) -> &[u8] {
-    unsafe { std::slice::from_raw_parts(typed.as_ptr().cast(), std::mem::size_of_val(typed)) }
+    unsafe { core::slice::from_raw_parts(typed.as_ptr().cast(), core::mem::size_of_val(typed)) }
 }
 
 pub fn convert_indices_32_to_16(indices: &[u32]) -> Result<Vec<u16>> {
@@ -38,8 +45,11 @@ pub fn convert_indices_16_to_32(indices: &[u16]) -> Result<Vec<u32>> {
 /// fixed-function normalized fixed point conversion.
 ///
 /// Maximum reconstruction error: 1/2^(N+1).
+///
+/// Native port of the inline C++ `meshopt_quantizeUnorm`, so it can be used in const contexts.
+/// NaN quantizes to zero.
 #[inline(always)]
-pub fn quantize_unorm(v: f32, n: i32) -> i32 {
+pub const fn quantize_unorm(v: f32, n: i32) -> i32 {
     let scale = ((1i32 << n) - 1i32) as f32;
     let v = if v >= 0f32 { v } else { 0f32 };
     let v = if v <= 1f32 { v } else { 1f32 };
@@ -52,8 +62,11 @@ pub fn quantize_unorm(v: f32, n: i32) -> i32 {
 /// fixed-function normalized fixed point conversion (except early OpenGL versions).
 ///
 /// Maximum reconstruction error: 1/2^N.
+///
+/// Native port of the inline C++ `meshopt_quantizeSnorm`, so it can be used in const contexts.
+/// NaN quantizes to the negative extreme, like in C++.
 #[inline(always)]
-pub fn quantize_snorm(v: f32, n: u32) -> i32 {
+pub const fn quantize_snorm(v: f32, n: u32) -> i32 {
     let scale = ((1 << (n - 1)) - 1) as f32;
     let round = if v >= 0f32 { 0.5f32 } else { -0.5f32 };
     let v = if v >= -1f32 { v } else { -1f32 };
@@ -71,8 +84,12 @@ union FloatUInt {
 /// Generates +-inf for overflow, preserves NaN, flushes denormals to zero, rounds to nearest.
 /// Representable magnitude range: [6e-5; 65504].
 /// Maximum relative reconstruction error: 5e-4.
+///
+/// Matches `meshopt_quantizeHalf` bit for bit, so the result is what the vertex codec filters
+/// and gltfpack produce. Like the C version, ties round away from zero rather than to even,
+/// and every NaN becomes the same quiet NaN.
 #[inline(always)]
-pub fn quantize_half(v: f32) -> u16 {
+pub const fn quantize_half(v: f32) -> u16 {
     let u = FloatUInt { fl: v };
     let ui = unsafe { u.ui };
     let s = ((ui >> 16) & 0x8000) as i32;
@@ -93,11 +110,64 @@ pub fn quantize_half(v: f32) -> u16 {
     (s | h) as u16
 }
 
+/// Reverse quantization of a half-precision floating point value.
+/// Preserves Inf/NaN, flushes denormals to zero.
+///
+/// Matches `meshopt_dequantizeHalf` bit for bit, including NaN payloads.
+#[inline(always)]
+pub const fn dequantize_half(h: u16) -> f32 {
+    let s = ((h & 0x8000) as u32) << 16;
+    let em = (h & 0x7fff) as i32;
+
+    // bias exponent and pad mantissa with 0; 112 is relative exponent bias (127-15)
+    let mut r = (em + (112 << 10)) << 13;
+
+    // denormal: flush to zero
+    r = if em < (1 << 10) { 0 } else { r };
+
+    // infinity/NaN; note that we preserve NaN payload as a byproduct of unifying inf/nan cases
+    // 112 is an exponent bias fixup; since we already applied it once, applying it twice converts 31 to 255
+    r += if em >= (31 << 10) { 112 << 23 } else { 0 };
+
+    let u = FloatUInt { ui: s | r as u32 };
+    unsafe { u.fl }
+}
+
+/// Quantizes a flat array of `x, y, z` positions into packed half-precision floats,
+/// e.g. for a 6-byte position stream (or 8 bytes with padding added by the caller).
+///
+/// # Panics
+///
+/// Panics if the length of `positions` is not a multiple of 3.
+pub fn quantize_positions_half(positions: &[f32]) -> Vec<u16> {
+    assert!(
+        positions.len().is_multiple_of(3),
+        "positions length ({}) must be a multiple of 3",
+        positions.len()
+    );
+    positions.iter().map(|&v| quantize_half(v)).collect()
+}
+
+/// Reverses `quantize_positions_half`, converting packed half-precision `x, y, z`
+/// positions back into floats.
+///
+/// # Panics
+///
+/// Panics if the length of `halfs` is not a multiple of 3.
+pub fn dequantize_positions_half(halfs: &[u16]) -> Vec<f32> {
+    assert!(
+        halfs.len().is_multiple_of(3),
+        "halfs length ({}) must be a multiple of 3",
+        halfs.len()
+    );
+    halfs.iter().map(|&h| dequantize_half(h)).collect()
+}
+
 /// Quantize a float into a floating point value with a limited number of significant mantissa bits.
 /// Generates +-inf for overflow, preserves NaN, flushes denormals to zero, rounds to nearest.
 /// Assumes N is in a valid mantissa precision range, which is 1..23
 #[inline(always)]
-pub fn quantize_float(v: f32, n: i32) -> f32 {
+pub const fn quantize_float(v: f32, n: i32) -> f32 {
     let mut u = FloatUInt { fl: v };
     let mut ui = unsafe { u.ui };
 
@@ -118,15 +188,62 @@ pub fn quantize_float(v: f32, n: i32) -> f32 {
     unsafe { u.fl }
 }
 
+/// Returns the reciprocal of `v`, or zero if `v` is zero.
 #[inline(always)]
 pub fn rcp_safe(v: f32) -> f32 {
-    if v.abs() as u32 == 0 {
+    if v == 0f32 {
         0f32
     } else {
         1f32 / v
     }
 }
 
+/// Cross product of the triangle edges `p0p1` and `p0p2`: its length is twice the area of the
+/// triangle, and it is exactly zero for zero area triangles.
+pub(crate) fn triangle_cross(p0: [f32; 3], p1: [f32; 3], p2: [f32; 3]) -> [f32; 3] {
+    let e1 = [p1[0] - p0[0], p1[1] - p0[1], p1[2] - p0[2]];
+    let e2 = [p2[0] - p0[0], p2[1] - p0[1], p2[2] - p0[2]];
+    [
+        e1[1] * e2[2] - e1[2] * e2[1],
+        e1[2] * e2[0] - e1[0] * e2[2],
+        e1[0] * e2[1] - e1[1] * e2[0],
+    ]
+}
+
+/// Minimal stand-in for `std::io::Cursor` when building without the `std` feature.
+///
+/// It provides the subset of the `std::io::Cursor` API that doesn't need `std`, so code using
+/// it keeps compiling when the `std` feature is enabled and `Cursor` becomes `std::io::Cursor`.
+#[cfg(not(feature = "std"))]
+#[derive(Debug, Clone, Default)]
+pub struct Cursor<T> {
+    inner: T,
+    pos: u64,
+}
+
+#[cfg(not(feature = "std"))]
+impl<T> Cursor<T> {
+    pub fn new(inner: T) -> Cursor<T> {
+        Cursor { inner, pos: 0 }
+    }
+
+    pub fn into_inner(self) -> T {
+        self.inner
+    }
+
+    pub fn get_ref(&self) -> &T {
+        &self.inner
+    }
+
+    pub fn position(&self) -> u64 {
+        self.pos
+    }
+
+    pub fn set_position(&mut self, pos: u64) {
+        self.pos = pos;
+    }
+}
+
 pub struct VertexDataAdapter<'a> {
     pub reader: Cursor<&'a [u8]>,
     pub vertex_count: usize,
@@ -141,7 +258,7 @@ impl<'a> VertexDataAdapter<'a> {
         position_offset: usize,
     ) -> Result<VertexDataAdapter<'a>> {
         let vertex_count = data.len() / vertex_stride;
-        if data.len() % vertex_stride != 0 {
+        if !data.len().is_multiple_of(vertex_stride) {
             Err(Error::memory_dynamic(format!(
                 "vertex data length ({}) must be evenly divisible by vertex_stride ({})",
                 data.len(),
@@ -162,23 +279,28 @@ impl<'a> VertexDataAdapter<'a> {
         }
     }
 
-    pub fn xyz_f32_at(&mut self, vertex: usize) -> Result<[f32; 3]> {
+    pub fn xyz_f32_at(&self, vertex: usize) -> Result<[f32; 3]> {
         if vertex >= self.vertex_count {
             return Err(Error::memory_dynamic(format!(
                 "vertex index ({}) must be less than total vertex count ({})",
                 vertex, self.vertex_count
             )));
         }
-        let reader_pos = self.reader.position();
-        let vertex_offset = vertex * self.vertex_stride;
-        self.reader
-            .set_position((vertex_offset + self.position_offset) as u64);
-        let mut scratch = [0u8; 12];
-        self.reader.read_exact(&mut scratch)?;
+        let offset = vertex * self.vertex_stride + self.position_offset;
+        let scratch: [u8; 12] = self
+            .reader
+            .get_ref()
+            .get(offset..offset + 12)
+            .and_then(|bytes| bytes.try_into().ok())
+            .ok_or_else(|| {
+                Error::memory_dynamic(format!(
+                    "position of vertex ({}) must be within the vertex data",
+                    vertex
+                ))
+            })?;
+
+        let position: [f32; 3] = unsafe { core::mem::transmute(scratch) };
 
-        let position: [f32; 3] = unsafe { std::mem::transmute(scratch) };
-
-        self.reader.set_position(reader_pos);
         Ok(position)
     }
 
@@ -190,6 +312,7 @@ impl<'a> VertexDataAdapter<'a> {
     }
 }
 
+#[cfg(feature = "std")]
 impl<'a> Read for VertexDataAdapter<'a> {
     fn read(&mut self, buf: &mut [u8]) -> std::result::Result<usize, std::io::Error> {
         self.reader.read(buf)
@@ -198,6 +321,7 @@ impl<'a> Read for VertexDataAdapter<'a> {
 
 #[cfg(test)]
 mod tests {
+    use super::*;
     use crate::{typed_to_bytes, Vertex, VertexDataAdapter};
     use memoffset::offset_of;
 
@@ -216,9 +340,9 @@ mod tests {
             },
         ];
 
-        let mut adapter = VertexDataAdapter::new(
-            typed_to_bytes(&*vertices),
-            std::mem::size_of::<Vertex>(),
+        let adapter = VertexDataAdapter::new(
+            typed_to_bytes(&vertices),
+            core::mem::size_of::<Vertex>(),
             offset_of!(Vertex, p),
         )
         .unwrap();
@@ -230,4 +354,156 @@ mod tests {
 
         adapter.xyz_f32_at(2).expect_err("should fail");
     }
+
+    #[test]
+    fn test_cursor_api() {
+        // the subset shared by `std::io::Cursor` and the no_std stand-in
+        let data = [1u8, 2, 3];
+        let mut cursor = crate::Cursor::new(&data[..]);
+        assert_eq!(cursor.position(), 0);
+        cursor.set_position(2);
+        assert_eq!(cursor.position(), 2);
+        assert_eq!(cursor.get_ref(), &data);
+        assert_eq!(cursor.into_inner(), &data);
+    }
+
+    #[test]
+    fn test_rcp_safe() {
+        assert_eq!(rcp_safe(0.0), 0.0);
+        assert_eq!(rcp_safe(0.5), 2.0);
+        assert_eq!(rcp_safe(-4.0), -0.25);
+    }
+
+    #[test]
+    fn test_dequantize_half() {
+        for v in [
+            0.0f32,
+            -0.0,
+            1.0,
+            -2.5,
+            65504.0,
+            f32::INFINITY,
+            f32::NEG_INFINITY,
+        ] {
+            assert_eq!(dequantize_half(quantize_half(v)).to_bits(), v.to_bits());
+        }
+        assert!(dequantize_half(quantize_half(f32::NAN)).is_nan());
+        // denormals flush to zero
+        assert_eq!(dequantize_half(1), 0.0);
+        for h in [0x3c00u16, 0xc000, 0x7bff, 0x0400] {
+            assert_eq!(dequantize_half(h), unsafe {
+                crate::ffi::meshopt_dequantizeHalf(h)
+            });
+        }
+    }
+
+    fn quantization_sweep() -> Vec<f32> {
+        let mut values: Vec<f32> = (-2500..=2500).map(|i| i as f32 * 0.001).collect();
+        values.extend((0..200).map(|i| f32::from_bits(i * 0x0123_4567)));
+        values.extend([
+            f32::INFINITY,
+            f32::NEG_INFINITY,
+            f32::NAN,
+            -f32::NAN,
+            f32::MAX,
+            f32::MIN,
+            f32::MIN_POSITIVE,
+            65504.0,
+            65520.0,
+            -0.0,
+        ]);
+        values
+    }
+
+    #[test]
+    fn test_quantize_matches_native() {
+        for v in quantization_sweep() {
+            assert_eq!(quantize_half(v), unsafe {
+                crate::ffi::meshopt_quantizeHalf(v)
+            });
+            for n in 1..=23 {
+                let native = unsafe { crate::ffi::meshopt_quantizeFloat(v, n) };
+                assert_eq!(
+                    quantize_float(v, n).to_bits(),
+                    native.to_bits(),
+                    "{} {}",
+                    v,
+                    n
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_half_matches_native_bit_patterns() {
+        for h in 0..=u16::MAX {
+            let native = unsafe { crate::ffi::meshopt_dequantizeHalf(h) };
+            assert_eq!(dequantize_half(h).to_bits(), native.to_bits(), "{:#06x}", h);
+        }
+        // a prime step covers every exponent and a spread of mantissas and signs
+        for bits in (0..=u32::MAX).step_by(4099) {
+            let v = f32::from_bits(bits);
+            let native = unsafe { crate::ffi::meshopt_quantizeHalf(v) };
+            assert_eq!(quantize_half(v), native, "{:#010x}", bits);
+        }
+        // ties round away from zero, like the C version
+        assert_eq!(quantize_half(1.0 + 1.0 / 2048.0), 0x3c01);
+        assert_eq!(quantize_half(-(1.0 + 1.0 / 2048.0)), 0xbc01);
+    }
+
+    #[test]
+    fn test_quantize_normalized_clamping() {
+        // meshopt_quantizeUnorm/meshopt_quantizeSnorm are inline C++, so there is nothing to
+        // link against; check the clamping and rounding rules they implement instead
+        for v in quantization_sweep() {
+            for n in 2..=16 {
+                let unorm = quantize_unorm(v, n);
+                let snorm = quantize_snorm(v, n as u32);
+                assert!((0..(1 << n)).contains(&unorm));
+                assert!((-(1 << (n - 1)) + 1..(1 << (n - 1))).contains(&snorm));
+                if (0.0..=1.0).contains(&v) {
+                    let scale = ((1 << n) - 1) as f32;
+                    assert!((unorm as f32 - v * scale).abs() <= 0.5);
+                }
+            }
+        }
+        assert_eq!(quantize_unorm(f32::NAN, 8), 0);
+        assert_eq!(quantize_unorm(f32::INFINITY, 8), 255);
+        assert_eq!(quantize_unorm(f32::NEG_INFINITY, 8), 0);
+        assert_eq!(quantize_unorm(0.5, 8), 128);
+        assert_eq!(quantize_snorm(f32::NAN, 8), -127);
+        assert_eq!(quantize_snorm(f32::INFINITY, 8), 127);
+        assert_eq!(quantize_snorm(f32::NEG_INFINITY, 8), -127);
+        assert_eq!(quantize_snorm(-0.5, 8), -64);
+
+        const HALF: u16 = quantize_half(1.0);
+        const UNORM: i32 = quantize_unorm(1.0, 10);
+        const SNORM: i32 = quantize_snorm(-1.0, 8);
+        const FLOAT: f32 = quantize_float(1.0 / 3.0, 8);
+        assert_eq!(HALF, 0x3c00);
+        assert_eq!(UNORM, 1023);
+        assert_eq!(SNORM, -127);
+        assert_eq!(FLOAT, quantize_float(1.0 / 3.0, 8));
+    }
+
+    #[test]
+    fn test_positions_half_roundtrip() {
+        let positions: Vec<f32> = (0..300)
+            .map(|i| (i as f32 - 150.0) * 0.137 + 0.001 * (i % 7) as f32)
+            .collect();
+        let halfs = quantize_positions_half(&positions);
+        assert_eq!(halfs.len(), positions.len());
+        let decoded = dequantize_positions_half(&halfs);
+        assert_eq!(decoded.len(), positions.len());
+        for (&original, &decoded) in positions.iter().zip(&decoded) {
+            // half floats have 11 significant bits; values below 6e-5 flush to zero
+            let bound = (original.abs() * 5e-4).max(6.2e-5);
+            assert!(
+                (original - decoded).abs() <= bound,
+                "{} decoded as {}",
+                original,
+                decoded
+            );
+        }
+    }
 }